@@ -28,14 +28,17 @@ fn create_test_pools(count: usize) -> Vec<Box<dyn types::Pool>> {
                     1_000_000_000 + i as u64 * 100_000_000,
                     50_000_000_000 + i as u64 * 5_000_000_000,
                 )) as Box<dyn types::Pool>,
-                2 => Box::new(dex::OrcaPool::new_whirlpool(
-                    Pubkey::new_unique(),
-                    token_a,
-                    token_b,
-                    1_000_000_000 + i as u64 * 100_000_000,
-                    50_000_000_000 + i as u64 * 5_000_000_000,
-                    10,
-                )) as Box<dyn types::Pool>,
+                2 => Box::new(
+                    dex::OrcaPool::new_whirlpool(
+                        Pubkey::new_unique(),
+                        token_a,
+                        token_b,
+                        1_000_000_000 + i as u64 * 100_000_000,
+                        50_000_000_000 + i as u64 * 5_000_000_000,
+                        10,
+                    )
+                    .unwrap(),
+                ) as Box<dyn types::Pool>,
                 _ => Box::new(dex::MeteoraPool::new(
                     Pubkey::new_unique(),
                     token_a,
@@ -161,6 +164,59 @@ fn benchmark_multi_hop_routing(c: &mut Criterion) {
     group.finish();
 }
 
+fn benchmark_indexed_routing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("indexed_routing");
+
+    // Many pools spread over many pairs, so most pools don't match the query
+    let tokens: Vec<Pubkey> = (0..20).map(|_| Pubkey::new_unique()).collect();
+    let options = router::RouterOptions::default();
+    let amount = 1_000_000u64;
+
+    for pool_count in [100, 500] {
+        let pools: Vec<Box<dyn types::Pool>> = (0..pool_count)
+            .map(|i| {
+                Box::new(dex::RaydiumPool::new(
+                    Pubkey::new_unique(),
+                    tokens[i % tokens.len()],
+                    tokens[(i + 1 + i / tokens.len() % (tokens.len() - 1)) % tokens.len()],
+                    1_000_000_000 + i as u64 * 100_000_000,
+                    50_000_000_000 + i as u64 * 5_000_000_000,
+                )) as Box<dyn types::Pool>
+            })
+            .collect();
+        let index = router::PoolIndex::new(&pools);
+        let token_a = *pools[0].token_a();
+        let token_b = *pools[0].token_b();
+
+        group.bench_with_input(BenchmarkId::new("linear", pool_count), &pool_count, |b, _| {
+            b.iter(|| {
+                router::SinglePoolRouter::find_best_route_with_options(
+                    black_box(&pools),
+                    black_box(&token_a),
+                    black_box(&token_b),
+                    black_box(amount),
+                    &options,
+                )
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("indexed", pool_count), &pool_count, |b, _| {
+            b.iter(|| {
+                router::SinglePoolRouter::find_best_route_indexed(
+                    black_box(&pools),
+                    &index,
+                    black_box(&token_a),
+                    black_box(&token_b),
+                    black_box(amount),
+                    &options,
+                )
+            });
+        });
+    }
+
+    group.finish();
+}
+
 fn benchmark_calculator(c: &mut Criterion) {
     let mut group = c.benchmark_group("calculator");
 
@@ -219,6 +275,7 @@ criterion_group!(
     benchmark_single_pool_routing,
     benchmark_split_routing,
     benchmark_multi_hop_routing,
+    benchmark_indexed_routing,
     benchmark_calculator,
     benchmark_pool_operations
 );
@@ -37,14 +37,17 @@ fn main() {
             2_000_000_000_000,   // 2,000 SOL (better liquidity)
             100_000_000_000_000, // 100,000 USDC
         )),
-        Box::new(dex::OrcaPool::new_whirlpool(
-            Pubkey::new_unique(),
-            token_sol,
-            token_usdc,
-            1_500_000_000_000,   // 1,500 SOL
-            75_000_000_000_000,  // 75,000 USDC
-            10, // 0.1% fee (lower than others)
-        )),
+        Box::new(
+            dex::OrcaPool::new_whirlpool(
+                Pubkey::new_unique(),
+                token_sol,
+                token_usdc,
+                1_500_000_000_000,   // 1,500 SOL
+                75_000_000_000_000,  // 75,000 USDC
+                10, // 0.1% fee (lower than others)
+            )
+            .unwrap(),
+        ),
         Box::new(dex::MeteoraPool::new(
             Pubkey::new_unique(),
             token_sol,
@@ -58,7 +61,8 @@ fn main() {
     for (i, pool) in pools.iter().enumerate() {
         let reserve_sol = pool.reserve_a() as f64 / 1_000_000_000.0;
         let reserve_usdc = pool.reserve_b() as f64 / 1_000_000.0;
-        let price = reserve_usdc / reserve_sol;
+        // Raw USDC (6 decimals) per raw SOL (9 decimals), rescaled to whole tokens
+        let price = pool.mid_price(true) * 1_000.0;
         println!("Pool {}: {}", i + 1, pool.dex_name());
         println!("  ├─ Liquidity: {:.0} SOL / {:.0} USDC", reserve_sol, reserve_usdc);
         println!("  ├─ Price: {:.2} USDC per SOL", price);
@@ -67,7 +71,7 @@ fn main() {
 
     // Step 3: Test swap amount
     let amount_in = 10_000_000_000; // 10 SOL
-    println!("💱 STEP 3: Swapping {} SOL for USDC", amount_in as f64 / 1_000_000_000.0);
+    println!("💱 STEP 3: Swapping {} SOL for USDC", types::format_token_amount(amount_in, 9));
     println!("─────────────────────────────────────────────────────────────\n");
 
     // Step 4: Single Pool Routing
@@ -83,9 +87,9 @@ fn main() {
     for (i, pool) in pools.iter().enumerate() {
         match pool.calculate_output(amount_in, true) {
             Ok((output, price_impact)) => {
-                let output_usdc = output as f64 / 1_000_000.0;
-                println!("  Pool {} ({}): {:.2} USDC (impact: {:.2}%)",
-                    i + 1, pool.dex_name(), output_usdc, price_impact as f64 / 100.0);
+                println!("  Pool {} ({}): {} USDC (impact: {:.2}%)",
+                    i + 1, pool.dex_name(), types::format_token_amount(output, 6),
+                    price_impact as f64 / 100.0);
             }
             Err(e) => {
                 println!("  Pool {} ({}): ERROR - {}", i + 1, pool.dex_name(), e);
@@ -132,27 +136,30 @@ fn main() {
     println!("   Distribution:");
     for step in &split_quote.route.steps {
         let pct = (step.amount_in as f64 / amount_in as f64) * 100.0;
-        let sol = step.amount_in as f64 / 1_000_000_000.0;
-        let usdc = step.amount_out as f64 / 1_000_000.0;
-        println!("     • {}: {:.1}% ({:.2} SOL → {:.2} USDC)",
-            step.dex, pct, sol, usdc);
+        println!("     • {}: {:.1}% ({} SOL → {} USDC)",
+            step.dex, pct,
+            types::format_token_amount(step.amount_in, 9),
+            types::format_token_amount(step.amount_out, 6));
     }
     println!();
 
     // Step 6: Comparison
     println!("📊 STEP 6: Strategy Comparison");
     println!("─────────────────────────────────────────────────────────────");
-    let improvement = ((split_output - single_output) / single_output) * 100.0;
+    let decision = router::explain_split_decision(&pools, &token_sol, &token_usdc, amount_in)
+        .expect("Split comparison failed");
+    let improvement = decision.improvement_bps as f64 / 100.0;
     let usdc_gain = split_output - single_output;
 
     println!("Single Pool:  {:.2} USDC", single_output);
     println!("Split Route:  {:.2} USDC", split_output);
     println!("─────────────────────────────────────────");
-    if improvement > 0.0 {
+    if decision.improvement_bps > 0 {
         println!("Improvement:  +{:.2}% (+{:.2} USDC) ✅", improvement, usdc_gain);
     } else {
         println!("Difference:   {:.2}% ({:.2} USDC)", improvement, usdc_gain);
     }
+    println!("Recommended:  {} ({} pools)", decision.recommended.name(), decision.pools_used);
     println!();
 
     // Step 7: Show why split routing works
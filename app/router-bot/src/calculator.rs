@@ -1,7 +1,55 @@
 //! AMM calculation utilities using constant product formula (x * y = k)
+//! and the StableSwap invariant for correlated-asset pools
 
 use crate::error::{Result, RouterError};
 
+/// How the final division of a swap calculation is rounded
+///
+/// On-chain programs round in the pool's favour: Raydium AMM v4, the SPL
+/// token-swap program used by Orca's legacy pools, and Orca Whirlpools all
+/// round output amounts `Down` and required input amounts `Up`. `NearestEven`
+/// (banker's rounding) is not used by any supported DEX; it is provided for
+/// comparing against off-chain quoters that round to nearest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Truncate toward zero
+    Down,
+    /// Round up whenever there is a remainder
+    Up,
+    /// Round to the nearest integer, ties to even
+    NearestEven,
+}
+
+impl RoundingMode {
+    /// Divide `numerator` by a non-zero `denominator` using this rounding mode
+    fn divide(self, numerator: u128, denominator: u128) -> Result<u128> {
+        let quotient = numerator
+            .checked_div(denominator)
+            .ok_or(RouterError::MathOverflow)?;
+        self.round(quotient, numerator % denominator, denominator)
+    }
+
+    /// Round the truncated `quotient` of a division that left `remainder`
+    fn round(self, quotient: u128, remainder: u128, denominator: u128) -> Result<u128> {
+        let round_up = match self {
+            RoundingMode::Down => false,
+            RoundingMode::Up => remainder > 0,
+            // Compares `2 * remainder` with `denominator` without overflowing
+            RoundingMode::NearestEven => match remainder.cmp(&(denominator - remainder)) {
+                std::cmp::Ordering::Less => false,
+                std::cmp::Ordering::Greater => true,
+                std::cmp::Ordering::Equal => quotient % 2 == 1,
+            },
+        };
+
+        if round_up {
+            quotient.checked_add(1).ok_or(RouterError::MathOverflow)
+        } else {
+            Ok(quotient)
+        }
+    }
+}
+
 /// Calculate output amount using constant product formula
 /// Formula: (x + Δx * (1 - fee)) * (y - Δy) = x * y
 ///
@@ -18,6 +66,18 @@ pub fn calculate_amount_out(
     reserve_in: u64,
     reserve_out: u64,
     fee_bps: u16,
+) -> Result<u64> {
+    calculate_amount_out_rounded(amount_in, reserve_in, reserve_out, fee_bps, RoundingMode::Down)
+}
+
+/// Calculate output amount like [`calculate_amount_out`], rounding the final
+/// division with `rounding` instead of truncating
+pub fn calculate_amount_out_rounded(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_bps: u16,
+    rounding: RoundingMode,
 ) -> Result<u64> {
     if reserve_in == 0 || reserve_out == 0 {
         return Err(RouterError::InvalidReserves);
@@ -46,9 +106,7 @@ pub fn calculate_amount_out(
         .ok_or(RouterError::MathOverflow)?;
 
     // Calculate output amount
-    let amount_out = numerator
-        .checked_div(denominator)
-        .ok_or(RouterError::MathOverflow)?;
+    let amount_out = rounding.divide(numerator, denominator)?;
 
     // Check for overflow when converting back to u64
     amount_out
@@ -56,6 +114,93 @@ pub fn calculate_amount_out(
         .map_err(|_| RouterError::MathOverflow)
 }
 
+/// Calculate [`calculate_amount_out`] for many input amounts against the same
+/// reserves and fee
+///
+/// The fee factor `(10000 - fee_bps)` and the `reserve_in * 10000` term are
+/// computed once and reused for every amount. Stops at the first amount that
+/// overflows and reports its position as `RouterError::BatchMathOverflow`.
+pub fn calculate_amount_out_batch(
+    amounts: &[u64],
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_bps: u16,
+) -> Result<Vec<u64>> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(RouterError::InvalidReserves);
+    }
+
+    // Shared across every amount; neither can overflow a u128
+    let fee_factor = 10000 - fee_bps as u128;
+    let scaled_reserve_in = reserve_in as u128 * 10000;
+
+    amounts
+        .iter()
+        .enumerate()
+        .map(|(index, &amount_in)| {
+            let overflow = || RouterError::BatchMathOverflow { index };
+
+            let amount_in_with_fee = amount_in as u128 * fee_factor;
+            let numerator = amount_in_with_fee
+                .checked_mul(reserve_out as u128)
+                .ok_or_else(overflow)?;
+            let denominator = scaled_reserve_in
+                .checked_add(amount_in_with_fee)
+                .ok_or_else(overflow)?;
+
+            u64::try_from(numerator / denominator).map_err(|_| overflow())
+        })
+        .collect()
+}
+
+/// Calculate output amount for a pool whose fee is split between LPs and a
+/// protocol treasury
+///
+/// The trader pays `lp_fee_bps + protocol_fee_bps`, so the output matches
+/// [`calculate_amount_out`] with the combined fee. Only the LP portion stays
+/// in the pool: the protocol fee is withdrawn from the input, so after the
+/// swap the input reserve grows by `amount_in - protocol_fee` rather than the
+/// full `amount_in`. Simulating a later step against the same pool should use
+/// that smaller reserve.
+///
+/// # Returns
+/// `(amount_out, protocol_fee)`, the protocol fee in input token units
+/// (rounded down)
+pub fn calculate_amount_out_with_fee_split(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    lp_fee_bps: u16,
+    protocol_fee_bps: u16,
+) -> Result<(u64, u64)> {
+    let total_fee_bps = lp_fee_bps
+        .checked_add(protocol_fee_bps)
+        .filter(|&total| total <= 10000)
+        .ok_or_else(|| {
+            RouterError::ConfigError(format!(
+                "Invalid fee split: {} bps LP + {} bps protocol exceeds 10000",
+                lp_fee_bps, protocol_fee_bps
+            ))
+        })?;
+
+    let amount_out = calculate_amount_out(amount_in, reserve_in, reserve_out, total_fee_bps)?;
+    let protocol_fee = calculate_fee_amount(amount_in, protocol_fee_bps);
+
+    Ok((amount_out, protocol_fee))
+}
+
+/// Calculate the fee charged on an input amount, in input token units
+///
+/// # Arguments
+/// * `amount_in` - Input amount
+/// * `fee_bps` - Fee in basis points (e.g., 25 = 0.25%)
+///
+/// # Returns
+/// Fee amount (rounded down)
+pub fn calculate_fee_amount(amount_in: u64, fee_bps: u16) -> u64 {
+    (amount_in as u128 * fee_bps as u128 / 10000) as u64
+}
+
 /// Calculate price impact in basis points
 ///
 /// Price impact = (1 - (actual_price / spot_price)) * 10000
@@ -112,6 +257,62 @@ pub fn calculate_price_impact(
     Ok(impact)
 }
 
+/// Calculate price impact in basis points, excluding the fee
+///
+/// `calculate_price_impact` compares the post-fee output to the spot price, so
+/// a pool's fee shows up as impact even on an infinitesimal trade. This grosses
+/// `amount_out` back up by `fee_bps` first, leaving only the slippage caused by
+/// moving along the curve:
+///
+/// Price impact = (1 - (amount_out / (1 - fee)) / (amount_in * spot_price)) * 10000
+///
+/// # Arguments
+/// * `amount_in` - Input amount
+/// * `amount_out` - Output amount (after fees)
+/// * `reserve_in` - Reserve of input token
+/// * `reserve_out` - Reserve of output token
+/// * `fee_bps` - Fee in basis points charged on the input
+///
+/// # Returns
+/// Price impact in basis points
+pub fn calculate_price_impact_excluding_fee(
+    amount_in: u64,
+    amount_out: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_bps: u16,
+) -> Result<u16> {
+    if reserve_in == 0 || reserve_out == 0 || amount_in == 0 {
+        return Ok(0);
+    }
+
+    // Calculate: (1 - (amount_out * reserve_in * 10000) /
+    //                 (amount_in * (10000 - fee_bps) * reserve_out)) * 10000
+    let numerator = (amount_out as u128)
+        .checked_mul(reserve_in as u128)
+        .ok_or(RouterError::MathOverflow)?
+        .checked_mul(10000)
+        .ok_or(RouterError::MathOverflow)?;
+
+    let denominator = (amount_in as u128)
+        .checked_mul(10000u128.saturating_sub(fee_bps as u128))
+        .ok_or(RouterError::MathOverflow)?
+        .checked_mul(reserve_out as u128)
+        .ok_or(RouterError::MathOverflow)?;
+
+    if denominator == 0 {
+        return Ok(0);
+    }
+
+    let price_ratio = numerator
+        .checked_mul(10000)
+        .ok_or(RouterError::MathOverflow)?
+        .checked_div(denominator)
+        .ok_or(RouterError::MathOverflow)?;
+
+    Ok(10000u128.saturating_sub(price_ratio) as u16)
+}
+
 /// Calculate the input amount needed to get a specific output amount
 ///
 /// # Arguments
@@ -163,6 +364,248 @@ pub fn calculate_amount_in(
         .map_err(|_| RouterError::MathOverflow)
 }
 
+/// Calculate the input amount like [`calculate_amount_in`], rounding the final
+/// division with `rounding`
+///
+/// Unlike `calculate_amount_in`, which always adds 1 to the truncated quotient,
+/// `RoundingMode::Up` only rounds up when the division is inexact.
+pub fn calculate_amount_in_rounded(
+    amount_out: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_bps: u16,
+    rounding: RoundingMode,
+) -> Result<u64> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(RouterError::InvalidReserves);
+    }
+
+    if amount_out == 0 {
+        return Ok(0);
+    }
+
+    if amount_out >= reserve_out {
+        return Err(RouterError::InsufficientLiquidity);
+    }
+
+    // Numerator: reserve_in * amount_out * 10000
+    let numerator = (reserve_in as u128)
+        .checked_mul(amount_out as u128)
+        .ok_or(RouterError::MathOverflow)?
+        .checked_mul(10000)
+        .ok_or(RouterError::MathOverflow)?;
+
+    // Denominator: (reserve_out - amount_out) * (10000 - fee_bps)
+    let denominator = ((reserve_out - amount_out) as u128)
+        .checked_mul((10000 - fee_bps) as u128)
+        .ok_or(RouterError::MathOverflow)?;
+
+    rounding
+        .divide(numerator, denominator)?
+        .try_into()
+        .map_err(|_| RouterError::MathOverflow)
+}
+
+/// Compute `a * b / denominator` through a 256-bit product, rounded with `rounding`
+///
+/// For fixed-point math (e.g. Q64.64 sqrt prices), where the product of two
+/// `u128` values overflows. Fails with `RouterError::MathOverflow` if
+/// `denominator` is zero or the result doesn't fit in a `u128`.
+pub fn mul_div(a: u128, b: u128, denominator: u128, rounding: RoundingMode) -> Result<u128> {
+    if denominator == 0 {
+        return Err(RouterError::MathOverflow);
+    }
+    if let Some(product) = a.checked_mul(b) {
+        return rounding.divide(product, denominator);
+    }
+
+    let (high, low) = widening_mul(a, b);
+    if high >= denominator {
+        return Err(RouterError::MathOverflow);
+    }
+
+    // Long division of the 256-bit product, one bit of `low` at a time. The
+    // remainder stays below `denominator`; a bit shifted out of it means the
+    // shifted value exceeds `denominator`, and the subtraction wraps back.
+    let mut remainder = high;
+    let mut quotient = 0u128;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        if carry == 1 || remainder >= denominator {
+            remainder = remainder.wrapping_sub(denominator);
+            quotient |= 1 << bit;
+        }
+    }
+
+    rounding.round(quotient, remainder, denominator)
+}
+
+/// Full 256-bit product of `a` and `b` as `(high, low)` halves
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const LOW_64: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & LOW_64);
+    let (b_high, b_low) = (b >> 64, b & LOW_64);
+
+    let low_low = a_low * b_low;
+    let high_low = a_high * b_low;
+    let low_high = a_low * b_high;
+    let high_high = a_high * b_high;
+
+    // Bits 64..128: three values below 2^64, so the sum can't overflow
+    let middle = (low_low >> 64) + (high_low & LOW_64) + (low_high & LOW_64);
+    let low = (middle << 64) | (low_low & LOW_64);
+    let high = high_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64);
+    (high, low)
+}
+
+/// Maximum Newton iterations for the StableSwap invariant
+const STABLE_MAX_ITERATIONS: usize = 255;
+
+/// Compute the StableSwap invariant `D` for a two-token pool
+///
+/// Solves `A·n^n·(x + y) + D = A·n^n·D + D^(n+1) / (n^n·x·y)` for `n = 2`
+/// by Newton's method, as in Curve.
+fn compute_stable_d(reserve_x: u128, reserve_y: u128, amp: u128) -> Result<u128> {
+    let sum = reserve_x.checked_add(reserve_y).ok_or(RouterError::MathOverflow)?;
+    if sum == 0 {
+        return Ok(0);
+    }
+
+    let ann = amp.checked_mul(4).ok_or(RouterError::MathOverflow)?;
+    let mut d = sum;
+
+    for _ in 0..STABLE_MAX_ITERATIONS {
+        // d_p = D^3 / (4 * x * y)
+        let d_p = d
+            .checked_mul(d)
+            .ok_or(RouterError::MathOverflow)?
+            .checked_div(reserve_x.checked_mul(2).ok_or(RouterError::MathOverflow)?)
+            .ok_or(RouterError::MathOverflow)?
+            .checked_mul(d)
+            .ok_or(RouterError::MathOverflow)?
+            .checked_div(reserve_y.checked_mul(2).ok_or(RouterError::MathOverflow)?)
+            .ok_or(RouterError::MathOverflow)?;
+
+        let previous = d;
+
+        // D = (Ann * S + 2 * d_p) * D / ((Ann - 1) * D + 3 * d_p)
+        let numerator = ann
+            .checked_mul(sum)
+            .and_then(|v| v.checked_add(d_p.checked_mul(2)?))
+            .and_then(|v| v.checked_mul(d))
+            .ok_or(RouterError::MathOverflow)?;
+        let denominator = ann
+            .saturating_sub(1)
+            .checked_mul(d)
+            .and_then(|v| v.checked_add(d_p.checked_mul(3)?))
+            .ok_or(RouterError::MathOverflow)?;
+
+        d = numerator
+            .checked_div(denominator)
+            .ok_or(RouterError::MathOverflow)?;
+
+        if d.abs_diff(previous) <= 1 {
+            return Ok(d);
+        }
+    }
+
+    Ok(d)
+}
+
+/// Solve the StableSwap invariant for the new output reserve given the new input reserve
+fn compute_stable_y(new_reserve_x: u128, d: u128, amp: u128) -> Result<u128> {
+    let ann = amp.checked_mul(4).ok_or(RouterError::MathOverflow)?;
+
+    // c = D^3 / (4 * x * Ann), b = x + D / Ann
+    let c = d
+        .checked_mul(d)
+        .ok_or(RouterError::MathOverflow)?
+        .checked_div(new_reserve_x.checked_mul(2).ok_or(RouterError::MathOverflow)?)
+        .ok_or(RouterError::MathOverflow)?
+        .checked_mul(d)
+        .ok_or(RouterError::MathOverflow)?
+        .checked_div(ann.checked_mul(2).ok_or(RouterError::MathOverflow)?)
+        .ok_or(RouterError::MathOverflow)?;
+    let b = new_reserve_x
+        .checked_add(d / ann)
+        .ok_or(RouterError::MathOverflow)?;
+
+    let mut y = d;
+
+    for _ in 0..STABLE_MAX_ITERATIONS {
+        let previous = y;
+
+        // y = (y^2 + c) / (2y + b - D)
+        let numerator = y
+            .checked_mul(y)
+            .and_then(|v| v.checked_add(c))
+            .ok_or(RouterError::MathOverflow)?;
+        let denominator = y
+            .checked_mul(2)
+            .and_then(|v| v.checked_add(b))
+            .and_then(|v| v.checked_sub(d))
+            .ok_or(RouterError::MathOverflow)?;
+
+        y = numerator
+            .checked_div(denominator)
+            .ok_or(RouterError::MathOverflow)?;
+
+        if y.abs_diff(previous) <= 1 {
+            return Ok(y);
+        }
+    }
+
+    Ok(y)
+}
+
+/// Calculate output amount using the StableSwap (Curve) invariant
+///
+/// # Arguments
+/// * `amount_in` - Input amount
+/// * `reserve_in` - Reserve of input token
+/// * `reserve_out` - Reserve of output token
+/// * `amp` - Amplification coefficient (higher = flatter curve around balance)
+/// * `fee_bps` - Fee in basis points, taken from the input
+///
+/// # Returns
+/// Output amount after fees
+pub fn calculate_stable_amount_out(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    amp: u64,
+    fee_bps: u16,
+) -> Result<u64> {
+    if reserve_in == 0 || reserve_out == 0 || amp == 0 {
+        return Err(RouterError::InvalidReserves);
+    }
+
+    if amount_in == 0 {
+        return Ok(0);
+    }
+
+    let amount_in_after_fee = (amount_in as u128)
+        .checked_mul(10000 - fee_bps as u128)
+        .ok_or(RouterError::MathOverflow)?
+        / 10000;
+
+    let d = compute_stable_d(reserve_in as u128, reserve_out as u128, amp as u128)?;
+    let new_reserve_in = (reserve_in as u128)
+        .checked_add(amount_in_after_fee)
+        .ok_or(RouterError::MathOverflow)?;
+    let new_reserve_out = compute_stable_y(new_reserve_in, d, amp as u128)?;
+
+    // Subtract 1 to round in the pool's favor
+    let amount_out = (reserve_out as u128)
+        .saturating_sub(new_reserve_out)
+        .saturating_sub(1);
+
+    amount_out
+        .try_into()
+        .map_err(|_| RouterError::MathOverflow)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -199,6 +642,94 @@ mod tests {
         assert_eq!(amount_out, 90);
     }
 
+    #[test]
+    fn test_fee_split_matches_combined_fee_output() {
+        let (reserve_in, reserve_out) = (1_000_000_000_000, 50_000_000_000);
+        let amount_in = 1_000_000_000;
+
+        // Raydium-style 22 bps to LPs + 3 bps to the protocol
+        let (amount_out, protocol_fee) =
+            calculate_amount_out_with_fee_split(amount_in, reserve_in, reserve_out, 22, 3)
+                .unwrap();
+        let combined = calculate_amount_out(amount_in, reserve_in, reserve_out, 25).unwrap();
+        assert_eq!(amount_out, combined);
+        assert_eq!(protocol_fee, 300_000);
+        assert_eq!(protocol_fee, calculate_fee_amount(amount_in, 3));
+
+        // No protocol share is the plain calculation
+        let (amount_out, protocol_fee) =
+            calculate_amount_out_with_fee_split(amount_in, reserve_in, reserve_out, 25, 0)
+                .unwrap();
+        let combined = calculate_amount_out(amount_in, reserve_in, reserve_out, 25).unwrap();
+        assert_eq!(amount_out, combined);
+        assert_eq!(protocol_fee, 0);
+    }
+
+    #[test]
+    fn test_fee_split_protocol_share_leaves_the_pool() {
+        let (reserve_in, reserve_out) = (1_000_000, 1_000_000);
+        let amount_in = 100_000;
+
+        let (first_out, protocol_fee) =
+            calculate_amount_out_with_fee_split(amount_in, reserve_in, reserve_out, 20, 10)
+                .unwrap();
+        assert_eq!(protocol_fee, 100);
+
+        // A second swap in the same direction prices against an input reserve
+        // net of the protocol fee, which is shallower than if the whole fee
+        // had stayed with LPs
+        let split_reserve_in = reserve_in + amount_in - protocol_fee;
+        let lp_only_reserve_in = reserve_in + amount_in;
+        let reserve_out = reserve_out - first_out;
+        let after_split =
+            calculate_amount_out(amount_in, split_reserve_in, reserve_out, 30).unwrap();
+        let after_lp_only =
+            calculate_amount_out(amount_in, lp_only_reserve_in, reserve_out, 30).unwrap();
+        assert!(after_split > after_lp_only);
+
+        // The combined fee may not exceed 100%
+        assert!(matches!(
+            calculate_amount_out_with_fee_split(amount_in, reserve_in, reserve_out, 9_000, 1_001),
+            Err(RouterError::ConfigError(_))
+        ));
+        assert!(calculate_amount_out_with_fee_split(1, 1, 1, u16::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn test_no_fee_swap_does_not_decrease_k() {
+        use crate::dex::RaydiumPool;
+        use crate::types::pool::Pool;
+        use solana_sdk::pubkey::Pubkey;
+
+        let mut pool = RaydiumPool::new_with_fee(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+            0,
+        )
+        .unwrap();
+
+        for (amount_in, a_to_b) in [(1_000_000, true), (123_456_789, false), (999, true)] {
+            let k_before = pool.constant_product_k();
+            let (reserve_a, reserve_b) = pool.reserves();
+            let (reserve_in, reserve_out) =
+                if a_to_b { (reserve_a, reserve_b) } else { (reserve_b, reserve_a) };
+
+            let amount_out = calculate_amount_out(amount_in, reserve_in, reserve_out, 0).unwrap();
+            let (reserve_in, reserve_out) = (reserve_in + amount_in, reserve_out - amount_out);
+            if a_to_b {
+                pool.update_reserves(reserve_in, reserve_out).unwrap();
+            } else {
+                pool.update_reserves(reserve_out, reserve_in).unwrap();
+            }
+
+            // Rounding the output down can only leave k higher
+            assert!(pool.constant_product_k() >= k_before);
+        }
+    }
+
     #[test]
     fn test_calculate_amount_out_zero_input() {
         let result = calculate_amount_out(0, 1000, 1000, 25).unwrap();
@@ -214,6 +745,30 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_amount_out_batch_matches_single_calls() {
+        let amounts = [0, 1, 999, 1_000_000, 123_456_789, 1_000_000_000_000, u64::MAX / 10000];
+        let (reserve_in, reserve_out, fee_bps) = (1_000_000_000_000, 50_000_000_000, 25);
+
+        let batch = calculate_amount_out_batch(&amounts, reserve_in, reserve_out, fee_bps).unwrap();
+
+        assert_eq!(batch.len(), amounts.len());
+        for (amount_in, amount_out) in amounts.iter().zip(batch) {
+            let single = calculate_amount_out(*amount_in, reserve_in, reserve_out, fee_bps);
+            assert_eq!(amount_out, single.unwrap());
+        }
+    }
+
+    #[test]
+    fn test_amount_out_batch_reports_overflow_index() {
+        let amounts = [1_000, u64::MAX, u64::MAX];
+        let result = calculate_amount_out_batch(&amounts, u64::MAX, u64::MAX, 0);
+        assert!(matches!(result, Err(RouterError::BatchMathOverflow { index: 1 })));
+
+        let result = calculate_amount_out_batch(&amounts, 0, 1_000, 25);
+        assert!(matches!(result, Err(RouterError::InvalidReserves)));
+    }
+
     #[test]
     fn test_price_impact_calculation() {
         // Small swap should have minimal impact
@@ -244,6 +799,56 @@ mod tests {
         assert!(impact > 100);
     }
 
+    #[test]
+    fn test_price_impact_excluding_fee_on_tiny_trade() {
+        let reserve_in = 1_000_000_000_000;
+        let reserve_out = 50_000_000_000_000;
+        let fee_bps = 25;
+        let amount_in = 1_000_000;
+
+        let amount_out = calculate_amount_out(amount_in, reserve_in, reserve_out, fee_bps).unwrap();
+
+        // The old measure reports roughly the fee as impact
+        let with_fee =
+            calculate_price_impact(amount_in, amount_out, reserve_in, reserve_out).unwrap();
+        assert!((25..=26).contains(&with_fee));
+
+        // Excluding the fee, a trade of 0.0001% of the pool has ~no impact
+        let excluding_fee = calculate_price_impact_excluding_fee(
+            amount_in,
+            amount_out,
+            reserve_in,
+            reserve_out,
+            fee_bps,
+        )
+        .unwrap();
+        assert!(excluding_fee <= 1);
+    }
+
+    #[test]
+    fn test_price_impact_excluding_fee_large_swap() {
+        let reserve_in = 1_000_000;
+        let reserve_out = 1_000_000;
+        let fee_bps = 30;
+        let amount_in = 100_000; // 10% of the pool
+
+        let amount_out = calculate_amount_out(amount_in, reserve_in, reserve_out, fee_bps).unwrap();
+        let with_fee =
+            calculate_price_impact(amount_in, amount_out, reserve_in, reserve_out).unwrap();
+        let excluding_fee = calculate_price_impact_excluding_fee(
+            amount_in,
+            amount_out,
+            reserve_in,
+            reserve_out,
+            fee_bps,
+        )
+        .unwrap();
+
+        // Curve impact remains and the fee share is removed
+        assert!(excluding_fee > 800);
+        assert!(with_fee - excluding_fee >= 25 && with_fee - excluding_fee <= 30);
+    }
+
     #[test]
     fn test_calculate_amount_in() {
         let reserve_in = 1_000_000;
@@ -274,6 +879,68 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_rounding_up_and_down_differ_by_one() {
+        // 100 * 7 * 10000 / (1000 * 10000 + 100 * 10000) = 0.636..., not an integer
+        let down = calculate_amount_out_rounded(100, 1_000, 7, 0, RoundingMode::Down).unwrap();
+        let up = calculate_amount_out_rounded(100, 1_000, 7, 0, RoundingMode::Up).unwrap();
+        assert_eq!(down, 0);
+        assert_eq!(up, down + 1);
+        assert_eq!(down, calculate_amount_out(100, 1_000, 7, 0).unwrap());
+
+        // 1_000 * 100 / (50_000 - 100) = 2.004..., not an integer
+        let down = calculate_amount_in_rounded(100, 1_000, 50_000, 0, RoundingMode::Down).unwrap();
+        let up = calculate_amount_in_rounded(100, 1_000, 50_000, 0, RoundingMode::Up).unwrap();
+        assert_eq!(down, 2);
+        assert_eq!(up, down + 1);
+        assert_eq!(up, calculate_amount_in(100, 1_000, 50_000, 0).unwrap());
+    }
+
+    #[test]
+    fn test_rounding_exact_and_nearest_even() {
+        // 1_000 * 500 / (1_000 - 500) = 1_000 exactly
+        let down = calculate_amount_in_rounded(500, 1_000, 1_000, 0, RoundingMode::Down).unwrap();
+        let up = calculate_amount_in_rounded(500, 1_000, 1_000, 0, RoundingMode::Up).unwrap();
+        assert_eq!(down, 1_000);
+        assert_eq!(up, 1_000);
+
+        assert_eq!(RoundingMode::NearestEven.divide(5, 2).unwrap(), 2);
+        assert_eq!(RoundingMode::NearestEven.divide(7, 2).unwrap(), 4);
+        assert_eq!(RoundingMode::NearestEven.divide(8, 3).unwrap(), 3);
+        assert_eq!(RoundingMode::NearestEven.divide(7, 3).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_mul_div_beyond_u128_products() {
+        // Products that fit take the plain path
+        assert_eq!(mul_div(6, 7, 4, RoundingMode::Down).unwrap(), 10);
+        assert_eq!(mul_div(6, 7, 4, RoundingMode::Up).unwrap(), 11);
+
+        // 2^100 * 2^100 / 2^90 = 2^110
+        let result = mul_div(1 << 100, 1 << 100, 1 << 90, RoundingMode::Down).unwrap();
+        assert_eq!(result, 1 << 110);
+        assert_eq!(
+            mul_div(u128::MAX, u128::MAX, u128::MAX, RoundingMode::Up).unwrap(),
+            u128::MAX
+        );
+
+        // (2^127 + 1) * 3 / 2 = 3 * 2^126 + 1.5
+        let a = (1u128 << 127) + 1;
+        let expected_floor = 3 * (1u128 << 126) + 1;
+        assert_eq!(mul_div(a, 3, 2, RoundingMode::Down).unwrap(), expected_floor);
+        assert_eq!(mul_div(a, 3, 2, RoundingMode::Up).unwrap(), expected_floor + 1);
+        assert_eq!(mul_div(a, 3, 2, RoundingMode::NearestEven).unwrap(), expected_floor + 1);
+
+        assert!(matches!(
+            mul_div(u128::MAX, 2, 1, RoundingMode::Down),
+            Err(RouterError::MathOverflow)
+        ));
+        assert!(matches!(
+            mul_div(1, 1, 0, RoundingMode::Down),
+            Err(RouterError::MathOverflow)
+        ));
+    }
+
     #[test]
     fn test_constant_product_property() {
         // Verify that x * y = k is maintained (approximately)
@@ -297,8 +964,43 @@ mod tests {
         assert!(diff < k_before / 1000); // Less than 0.1% difference
     }
 
+    #[test]
+    fn test_stable_swap_balanced_pool_near_one_to_one() {
+        let reserve = 1_000_000_000_000; // 1M USDC / 1M USDT
+        let amount_in = 10_000_000_000; // 1% of the pool
+
+        let stable_out = calculate_stable_amount_out(amount_in, reserve, reserve, 100, 0).unwrap();
+        let cp_out = calculate_amount_out(amount_in, reserve, reserve, 0).unwrap();
+
+        // Stable curve stays close to 1:1 while constant product loses ~1%
+        assert!(stable_out < amount_in);
+        assert!(stable_out > cp_out);
+        assert!((amount_in - stable_out) * 10 < amount_in - cp_out);
+    }
+
+    #[test]
+    fn test_stable_swap_zero_input_and_reserves() {
+        assert_eq!(calculate_stable_amount_out(0, 1000, 1000, 100, 4).unwrap(), 0);
+        assert!(calculate_stable_amount_out(100, 0, 1000, 100, 4).is_err());
+        assert!(calculate_stable_amount_out(100, 1000, 1000, 0, 4).is_err());
+    }
+
     // Property-based tests
     proptest! {
+        #[test]
+        fn prop_mul_div_undoes_multiplication(
+            x in any::<u64>(),
+            b in any::<u64>(),
+            c in 1u64..,
+        ) {
+            // (x * c) * b can reach 2^192, beyond u128; dividing by c leaves x * b
+            let a = x as u128 * c as u128;
+            let expected = x as u128 * b as u128;
+            for rounding in [RoundingMode::Down, RoundingMode::Up] {
+                prop_assert_eq!(mul_div(a, b as u128, c as u128, rounding).unwrap(), expected);
+            }
+        }
+
         #[test]
         fn prop_output_less_than_reserve(
             amount_in in 1u64..1_000_000,
@@ -1,28 +1,228 @@
 //! Solana RPC client wrapper
 
+use crate::dex::raydium::{
+    RaydiumAmmInfo, RaydiumPool, BASE_MINT_OFFSET, QUOTE_MINT_OFFSET, RAYDIUM_AMM_V4_ACCOUNT_SIZE,
+};
 use crate::error::{Result, RouterError};
-use solana_client::rpc_client::RpcClient;
+use log::{info, warn};
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::{
+    client_error::ClientErrorKind,
+    pubsub_client::PubsubClient,
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_commitment_config::CommitmentConfig;
 use solana_sdk::{
     account::Account,
     pubkey::Pubkey,
 };
 use spl_token::{
     solana_program::program_pack::Pack,
-    state::Mint,
+    state::{Account as TokenAccount, Mint},
 };
-use std::{str::FromStr, sync::Arc};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::mpsc::{self, Receiver, Sender};
+
+/// Maximum number of accounts per `getMultipleAccounts` request
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Maximum number of pools returned by a single discovery call
+pub const MAX_DISCOVERED_POOLS: usize = 20;
+
+/// Retries for transient RPC failures before giving up
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Delay before the first retry; doubled on every subsequent attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+
+/// Buffered account updates per subscription before the background task blocks
+const SUBSCRIPTION_BUFFER: usize = 64;
+
+/// Delay before the first reconnect attempt of a dropped subscription
+const SUBSCRIPTION_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Upper bound on the reconnect delay
+const SUBSCRIPTION_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// How often an idle subscription checks whether its receiver was dropped
+const SUBSCRIPTION_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The RPC calls `SolanaClient` can fail over between endpoints
+///
+/// Implemented by `RpcClient`; tests substitute their own transports.
+pub trait RpcTransport: Send + Sync {
+    /// Endpoint URL
+    fn url(&self) -> String;
+
+    /// Fetch an account, failing with `RouterError::AccountNotFound` if it
+    /// doesn't exist
+    fn get_account(&self, address: &Pubkey) -> Result<Account>;
+
+    /// Version of the node's software
+    fn get_version(&self) -> Result<String>;
+}
+
+impl RpcTransport for RpcClient {
+    fn url(&self) -> String {
+        RpcClient::url(self)
+    }
+
+    fn get_account(&self, address: &Pubkey) -> Result<Account> {
+        RpcClient::get_account_with_commitment(self, address, self.commitment())?
+            .value
+            .ok_or_else(|| RouterError::AccountNotFound(address.to_string()))
+    }
+
+    fn get_version(&self) -> Result<String> {
+        Ok(RpcClient::get_version(self)?.solana_core)
+    }
+}
+
+/// One RPC endpoint: the transport used for failover calls, and the full
+/// client used for everything else
+struct Endpoint {
+    url: String,
+    rpc: Arc<RpcClient>,
+    transport: Arc<dyn RpcTransport>,
+}
+
+/// Source of token mint accounts
+pub trait MintSource {
+    /// Fetch and parse a token mint account
+    fn fetch_mint(&self, mint_address: &Pubkey) -> Result<Mint>;
+}
+
+/// Shared cache of parsed mint accounts
+///
+/// Mint data (decimals, authorities) is effectively static during a run, so each
+/// mint only needs to be fetched once.
+#[derive(Clone, Default)]
+pub struct MintCache {
+    mints: Arc<Mutex<HashMap<Pubkey, Mint>>>,
+}
+
+impl MintCache {
+    /// Return the cached mint, fetching it from `source` on a miss
+    pub fn get_or_fetch(&self, source: &impl MintSource, mint_address: &Pubkey) -> Result<Mint> {
+        if let Some(mint) = self.mints.lock().unwrap().get(mint_address) {
+            return Ok(*mint);
+        }
+
+        // Fetch without holding the lock so slow RPC calls don't block other readers
+        let mint = source.fetch_mint(mint_address)?;
+        self.mints.lock().unwrap().insert(*mint_address, mint);
+        Ok(mint)
+    }
+
+    /// Drop every cached mint
+    pub fn clear(&self) {
+        self.mints.lock().unwrap().clear();
+    }
+
+    /// Number of cached mints
+    pub fn len(&self) -> usize {
+        self.mints.lock().unwrap().len()
+    }
+
+    /// Whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
 
 /// Wrapper around Solana RPC client with convenience methods
+///
+/// A client may have fallback endpoints: `fetch_account` and `get_version` move
+/// on to the next endpoint when one fails with an RPC error, and the endpoint
+/// that last succeeded stays active for subsequent calls.
 #[derive(Clone)]
 pub struct SolanaClient {
-    client: Arc<RpcClient>,
+    endpoints: Arc<Vec<Endpoint>>,
+    active: Arc<AtomicUsize>,
+    commitment: CommitmentConfig,
+    mint_cache: MintCache,
 }
 
 impl SolanaClient {
     /// Create a new Solana client
     pub fn new(rpc_url: String) -> Self {
+        Self::new_with_commitment(rpc_url, CommitmentConfig::default())
+    }
+
+    /// Create a new Solana client that uses `commitment` for all RPC calls
+    pub fn new_with_commitment(rpc_url: String, commitment: CommitmentConfig) -> Self {
+        Self::from_urls(vec![rpc_url], commitment)
+    }
+
+    /// Create a client that fails over to `fallbacks`, in order, when `primary`
+    /// returns RPC errors, using `commitment` on every endpoint
+    pub fn new_with_fallbacks(
+        primary: String,
+        fallbacks: Vec<String>,
+        commitment: CommitmentConfig,
+    ) -> Self {
+        let mut urls = vec![primary];
+        urls.extend(fallbacks);
+        Self::from_urls(urls, commitment)
+    }
+
+    fn from_urls(urls: Vec<String>, commitment: CommitmentConfig) -> Self {
+        let endpoints = urls
+            .into_iter()
+            .map(|url| {
+                let rpc = Arc::new(RpcClient::new_with_commitment(url.clone(), commitment));
+                Endpoint {
+                    url,
+                    transport: rpc.clone(),
+                    rpc,
+                }
+            })
+            .collect();
+
+        Self::from_endpoints(endpoints, commitment)
+    }
+
+    /// Create a client whose failover calls go through `transports`, in order
+    ///
+    /// Lets callers wrap or replace the RPC transport, e.g. to instrument it or
+    /// to exercise failover without a network.
+    pub fn from_transports(
+        transports: Vec<Arc<dyn RpcTransport>>,
+        commitment: CommitmentConfig,
+    ) -> Self {
+        let endpoints = transports
+            .into_iter()
+            .map(|transport| {
+                let url = transport.url();
+                Endpoint {
+                    rpc: Arc::new(RpcClient::new_with_commitment(url.clone(), commitment)),
+                    url,
+                    transport,
+                }
+            })
+            .collect();
+
+        Self::from_endpoints(endpoints, commitment)
+    }
+
+    fn from_endpoints(endpoints: Vec<Endpoint>, commitment: CommitmentConfig) -> Self {
+        assert!(!endpoints.is_empty(), "SolanaClient needs at least one endpoint");
         Self {
-            client: Arc::new(RpcClient::new(rpc_url)),
+            endpoints: Arc::new(endpoints),
+            active: Arc::new(AtomicUsize::new(0)),
+            commitment,
+            mint_cache: MintCache::default(),
         }
     }
 
@@ -36,16 +236,31 @@ impl SolanaClient {
         Self::new("https://api.mainnet-beta.solana.com".to_string())
     }
 
-    /// Get the underlying RPC client
+    /// Get the RPC client of the active endpoint
     pub fn rpc(&self) -> &RpcClient {
-        &self.client
+        &self.active_endpoint_ref().rpc
+    }
+
+    /// URL of the endpoint currently used for RPC calls
+    pub fn active_endpoint(&self) -> &str {
+        &self.active_endpoint_ref().url
+    }
+
+    fn active_endpoint_ref(&self) -> &Endpoint {
+        &self.endpoints[self.active.load(Ordering::Relaxed)]
+    }
+
+    /// Commitment level used for RPC calls
+    pub fn commitment(&self) -> CommitmentConfig {
+        self.commitment
     }
 
     /// Fetch account data
+    ///
+    /// Fails with `RouterError::AccountNotFound` only when the account doesn't
+    /// exist; RPC failures on every endpoint surface as `RouterError::RpcError`.
     pub fn fetch_account(&self, address: &Pubkey) -> Result<Account> {
-        self.client
-            .get_account(address)
-            .map_err(|_| RouterError::AccountNotFound(address.to_string()))
+        self.with_failover(|transport| transport.get_account(address))
     }
 
     /// Fetch account data from string address
@@ -76,28 +291,369 @@ impl SolanaClient {
         self.fetch_mint(&pubkey)
     }
 
+    /// Fetch a token mint, reusing a previously fetched copy when available
+    pub fn fetch_mint_cached(&self, mint_address: &Pubkey) -> Result<Mint> {
+        self.mint_cache.get_or_fetch(self, mint_address)
+    }
+
+    /// Forget all cached mints (e.g. periodically in long-running bots)
+    pub fn clear_mint_cache(&self) {
+        self.mint_cache.clear();
+    }
+
     /// Fetch multiple accounts in parallel
+    ///
+    /// Uses `getMultipleAccounts`, batching up to `MAX_MULTIPLE_ACCOUNTS` addresses
+    /// per request. Results are returned in the same order as `addresses`. Missing
+    /// accounts are `RouterError::AccountNotFound`; if a whole request fails, each
+    /// of its addresses gets a `RouterError::RpcError` carrying the RPC error.
     pub async fn fetch_accounts_parallel(&self, addresses: &[Pubkey]) -> Vec<Result<Account>> {
-        // In a real implementation, this would use get_multiple_accounts
-        // For now, we'll fetch sequentially but keep the async signature for future optimization
-        addresses
+        let mut results = Vec::with_capacity(addresses.len());
+
+        for chunk in addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            match self.rpc().get_multiple_accounts(chunk) {
+                Ok(accounts) => {
+                    for (addr, account) in chunk.iter().zip(accounts) {
+                        results.push(
+                            account.ok_or_else(|| RouterError::AccountNotFound(addr.to_string())),
+                        );
+                    }
+                }
+                Err(err) => {
+                    // `ClientError` isn't `Clone`, so each address gets a copy of its message
+                    let message = format!("getMultipleAccounts failed: {}", err);
+                    results.extend(chunk.iter().map(|_| {
+                        let kind = ClientErrorKind::Custom(message.clone());
+                        Err(RouterError::RpcError(kind.into()))
+                    }));
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Discover Raydium AMM v4 pools trading `token_a` against `token_b`
+    ///
+    /// `getProgramAccounts` over the whole Raydium program is expensive, so the
+    /// account size and both mints are always used as server-side filters (once
+    /// per base/quote orientation), and only addresses are downloaded. Account
+    /// data is then fetched for at most `MAX_DISCOVERED_POOLS` pools. Reserves
+    /// are read from each pool's vault token accounts; pools that fail to parse
+    /// or whose vaults cannot be fetched are skipped.
+    pub fn discover_raydium_pools(
+        &self,
+        token_a: Pubkey,
+        token_b: Pubkey,
+    ) -> Result<Vec<RaydiumPool>> {
+        if token_a == token_b {
+            return Err(RouterError::ConfigError(
+                "Pool discovery requires two distinct mints".to_string(),
+            ));
+        }
+
+        let program_id = RaydiumPool::program_id();
+        let mut pool_addresses = Vec::new();
+
+        for (base_mint, quote_mint) in [(token_a, token_b), (token_b, token_a)] {
+            let config = RpcProgramAccountsConfig {
+                filters: Some(raydium_pair_filters(&base_mint, &quote_mint)),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    // Addresses only; data is fetched below for the pools kept
+                    data_slice: Some(UiDataSliceConfig {
+                        offset: 0,
+                        length: 0,
+                    }),
+                    commitment: Some(self.commitment),
+                    ..RpcAccountInfoConfig::default()
+                },
+                ..RpcProgramAccountsConfig::default()
+            };
+
+            let accounts = self.with_retry(
+                || {
+                    self.rpc()
+                        .get_program_ui_accounts_with_config(&program_id, config.clone())
+                        .map_err(RouterError::from)
+                },
+                DEFAULT_MAX_RETRIES,
+            )?;
+            pool_addresses.extend(accounts.into_iter().map(|(address, _)| address));
+        }
+        pool_addresses.truncate(MAX_DISCOVERED_POOLS);
+
+        let pool_accounts = self.rpc().get_multiple_accounts(&pool_addresses)?;
+        let amms: Vec<(Pubkey, Account, RaydiumAmmInfo)> = pool_addresses
+            .iter()
+            .zip(pool_accounts)
+            .filter_map(|(address, account)| {
+                let Some(account) = account else {
+                    warn!("Skipping Raydium pool {}: account closed", address);
+                    return None;
+                };
+                match RaydiumAmmInfo::parse(&account.data) {
+                    Ok(amm) => Some((*address, account, amm)),
+                    Err(e) => {
+                        warn!("Skipping Raydium pool {}: {}", address, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        let vaults: Vec<Pubkey> = amms
             .iter()
-            .map(|addr| self.fetch_account(addr))
-            .collect()
+            .flat_map(|(_, _, amm)| [amm.base_vault, amm.quote_vault])
+            .collect();
+        let mut vault_balances = HashMap::new();
+        for chunk in vaults.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let accounts = self.rpc().get_multiple_accounts(chunk)?;
+            for (vault, account) in chunk.iter().zip(accounts) {
+                if let Some(token_account) =
+                    account.and_then(|a| TokenAccount::unpack(&a.data).ok())
+                {
+                    vault_balances.insert(*vault, token_account.amount);
+                }
+            }
+        }
+
+        let mut pools = Vec::with_capacity(amms.len());
+        for (address, account, amm) in &amms {
+            let (Some(&reserve_a), Some(&reserve_b)) = (
+                vault_balances.get(&amm.base_vault),
+                vault_balances.get(&amm.quote_vault),
+            ) else {
+                warn!("Skipping Raydium pool {}: vault balances unavailable", address);
+                continue;
+            };
+            match RaydiumPool::from_account_data(*address, &account.data, reserve_a, reserve_b) {
+                Ok(pool) => pools.push(pool),
+                Err(e) => warn!("Skipping Raydium pool {}: {}", address, e),
+            }
+        }
+
+        Ok(pools)
+    }
+
+    /// Subscribe to updates of an account over WebSocket
+    ///
+    /// The WebSocket endpoint is derived from the RPC URL (`https://` becomes
+    /// `wss://`, `http://` becomes `ws://`), so the RPC provider must serve both on
+    /// the same host. Updates are delivered on the returned channel by a background
+    /// thread, which reconnects with exponential backoff when the connection drops
+    /// and exits once the receiver is dropped.
+    pub fn subscribe_account(&self, address: Pubkey) -> Result<Receiver<Account>> {
+        let ws_url = websocket_url(&self.rpc().url())?;
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(self.commitment),
+            ..RpcAccountInfoConfig::default()
+        };
+        let (sender, receiver) = mpsc::channel(SUBSCRIPTION_BUFFER);
+
+        thread::Builder::new()
+            .name(format!("account-subscription-{}", address))
+            .spawn(move || run_account_subscription(&ws_url, &address, config, sender))
+            .map_err(|e| RouterError::Other(e.into()))?;
+
+        Ok(receiver)
     }
 
     /// Get network version (useful for testing connectivity)
     pub fn get_version(&self) -> Result<String> {
-        self.client
-            .get_version()
-            .map_err(|e| RouterError::RpcError(e))
-            .map(|v| format!("{}", v.solana_core))
+        self.with_failover(|transport| transport.get_version())
+    }
+
+    /// Run `f` against the active endpoint, then each following endpoint in
+    /// turn, until one succeeds
+    ///
+    /// Each endpoint gets the usual transient-error retries first. Only RPC
+    /// errors move on to the next endpoint; once every endpoint has failed the
+    /// last error is returned.
+    fn with_failover<T>(&self, f: impl Fn(&dyn RpcTransport) -> Result<T>) -> Result<T> {
+        let start = self.active.load(Ordering::Relaxed);
+        let mut last_error = None;
+
+        for offset in 0..self.endpoints.len() {
+            let idx = (start + offset) % self.endpoints.len();
+            let endpoint = &self.endpoints[idx];
+
+            match self.with_retry(|| f(endpoint.transport.as_ref()), DEFAULT_MAX_RETRIES) {
+                Err(e @ RouterError::RpcError(_)) => {
+                    warn!("RPC endpoint {} failed: {}", endpoint.url, e);
+                    last_error = Some(e);
+                }
+                result => {
+                    if idx != start {
+                        info!("🔀 Failed over to RPC endpoint {}", endpoint.url);
+                        self.active.store(idx, Ordering::Relaxed);
+                    }
+                    return result;
+                }
+            }
+        }
+
+        Err(last_error.expect("at least one endpoint was tried"))
+    }
+
+    /// Run `f`, retrying transient RPC failures with exponential backoff and jitter
+    ///
+    /// Errors that retrying cannot fix (bad input, parse failures, missing
+    /// accounts) are returned immediately.
+    fn with_retry<T>(&self, f: impl Fn() -> Result<T>, max_retries: u32) -> Result<T> {
+        let mut attempt = 0;
+
+        loop {
+            match f() {
+                Err(e) if attempt < max_retries && is_transient(&e) => {
+                    let delay = retry_delay(attempt);
+                    warn!(
+                        "Transient RPC error (attempt {}/{}), retrying in {:?}: {}",
+                        attempt + 1,
+                        max_retries,
+                        delay,
+                        e
+                    );
+                    thread::sleep(delay);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Whether an error is worth retrying (timeouts, rate limits, unavailable nodes)
+fn is_transient(err: &RouterError) -> bool {
+    let RouterError::RpcError(client_error) = err else {
+        return false;
+    };
+
+    match client_error.kind() {
+        ClientErrorKind::Io(_) => true,
+        ClientErrorKind::Reqwest(e) => {
+            e.is_timeout()
+                || e.is_connect()
+                || e
+                    .status()
+                    .is_some_and(|status| status.as_u16() == 429 || status.is_server_error())
+        }
+        _ => false,
+    }
+}
+
+/// Backoff before retry number `attempt` (0-based): base * 2^attempt plus up to 50% jitter
+fn retry_delay(attempt: u32) -> Duration {
+    let delay = RETRY_BASE_DELAY * 2u32.saturating_pow(attempt);
+    let jitter_range = delay.as_millis() as u64 / 2 + 1;
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64)
+        .unwrap_or(0);
+    delay + Duration::from_millis(seed % jitter_range)
+}
+
+/// `getProgramAccounts` filters matching Raydium AMM v4 pools for a base/quote pair
+fn raydium_pair_filters(base_mint: &Pubkey, quote_mint: &Pubkey) -> Vec<RpcFilterType> {
+    vec![
+        RpcFilterType::DataSize(RAYDIUM_AMM_V4_ACCOUNT_SIZE as u64),
+        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(BASE_MINT_OFFSET, base_mint.as_ref())),
+        RpcFilterType::Memcmp(Memcmp::new_base58_encoded(QUOTE_MINT_OFFSET, quote_mint.as_ref())),
+    ]
+}
+
+/// Derive the WebSocket endpoint for an HTTP(S) RPC URL
+fn websocket_url(rpc_url: &str) -> Result<String> {
+    if let Some(rest) = rpc_url.strip_prefix("https://") {
+        Ok(format!("wss://{}", rest))
+    } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+        Ok(format!("ws://{}", rest))
+    } else {
+        Err(RouterError::ConfigError(format!(
+            "Cannot derive a WebSocket URL from {}",
+            rpc_url
+        )))
+    }
+}
+
+/// Forward account updates to `sender`, reconnecting until the receiver is dropped
+fn run_account_subscription(
+    ws_url: &str,
+    address: &Pubkey,
+    config: RpcAccountInfoConfig,
+    sender: Sender<Account>,
+) {
+    let mut backoff = SUBSCRIPTION_INITIAL_BACKOFF;
+
+    while !sender.is_closed() {
+        match PubsubClient::account_subscribe(ws_url, address, Some(config.clone())) {
+            Ok((mut subscription, updates)) => {
+                backoff = SUBSCRIPTION_INITIAL_BACKOFF;
+
+                loop {
+                    match updates.recv_timeout(SUBSCRIPTION_POLL_INTERVAL) {
+                        Ok(response) => {
+                            let Some(account) = response.value.decode::<Account>() else {
+                                warn!("Could not decode update for {}", address);
+                                continue;
+                            };
+                            if sender.blocking_send(account).is_err() {
+                                break;
+                            }
+                        }
+                        Err(e) if e.is_timeout() => {
+                            if sender.is_closed() {
+                                break;
+                            }
+                        }
+                        Err(_) => {
+                            warn!("Subscription to {} disconnected", address);
+                            break;
+                        }
+                    }
+                }
+
+                let _ = subscription.shutdown();
+            }
+            Err(e) => warn!("Failed to subscribe to {}: {}", address, e),
+        }
+
+        if sender.is_closed() {
+            break;
+        }
+
+        thread::sleep(backoff);
+        backoff = (backoff * 2).min(SUBSCRIPTION_MAX_BACKOFF);
+    }
+}
+
+impl MintSource for SolanaClient {
+    fn fetch_mint(&self, mint_address: &Pubkey) -> Result<Mint> {
+        SolanaClient::fetch_mint(self, mint_address)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Mint source that counts how often it is hit
+    struct CountingMintSource {
+        calls: AtomicUsize,
+    }
+
+    impl MintSource for CountingMintSource {
+        fn fetch_mint(&self, _mint_address: &Pubkey) -> Result<Mint> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Mint {
+                decimals: 6,
+                is_initialized: true,
+                ..Mint::default()
+            })
+        }
+    }
 
     #[test]
     fn test_client_creation() {
@@ -108,6 +664,270 @@ mod tests {
         assert!(client.rpc().url().contains("mainnet"));
     }
 
+    #[test]
+    fn test_client_commitment() {
+        let client = SolanaClient::new_devnet();
+        assert_eq!(client.commitment(), CommitmentConfig::default());
+
+        let client = SolanaClient::new_with_commitment(
+            "https://api.devnet.solana.com".to_string(),
+            CommitmentConfig::processed(),
+        );
+        assert_eq!(client.commitment(), CommitmentConfig::processed());
+        assert_eq!(client.rpc().commitment(), CommitmentConfig::processed());
+    }
+
+    fn transient_error() -> RouterError {
+        let io_error = std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out");
+        RouterError::RpcError(io_error.into())
+    }
+
+    #[test]
+    fn test_with_retry_recovers_from_transient_errors() {
+        let client = SolanaClient::new_devnet();
+        let calls = AtomicUsize::new(0);
+
+        let result = client.with_retry(
+            || {
+                if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err(transient_error())
+                } else {
+                    Ok(42)
+                }
+            },
+            3,
+        );
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_with_retry_gives_up_and_skips_permanent_errors() {
+        let client = SolanaClient::new_devnet();
+
+        let calls = AtomicUsize::new(0);
+        let result: Result<()> = client.with_retry(
+            || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(transient_error())
+            },
+            1,
+        );
+        assert!(matches!(result, Err(RouterError::RpcError(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let calls = AtomicUsize::new(0);
+        let result: Result<()> = client.with_retry(
+            || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(RouterError::InvalidAccountData("bad pubkey".to_string()))
+            },
+            3,
+        );
+        assert!(matches!(result, Err(RouterError::InvalidAccountData(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    /// Transport that either always fails with an RPC error or always succeeds
+    struct FakeTransport {
+        url: String,
+        healthy: bool,
+        calls: AtomicUsize,
+    }
+
+    impl FakeTransport {
+        fn new(url: &str, healthy: bool) -> Arc<Self> {
+            Arc::new(Self {
+                url: url.to_string(),
+                healthy,
+                calls: AtomicUsize::new(0),
+            })
+        }
+
+        fn respond<T>(&self, value: T) -> Result<T> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.healthy {
+                Ok(value)
+            } else {
+                let kind = ClientErrorKind::Custom(format!("{} is down", self.url));
+                Err(RouterError::RpcError(kind.into()))
+            }
+        }
+    }
+
+    impl RpcTransport for FakeTransport {
+        fn url(&self) -> String {
+            self.url.clone()
+        }
+
+        fn get_account(&self, _address: &Pubkey) -> Result<Account> {
+            self.respond(Account::default())
+        }
+
+        fn get_version(&self) -> Result<String> {
+            self.respond("2.0.0".to_string())
+        }
+    }
+
+    #[test]
+    fn test_fails_over_to_fallback_endpoint() {
+        let primary = FakeTransport::new("http://bogus.invalid", false);
+        let fallback = FakeTransport::new("http://fallback.local", true);
+        let client = SolanaClient::from_transports(
+            vec![primary.clone(), fallback.clone()],
+            CommitmentConfig::default(),
+        );
+        assert_eq!(client.active_endpoint(), "http://bogus.invalid");
+
+        assert_eq!(client.get_version().unwrap(), "2.0.0");
+        assert_eq!(client.active_endpoint(), "http://fallback.local");
+        assert_eq!(client.rpc().url(), "http://fallback.local");
+
+        // The fallback stays active, so the dead primary isn't retried
+        client.fetch_account(&Pubkey::new_unique()).unwrap();
+        assert_eq!(primary.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(fallback.calls.load(Ordering::SeqCst), 2);
+    }
+
+    /// Transport on which no account exists
+    struct EmptyTransport;
+
+    impl RpcTransport for EmptyTransport {
+        fn url(&self) -> String {
+            "http://empty.local".to_string()
+        }
+
+        fn get_account(&self, address: &Pubkey) -> Result<Account> {
+            Err(RouterError::AccountNotFound(address.to_string()))
+        }
+
+        fn get_version(&self) -> Result<String> {
+            Ok("2.0.0".to_string())
+        }
+    }
+
+    #[test]
+    fn test_fetch_account_distinguishes_missing_accounts_from_rpc_errors() {
+        let down = FakeTransport::new("http://down.invalid", false);
+        let client = SolanaClient::from_transports(vec![down], CommitmentConfig::default());
+        assert!(matches!(
+            client.fetch_account(&Pubkey::new_unique()),
+            Err(RouterError::RpcError(_))
+        ));
+
+        // A missing account is an answer, not an endpoint failure
+        let fallback = FakeTransport::new("http://fallback.local", true);
+        let client = SolanaClient::from_transports(
+            vec![Arc::new(EmptyTransport), fallback.clone()],
+            CommitmentConfig::default(),
+        );
+        assert!(matches!(
+            client.fetch_account(&Pubkey::new_unique()),
+            Err(RouterError::AccountNotFound(_))
+        ));
+        assert_eq!(fallback.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_failover_returns_last_error_when_all_fail() {
+        let endpoints = [
+            FakeTransport::new("http://first.invalid", false),
+            FakeTransport::new("http://second.invalid", false),
+        ];
+        let client = SolanaClient::from_transports(
+            endpoints.iter().map(|e| e.clone() as Arc<dyn RpcTransport>).collect(),
+            CommitmentConfig::default(),
+        );
+
+        let Err(RouterError::RpcError(error)) = client.get_version() else {
+            panic!("expected every endpoint to fail");
+        };
+        assert!(error.to_string().contains("second.invalid"));
+        assert_eq!(client.active_endpoint(), "http://first.invalid");
+        assert!(endpoints.iter().all(|e| e.calls.load(Ordering::SeqCst) == 1));
+    }
+
+    #[test]
+    fn test_new_with_fallbacks_starts_on_primary() {
+        let client = SolanaClient::new_with_fallbacks(
+            "https://primary.rpc.com".to_string(),
+            vec!["https://fallback.rpc.com".to_string()],
+            CommitmentConfig::finalized(),
+        );
+        assert_eq!(client.active_endpoint(), "https://primary.rpc.com");
+        assert_eq!(client.rpc().url(), "https://primary.rpc.com");
+        assert_eq!(client.commitment(), CommitmentConfig::finalized());
+        assert_eq!(client.rpc().commitment(), CommitmentConfig::finalized());
+    }
+
+    #[test]
+    fn test_raydium_pair_filters() {
+        let base_mint = Pubkey::new_unique();
+        let quote_mint = Pubkey::new_unique();
+
+        let filters = raydium_pair_filters(&base_mint, &quote_mint);
+
+        assert_eq!(filters.len(), 3);
+        assert_eq!(
+            filters[0],
+            RpcFilterType::DataSize(RAYDIUM_AMM_V4_ACCOUNT_SIZE as u64)
+        );
+        let mut data = vec![0u8; RAYDIUM_AMM_V4_ACCOUNT_SIZE];
+        data[BASE_MINT_OFFSET..BASE_MINT_OFFSET + 32].copy_from_slice(base_mint.as_ref());
+        data[QUOTE_MINT_OFFSET..QUOTE_MINT_OFFSET + 32].copy_from_slice(quote_mint.as_ref());
+        for filter in &filters[1..] {
+            let RpcFilterType::Memcmp(memcmp) = filter else {
+                panic!("expected memcmp filter, got {:?}", filter);
+            };
+            assert!(memcmp.bytes_match(&data));
+        }
+    }
+
+    #[test]
+    fn test_discover_rejects_identical_mints() {
+        let client = SolanaClient::new_devnet();
+        let mint = Pubkey::new_unique();
+
+        let result = client.discover_raydium_pools(mint, mint);
+        assert!(matches!(result, Err(RouterError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_websocket_url() {
+        assert_eq!(
+            websocket_url("https://api.devnet.solana.com").unwrap(),
+            "wss://api.devnet.solana.com"
+        );
+        assert_eq!(
+            websocket_url("http://localhost:8899").unwrap(),
+            "ws://localhost:8899"
+        );
+        assert!(matches!(
+            websocket_url("localhost:8899"),
+            Err(RouterError::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fetch_accounts_parallel_reports_rpc_errors() {
+        // Nothing listens on port 1, so the whole request fails
+        let client = SolanaClient::new("http://127.0.0.1:1".to_string());
+        let addresses = [Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let results = client.fetch_accounts_parallel(&addresses).await;
+
+        assert_eq!(results.len(), addresses.len());
+        for result in results {
+            match result {
+                Err(RouterError::RpcError(err)) => {
+                    assert!(err.to_string().contains("getMultipleAccounts failed"));
+                }
+                other => panic!("expected an RPC error, got {:?}", other.map(|_| ())),
+            }
+        }
+    }
+
     #[test]
     fn test_custom_rpc_url() {
         let custom_url = "https://custom.rpc.com";
@@ -146,6 +966,28 @@ mod tests {
         assert!(!version.is_empty());
     }
 
+    #[test]
+    fn test_mint_cache_fetches_once() {
+        let source = CountingMintSource {
+            calls: AtomicUsize::new(0),
+        };
+        let cache = MintCache::default();
+        let mint_address = Pubkey::new_unique();
+
+        let first = cache.get_or_fetch(&source, &mint_address).unwrap();
+        let second = cache.get_or_fetch(&source, &mint_address).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(second.decimals, 6);
+        assert_eq!(source.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.len(), 1);
+
+        cache.clear();
+        assert!(cache.is_empty());
+        cache.get_or_fetch(&source, &mint_address).unwrap();
+        assert_eq!(source.calls.load(Ordering::SeqCst), 2);
+    }
+
     #[test]
     fn test_invalid_mint_address() {
         let client = SolanaClient::new_devnet();
@@ -1,12 +1,23 @@
 //! Configuration management
 
 use crate::error::{Result, RouterError};
+use crate::router::RouterOptions;
+use crate::router::split::DEFAULT_MAX_SPLIT_POOLS;
+use crate::types::parse_token_amount;
 use clap::Parser;
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, read_keypair_file};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Largest referral fee an executor may skim from the output (10%)
+pub const MAX_REFERRAL_FEE_BPS: u16 = 1000;
 
 /// Command-line arguments
-#[derive(Parser, Debug, Clone)]
+#[derive(Parser, Debug, Clone, Serialize, Deserialize)]
 #[command(name = "router-bot")]
 #[command(about = "Solana Liquidity Router Bot", long_about = None)]
 pub struct CliArgs {
@@ -14,9 +25,9 @@ pub struct CliArgs {
     #[arg(short, long)]
     pub rpc_url: Option<String>,
 
-    /// Network (devnet, mainnet-beta, or custom RPC)
-    #[arg(short, long, default_value = "devnet")]
-    pub network: String,
+    /// Network (devnet, mainnet-beta, or custom RPC) [default: devnet]
+    #[arg(short, long)]
+    pub network: Option<String>,
 
     /// Input token mint address
     #[arg(long)]
@@ -26,11 +37,12 @@ pub struct CliArgs {
     #[arg(long)]
     pub token_out: Option<String>,
 
-    /// Amount to swap (in token decimals)
+    /// Amount to swap: raw units (e.g. 1500000000), or a decimal token amount
+    /// (e.g. 1.5) converted using the input mint's decimals
     #[arg(long)]
-    pub amount: Option<u64>,
+    pub amount: Option<String>,
 
-    /// Routing strategy (single, split, multihop, or all)
+    /// Routing strategy (single, split, multihop, all, or best_of to compare all in parallel)
     #[arg(long, default_value = "all")]
     pub strategy: String,
 
@@ -38,6 +50,10 @@ pub struct CliArgs {
     #[arg(long, default_value = "2")]
     pub max_hops: usize,
 
+    /// Reject routes whose price impact exceeds this many basis points
+    #[arg(long)]
+    pub max_impact_bps: Option<u16>,
+
     /// Dry run mode (don't execute, just show routes)
     #[arg(long, default_value = "true")]
     pub dry_run: bool,
@@ -49,6 +65,30 @@ pub struct CliArgs {
     /// Verbose logging
     #[arg(short, long)]
     pub verbose: bool,
+
+    /// Output format (text or json)
+    #[arg(long, default_value = "text")]
+    pub output: String,
+
+    /// Log format (pretty, or json for one structured record per line)
+    #[arg(long, default_value = "pretty")]
+    pub log_format: String,
+
+    /// Signer keypair file for live execution
+    #[arg(long)]
+    pub keypair_path: Option<PathBuf>,
+
+    /// Quote from local pool data only, without connecting to RPC (forces dry run)
+    #[arg(long)]
+    pub offline: bool,
+
+    /// JSON or TOML file defining the pools to route through
+    #[arg(long)]
+    pub pools_file: Option<PathBuf>,
+
+    /// Print each DEX's best single-pool quote for the swap instead of routing it
+    #[arg(long)]
+    pub compare_dexes: bool,
 }
 
 /// Configuration file format
@@ -63,30 +103,72 @@ pub struct ConfigFile {
 pub struct NetworkConfig {
     pub rpc_url: Option<String>,
     pub network: Option<String>,
+    /// RPC commitment level: processed, confirmed, or finalized
+    pub commitment: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoutingConfig {
     pub max_hops: Option<usize>,
     pub default_strategy: Option<String>,
+    /// Reject routes whose price impact exceeds this many basis points
+    pub max_impact_bps: Option<u16>,
+    /// Abort when the best quote's output is below this share of its input, in
+    /// basis points of a 1:1 rate (only meaningful when both tokens share decimals)
+    pub min_output_bps_of_input: Option<u16>,
+    /// Base58 addresses of pools that must never be routed through
+    pub blocked_pools: Option<Vec<String>>,
+    /// Split a swap across at most this many pools (default 3)
+    pub max_split_pools: Option<usize>,
+    /// Route through only the deepest pool per DEX and token pair
+    pub deepest_pool_per_dex: Option<bool>,
+    /// Output discount per hop after the first when comparing routes, in basis points
+    pub hop_penalty_bps: Option<u16>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionConfig {
     pub dry_run: Option<bool>,
     pub slippage_bps: Option<u16>,
+    pub priority_fee_micro_lamports: Option<u64>,
+    pub compute_unit_limit: Option<u32>,
+    pub keypair_path: Option<PathBuf>,
+    /// Simulate dry runs against the RPC node instead of only locally
+    pub simulate_on_chain: Option<bool>,
+    /// Referral fee skimmed from the swap output, in basis points (at most 1000)
+    pub referral_fee_bps: Option<u16>,
 }
 
 /// Final configuration combining CLI args, config file, and defaults
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Config {
     pub rpc_url: String,
     pub network: String,
+    /// Serialized by level name (processed, confirmed, or finalized)
+    #[serde(with = "commitment_by_name")]
+    pub commitment: CommitmentConfig,
     pub max_hops: usize,
     pub strategy: String,
+    pub max_impact_bps: Option<u16>,
+    pub min_output_bps_of_input: Option<u16>,
+    /// Serialized as a sorted list of base58 addresses
+    #[serde(with = "pubkeys_as_strings")]
+    pub blocked_pools: HashSet<Pubkey>,
+    pub max_split_pools: usize,
+    pub deepest_pool_per_dex: bool,
+    pub hop_penalty_bps: u16,
     pub dry_run: bool,
     pub slippage_bps: u16,
     pub verbose: bool,
+    pub output: String,
+    pub log_format: String,
+    pub priority_fee_micro_lamports: Option<u64>,
+    pub compute_unit_limit: Option<u32>,
+    pub keypair_path: Option<PathBuf>,
+    pub simulate_on_chain: bool,
+    pub referral_fee_bps: Option<u16>,
+    pub offline: bool,
+    pub pools_file: Option<PathBuf>,
 }
 
 impl Config {
@@ -103,6 +185,31 @@ impl Config {
             }
         };
 
+        Self::resolve(config_file, args)
+    }
+
+    /// Create config from an already-loaded config file, without any CLI input
+    ///
+    /// Applies the same precedence and validation as `from_args`, with every
+    /// CLI flag left at its default.
+    pub fn from_config_file(config_file: ConfigFile) -> Result<Self> {
+        Self::resolve(config_file, CliArgs::parse_from(["router-bot"]))
+    }
+
+    /// Resolve the final config from a config file and CLI args
+    fn resolve(config_file: ConfigFile, args: CliArgs) -> Result<Self> {
+        // Determine network (priority: CLI > config file > devnet)
+        let network = args
+            .network
+            .clone()
+            .or_else(|| {
+                config_file
+                    .network
+                    .as_ref()
+                    .and_then(|n| n.network.clone())
+            })
+            .unwrap_or_else(|| "devnet".to_string());
+
         // Determine RPC URL (priority: CLI > env > config file > default)
         let rpc_url = args
             .rpc_url
@@ -112,7 +219,17 @@ impl Config {
                     .as_ref()
                     .and_then(|n| n.rpc_url.clone())
             })
-            .unwrap_or_else(|| Self::default_rpc_url(&args.network));
+            .unwrap_or_else(|| Self::default_rpc_url(&network));
+
+        // Determine commitment level
+        let commitment = match config_file
+            .network
+            .as_ref()
+            .and_then(|n| n.commitment.as_deref())
+        {
+            Some(level) => parse_commitment(level)?,
+            None => CommitmentConfig::default(),
+        };
 
         // Determine max hops
         let max_hops = config_file
@@ -128,12 +245,58 @@ impl Config {
             .and_then(|r| r.default_strategy.clone())
             .unwrap_or_else(|| args.strategy.clone());
 
-        // Determine dry run mode
-        let dry_run = config_file
-            .execution
+        // Determine price impact limit (CLI > config file)
+        let max_impact_bps = args.max_impact_bps.or_else(|| {
+            config_file
+                .routing
+                .as_ref()
+                .and_then(|r| r.max_impact_bps)
+        });
+
+        // Determine minimum output threshold (config file only)
+        let min_output_bps_of_input = config_file
+            .routing
+            .as_ref()
+            .and_then(|r| r.min_output_bps_of_input);
+
+        // Determine blocked pools (config file only)
+        let blocked_pools = match config_file
+            .routing
             .as_ref()
-            .and_then(|e| e.dry_run)
-            .unwrap_or(args.dry_run);
+            .and_then(|r| r.blocked_pools.as_deref())
+        {
+            Some(addresses) => parse_blocked_pools(addresses)?,
+            None => HashSet::new(),
+        };
+
+        // Determine split pool cap (config file only)
+        let max_split_pools = config_file
+            .routing
+            .as_ref()
+            .and_then(|r| r.max_split_pools)
+            .unwrap_or(DEFAULT_MAX_SPLIT_POOLS);
+
+        // Determine whether to keep only the deepest pool per DEX (config file only)
+        let deepest_pool_per_dex = config_file
+            .routing
+            .as_ref()
+            .and_then(|r| r.deepest_pool_per_dex)
+            .unwrap_or(false);
+
+        // Determine the per-hop penalty (config file only)
+        let hop_penalty_bps = config_file
+            .routing
+            .as_ref()
+            .and_then(|r| r.hop_penalty_bps)
+            .unwrap_or(0);
+
+        // Determine dry run mode (offline mode can never execute)
+        let dry_run = args.offline
+            || config_file
+                .execution
+                .as_ref()
+                .and_then(|e| e.dry_run)
+                .unwrap_or(args.dry_run);
 
         // Determine slippage
         let slippage_bps = config_file
@@ -142,6 +305,62 @@ impl Config {
             .and_then(|e| e.slippage_bps)
             .unwrap_or(100); // Default 1%
 
+        // Determine priority fee settings (config file only)
+        let priority_fee_micro_lamports = config_file
+            .execution
+            .as_ref()
+            .and_then(|e| e.priority_fee_micro_lamports);
+        let compute_unit_limit = config_file
+            .execution
+            .as_ref()
+            .and_then(|e| e.compute_unit_limit);
+
+        // Determine signer keypair path (CLI > config file)
+        let keypair_path = args.keypair_path.clone().or_else(|| {
+            config_file
+                .execution
+                .as_ref()
+                .and_then(|e| e.keypair_path.clone())
+        });
+
+        // Determine dry-run simulation mode (config file only)
+        let simulate_on_chain = config_file
+            .execution
+            .as_ref()
+            .and_then(|e| e.simulate_on_chain)
+            .unwrap_or(false)
+            && !args.offline;
+
+        // Determine referral fee (config file only)
+        let referral_fee_bps = config_file
+            .execution
+            .as_ref()
+            .and_then(|e| e.referral_fee_bps);
+        if let Some(bps) = referral_fee_bps {
+            validate_referral_fee(bps)?;
+        }
+
+        // Validate output format
+        if args.output != "text" && args.output != "json" {
+            return Err(RouterError::ConfigError(format!(
+                "Unknown output format: {} (expected text or json)",
+                args.output
+            )));
+        }
+
+        // Validate the swap amount before any RPC call is made
+        if let Some(amount) = &args.amount {
+            validate_amount(amount)?;
+        }
+
+        // Validate log format
+        if args.log_format != "pretty" && args.log_format != "json" {
+            return Err(RouterError::ConfigError(format!(
+                "Unknown log format: {} (expected pretty or json)",
+                args.log_format
+            )));
+        }
+
         // Validate max_hops
         if max_hops == 0 || max_hops > 3 {
             return Err(RouterError::ConfigError(
@@ -149,17 +368,55 @@ impl Config {
             ));
         }
 
+        // Validate split pool cap
+        if max_split_pools == 0 {
+            return Err(RouterError::ConfigError(
+                "max_split_pools must be at least 1".to_string(),
+            ));
+        }
+
         Ok(Self {
             rpc_url,
-            network: args.network,
+            network,
+            commitment,
             max_hops,
             strategy,
+            max_impact_bps,
+            min_output_bps_of_input,
+            blocked_pools,
+            max_split_pools,
+            deepest_pool_per_dex,
+            hop_penalty_bps,
             dry_run,
             slippage_bps,
             verbose: args.verbose,
+            output: args.output,
+            log_format: args.log_format,
+            priority_fee_micro_lamports,
+            compute_unit_limit,
+            keypair_path,
+            simulate_on_chain,
+            referral_fee_bps,
+            offline: args.offline,
+            pools_file: args.pools_file,
         })
     }
 
+    /// Write the effective configuration to `path` as TOML
+    ///
+    /// Values are written as resolved, so a CLI flag that overrode the loaded
+    /// config file is saved rather than the file's original value. Session-only
+    /// flags (`verbose`, `output`, `log_format`, `offline`, `pools_file`) are not
+    /// saved.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = toml::to_string(&ConfigFile::from_config(self)).map_err(|e| {
+            RouterError::ConfigError(format!("Failed to serialize config: {}", e))
+        })?;
+
+        std::fs::write(path, contents)
+            .map_err(|e| RouterError::ConfigError(format!("Failed to write config file: {}", e)))
+    }
+
     /// Load config file from path
     fn load_config_file(path: &PathBuf) -> Result<ConfigFile> {
         let contents = std::fs::read_to_string(path)
@@ -185,18 +442,248 @@ impl Config {
         Self {
             rpc_url: "https://api.devnet.solana.com".to_string(),
             network: "devnet".to_string(),
+            commitment: CommitmentConfig::default(),
             max_hops: 2,
             strategy: "all".to_string(),
+            max_impact_bps: None,
+            min_output_bps_of_input: None,
+            blocked_pools: HashSet::new(),
+            max_split_pools: DEFAULT_MAX_SPLIT_POOLS,
+            deepest_pool_per_dex: false,
+            hop_penalty_bps: 0,
             dry_run: true,
             slippage_bps: 100,
             verbose: false,
+            output: "text".to_string(),
+            log_format: "pretty".to_string(),
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+            keypair_path: None,
+            simulate_on_chain: false,
+            referral_fee_bps: None,
+            offline: false,
+            pools_file: None,
+        }
+    }
+
+    /// Router options for the configured hop, price impact and split pool limits,
+    /// hop penalty, blocked pools and pool selection
+    pub fn router_options(&self) -> RouterOptions {
+        let mut options = RouterOptions::default()
+            .with_max_hops(self.max_hops)
+            .with_max_split_pools(self.max_split_pools)
+            .with_hop_penalty(self.hop_penalty_bps);
+        if let Some(bps) = self.max_impact_bps {
+            options = options.with_max_price_impact(bps);
+        }
+        if !self.blocked_pools.is_empty() {
+            options = options.with_excluded_pools(self.blocked_pools.iter().copied().collect());
+        }
+        if self.deepest_pool_per_dex {
+            options = options.with_deepest_pool_per_dex();
+        }
+        options
+    }
+
+    /// Smallest acceptable output for `amount_in`, if a minimum output is configured
+    pub fn min_output_for(&self, amount_in: u64) -> Option<u64> {
+        self.min_output_bps_of_input
+            .map(|bps| (amount_in as u128 * bps as u128 / 10000) as u64)
+    }
+
+    /// Load the signer keypair, if one is configured
+    pub fn load_signer(&self) -> Result<Option<Keypair>> {
+        self.keypair_path.as_deref().map(load_keypair).transpose()
+    }
+}
+
+impl ConfigFile {
+    /// Config file that reproduces `config` when loaded
+    pub fn from_config(config: &Config) -> ConfigFile {
+        ConfigFile {
+            network: Some(NetworkConfig {
+                rpc_url: Some(config.rpc_url.clone()),
+                network: Some(config.network.clone()),
+                commitment: Some(commitment_name(&config.commitment).to_string()),
+            }),
+            routing: Some(RoutingConfig {
+                max_hops: Some(config.max_hops),
+                default_strategy: Some(config.strategy.clone()),
+                max_impact_bps: config.max_impact_bps,
+                min_output_bps_of_input: config.min_output_bps_of_input,
+                blocked_pools: (!config.blocked_pools.is_empty()).then(|| {
+                    let mut addresses: Vec<String> =
+                        config.blocked_pools.iter().map(|p| p.to_string()).collect();
+                    addresses.sort();
+                    addresses
+                }),
+                max_split_pools: Some(config.max_split_pools),
+                deepest_pool_per_dex: Some(config.deepest_pool_per_dex),
+                hop_penalty_bps: Some(config.hop_penalty_bps),
+            }),
+            execution: Some(ExecutionConfig {
+                dry_run: Some(config.dry_run),
+                slippage_bps: Some(config.slippage_bps),
+                priority_fee_micro_lamports: config.priority_fee_micro_lamports,
+                compute_unit_limit: config.compute_unit_limit,
+                keypair_path: config.keypair_path.clone(),
+                simulate_on_chain: Some(config.simulate_on_chain),
+                referral_fee_bps: config.referral_fee_bps,
+            }),
         }
     }
 }
 
+/// Parse a commitment level name (processed, confirmed, or finalized)
+pub fn parse_commitment(level: &str) -> Result<CommitmentConfig> {
+    match level {
+        "processed" => Ok(CommitmentConfig::processed()),
+        "confirmed" => Ok(CommitmentConfig::confirmed()),
+        "finalized" => Ok(CommitmentConfig::finalized()),
+        other => Err(RouterError::ConfigError(format!(
+            "Unknown commitment: {} (expected processed, confirmed, or finalized)",
+            other
+        ))),
+    }
+}
+
+/// Parse base58 pool addresses, rejecting the whole list if any entry is invalid
+fn parse_blocked_pools(addresses: &[String]) -> Result<HashSet<Pubkey>> {
+    addresses
+        .iter()
+        .map(|address| {
+            Pubkey::from_str(address).map_err(|e| {
+                RouterError::ConfigError(format!("Invalid blocked pool {}: {}", address, e))
+            })
+        })
+        .collect()
+}
+
+/// Check that `--amount` is a well-formed, non-zero number
+///
+/// Decimals are not known until the input mint is fetched, so a decimal amount
+/// is only checked for syntax and a whole part that fits in `u64`; see
+/// `amount_to_raw` for the conversion itself.
+fn validate_amount(amount: &str) -> Result<()> {
+    parse_token_amount(amount, 0).map_err(|e| match e {
+        RouterError::MathOverflow => {
+            RouterError::ConfigError(format!("Amount {} overflows u64", amount))
+        }
+        other => other,
+    })?;
+
+    if !amount.chars().any(|c| ('1'..='9').contains(&c)) {
+        return Err(RouterError::ConfigError(
+            "Amount must be greater than zero".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Reject referral fees above `MAX_REFERRAL_FEE_BPS`
+pub fn validate_referral_fee(referral_fee_bps: u16) -> Result<()> {
+    if referral_fee_bps > MAX_REFERRAL_FEE_BPS {
+        return Err(RouterError::ConfigError(format!(
+            "Referral fee of {} bps exceeds the {} bps maximum",
+            referral_fee_bps, MAX_REFERRAL_FEE_BPS
+        )));
+    }
+    Ok(())
+}
+
+/// Convert a decimal `--amount` into raw units of a mint with `decimals` places
+///
+/// Unlike `parse_token_amount`, overflow and amounts that truncate to zero raw
+/// units are reported as `RouterError::ConfigError` naming the decimals, since
+/// both come from user input.
+pub fn amount_to_raw(amount: &str, decimals: u8) -> Result<u64> {
+    let raw = parse_token_amount(amount, decimals).map_err(|e| match e {
+        RouterError::MathOverflow => RouterError::ConfigError(format!(
+            "Amount {} overflows u64 at {} decimals",
+            amount, decimals
+        )),
+        other => other,
+    })?;
+
+    if raw == 0 {
+        return Err(RouterError::ConfigError(format!(
+            "Amount {} is zero at {} decimals",
+            amount, decimals
+        )));
+    }
+
+    Ok(raw)
+}
+
+/// Name of a commitment level, as accepted by `parse_commitment`
+fn commitment_name(commitment: &CommitmentConfig) -> &'static str {
+    if *commitment == CommitmentConfig::processed() {
+        "processed"
+    } else if *commitment == CommitmentConfig::confirmed() {
+        "confirmed"
+    } else {
+        "finalized"
+    }
+}
+
+/// Serde support for a `CommitmentConfig` stored by level name
+mod commitment_by_name {
+    use super::*;
+    use serde::de::Error;
+
+    pub fn serialize<S: Serializer>(
+        commitment: &CommitmentConfig,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(commitment_name(commitment))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<CommitmentConfig, D::Error> {
+        let level = String::deserialize(deserializer)?;
+        parse_commitment(&level).map_err(D::Error::custom)
+    }
+}
+
+/// Serde support for a pubkey set stored as sorted base58 strings
+mod pubkeys_as_strings {
+    use super::*;
+    use serde::de::Error;
+
+    pub fn serialize<S: Serializer>(
+        pubkeys: &HashSet<Pubkey>,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let mut addresses: Vec<String> = pubkeys.iter().map(|p| p.to_string()).collect();
+        addresses.sort();
+        addresses.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<HashSet<Pubkey>, D::Error> {
+        let addresses = Vec::<String>::deserialize(deserializer)?;
+        parse_blocked_pools(&addresses).map_err(D::Error::custom)
+    }
+}
+
+/// Load a keypair from a Solana CLI-style JSON keypair file
+pub fn load_keypair(path: &Path) -> Result<Keypair> {
+    read_keypair_file(path).map_err(|e| {
+        RouterError::ConfigError(format!(
+            "Failed to read keypair file {}: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use solana_sdk::signature::{Signer, write_keypair_file};
 
     #[test]
     fn test_default_config() {
@@ -211,15 +698,22 @@ mod tests {
     fn test_config_from_args() {
         let args = CliArgs {
             rpc_url: Some("https://custom.rpc.com".to_string()),
-            network: "mainnet".to_string(),
+            network: Some("mainnet".to_string()),
             token_in: None,
             token_out: None,
             amount: None,
             strategy: "single".to_string(),
             max_hops: 3,
+            max_impact_bps: None,
             dry_run: false,
             config: None,
             verbose: true,
+            output: "json".to_string(),
+            log_format: "json".to_string(),
+            keypair_path: None,
+            offline: false,
+            pools_file: None,
+            compare_dexes: false,
         };
 
         let config = Config::from_args(args).unwrap();
@@ -228,27 +722,294 @@ mod tests {
         assert_eq!(config.max_hops, 3);
         assert!(!config.dry_run);
         assert!(config.verbose);
+        assert_eq!(config.output, "json");
+        assert_eq!(config.log_format, "json");
     }
 
     #[test]
     fn test_invalid_max_hops() {
         let args = CliArgs {
             rpc_url: None,
-            network: "devnet".to_string(),
+            network: None,
             token_in: None,
             token_out: None,
             amount: None,
             strategy: "all".to_string(),
             max_hops: 0, // Invalid!
+            max_impact_bps: None,
             dry_run: true,
             config: None,
             verbose: false,
+            output: "text".to_string(),
+            log_format: "pretty".to_string(),
+            keypair_path: None,
+            offline: false,
+            pools_file: None,
+            compare_dexes: false,
         };
 
         let result = Config::from_args(args);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_offline_forces_dry_run() {
+        let path = std::env::temp_dir().join("router-bot-offline.toml");
+        std::fs::write(&path, "[execution]\ndry_run = false\nsimulate_on_chain = true\n").unwrap();
+
+        let args =
+            CliArgs::parse_from(["router-bot", "--offline", "--config", path.to_str().unwrap()]);
+        let config = Config::from_args(args);
+        std::fs::remove_file(&path).unwrap();
+
+        let config = config.unwrap();
+        assert!(config.offline);
+        assert!(config.dry_run);
+        assert!(!config.simulate_on_chain);
+    }
+
+    #[test]
+    fn test_amount_validation() {
+        for amount in ["0", "0.000", "0,000"] {
+            let args = CliArgs::parse_from(["router-bot", "--amount", amount]);
+            assert!(matches!(Config::from_args(args), Err(RouterError::ConfigError(_))));
+        }
+
+        let args = CliArgs::parse_from(["router-bot", "--amount", "18446744073709551616"]);
+        assert!(matches!(Config::from_args(args), Err(RouterError::ConfigError(_))));
+
+        let args = CliArgs::parse_from(["router-bot", "--amount", "0.5"]);
+        assert!(Config::from_args(args).is_ok());
+    }
+
+    #[test]
+    fn test_amount_to_raw_guards_overflow() {
+        assert_eq!(amount_to_raw("1.5", 9).unwrap(), 1_500_000_000);
+
+        // 1000 tokens at 18 decimals is 1e21 raw units, beyond u64::MAX
+        assert!(matches!(amount_to_raw("1000", 18), Err(RouterError::ConfigError(_))));
+        assert!(matches!(amount_to_raw("0.0000001", 6), Err(RouterError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_max_impact_reaches_router_options() {
+        let args = CliArgs::parse_from(["router-bot", "--max-hops", "3", "--max-impact-bps", "150"]);
+        let config = Config::from_args(args).unwrap();
+        assert_eq!(config.max_impact_bps, Some(150));
+
+        let options = config.router_options();
+        assert_eq!(options.max_hops, 3);
+        assert_eq!(options.max_price_impact_bps, Some(150));
+
+        let options = Config::default_devnet().router_options();
+        assert_eq!(options.max_price_impact_bps, None);
+    }
+
+    #[test]
+    fn test_save_round_trips() {
+        let config = Config {
+            rpc_url: "https://custom.rpc.com".to_string(),
+            network: "mainnet-beta".to_string(),
+            commitment: CommitmentConfig::finalized(),
+            max_hops: 3,
+            strategy: "split".to_string(),
+            max_impact_bps: Some(200),
+            min_output_bps_of_input: Some(9000),
+            blocked_pools: HashSet::from([Pubkey::new_unique(), Pubkey::new_unique()]),
+            max_split_pools: 2,
+            deepest_pool_per_dex: true,
+            hop_penalty_bps: 15,
+            dry_run: false,
+            slippage_bps: 50,
+            priority_fee_micro_lamports: Some(10_000),
+            compute_unit_limit: Some(400_000),
+            keypair_path: Some(PathBuf::from("/tmp/router-bot-signer.json")),
+            simulate_on_chain: true,
+            referral_fee_bps: Some(25),
+            ..Config::default_devnet()
+        };
+
+        let path = std::env::temp_dir().join("router-bot-saved.toml");
+        config.save(&path).unwrap();
+        let file = Config::load_config_file(&path);
+        let args = CliArgs::parse_from(["router-bot", "--config", path.to_str().unwrap()]);
+        let reloaded = Config::from_args(args);
+        std::fs::remove_file(&path).unwrap();
+
+        let file = file.unwrap();
+        assert_eq!(file.network.unwrap().commitment.as_deref(), Some("finalized"));
+        assert_eq!(file.routing.unwrap().max_hops, Some(3));
+        assert_eq!(file.execution.unwrap().slippage_bps, Some(50));
+        assert_eq!(reloaded.unwrap(), config);
+    }
+
+    #[test]
+    fn test_from_config_file() {
+        let blocked = Pubkey::new_unique();
+        let contents = format!(
+            r#"
+[network]
+network = "mainnet-beta"
+commitment = "confirmed"
+
+[routing]
+max_hops = 3
+default_strategy = "split"
+max_impact_bps = 250
+min_output_bps_of_input = 9500
+blocked_pools = ["{}"]
+max_split_pools = 4
+deepest_pool_per_dex = true
+hop_penalty_bps = 20
+
+[execution]
+dry_run = false
+slippage_bps = 30
+priority_fee_micro_lamports = 5000
+compute_unit_limit = 300000
+keypair_path = "/tmp/signer.json"
+simulate_on_chain = true
+referral_fee_bps = 10
+"#,
+            blocked
+        );
+        let file: ConfigFile = toml::from_str(&contents).unwrap();
+
+        let config = Config::from_config_file(file.clone()).unwrap();
+        assert_eq!(config.network, "mainnet-beta");
+        assert_eq!(config.rpc_url, "https://api.mainnet-beta.solana.com");
+        assert_eq!(config.commitment, CommitmentConfig::confirmed());
+        assert_eq!(config.max_hops, 3);
+        assert_eq!(config.strategy, "split");
+        assert_eq!(config.max_impact_bps, Some(250));
+        assert_eq!(config.min_output_bps_of_input, Some(9500));
+        assert_eq!(config.blocked_pools, HashSet::from([blocked]));
+        assert_eq!(config.max_split_pools, 4);
+        assert!(config.deepest_pool_per_dex);
+        assert_eq!(config.hop_penalty_bps, 20);
+        assert_eq!(config.router_options().hop_penalty_bps, 20);
+        assert!(!config.dry_run);
+        assert_eq!(config.slippage_bps, 30);
+        assert_eq!(config.priority_fee_micro_lamports, Some(5000));
+        assert_eq!(config.compute_unit_limit, Some(300_000));
+        assert_eq!(config.keypair_path, Some(PathBuf::from("/tmp/signer.json")));
+        assert!(config.simulate_on_chain);
+        assert_eq!(config.referral_fee_bps, Some(10));
+        // CLI-only settings keep their defaults
+        assert_eq!(config.output, "text");
+        assert_eq!(config.log_format, "pretty");
+        assert!(!config.offline);
+
+        // The resolved config itself round-trips through serde
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("\"commitment\":\"confirmed\""));
+        let parsed: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, config);
+
+        // Validation still applies
+        let mut invalid = file;
+        invalid.routing.as_mut().unwrap().max_hops = Some(0);
+        assert!(matches!(
+            Config::from_config_file(invalid),
+            Err(RouterError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_save_keeps_cli_overrides() {
+        let path = std::env::temp_dir().join("router-bot-override.toml");
+        std::fs::write(&path, "[network]\nrpc_url = \"https://file.rpc.com\"\n").unwrap();
+
+        let args = CliArgs::parse_from([
+            "router-bot",
+            "--config",
+            path.to_str().unwrap(),
+            "--rpc-url",
+            "https://cli.rpc.com",
+        ]);
+        let config = Config::from_args(args).unwrap();
+        config.save(&path).unwrap();
+        let file = Config::load_config_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let network = file.unwrap().network.unwrap();
+        assert_eq!(network.rpc_url.as_deref(), Some("https://cli.rpc.com"));
+        assert_eq!(network.network.as_deref(), Some("devnet"));
+    }
+
+    #[test]
+    fn test_blocked_pools_are_excluded() {
+        use crate::dex::RaydiumPool;
+        use crate::router::SinglePoolRouter;
+        use crate::types::pool::Pool;
+
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let honeypot = RaydiumPool::new(
+            Pubkey::new_unique(),
+            token_a,
+            token_b,
+            1_000_000_000_000,
+            50_000_000_000_000,
+        );
+        let fallback = RaydiumPool::new(
+            Pubkey::new_unique(),
+            token_a,
+            token_b,
+            1_000_000_000,
+            50_000_000_000,
+        );
+        let pools: Vec<Box<dyn Pool>> =
+            vec![Box::new(honeypot.clone()), Box::new(fallback.clone())];
+
+        let path = std::env::temp_dir().join("router-bot-blocked-pools.toml");
+        std::fs::write(
+            &path,
+            format!("[routing]\nblocked_pools = [\"{}\"]\n", honeypot.address()),
+        )
+        .unwrap();
+        let args = CliArgs::parse_from(["router-bot", "--config", path.to_str().unwrap()]);
+        let config = Config::from_args(args);
+        std::fs::remove_file(&path).unwrap();
+
+        let config = config.unwrap();
+        assert!(config.blocked_pools.contains(honeypot.address()));
+
+        let quote = SinglePoolRouter::find_best_route_with_options(
+            &pools,
+            &token_a,
+            &token_b,
+            1_000_000,
+            &config.router_options(),
+        )
+        .unwrap();
+        assert_eq!(quote.route.steps[0].pool_address, *fallback.address());
+    }
+
+    #[test]
+    fn test_invalid_blocked_pool_fails_config() {
+        let path = std::env::temp_dir().join("router-bot-invalid-blocked-pool.toml");
+        std::fs::write(&path, "[routing]\nblocked_pools = [\"not-a-pubkey\"]\n").unwrap();
+
+        let args = CliArgs::parse_from(["router-bot", "--config", path.to_str().unwrap()]);
+        let result = Config::from_args(args);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(RouterError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_referral_fee_over_limit_fails_config() {
+        let path = std::env::temp_dir().join("router-bot-referral-fee.toml");
+        std::fs::write(&path, "[execution]\nreferral_fee_bps = 1500\n").unwrap();
+
+        let args = CliArgs::parse_from(["router-bot", "--config", path.to_str().unwrap()]);
+        let result = Config::from_args(args);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(RouterError::ConfigError(_))));
+    }
+
     #[test]
     fn test_default_rpc_urls() {
         assert_eq!(
@@ -260,4 +1021,51 @@ mod tests {
             "https://api.mainnet-beta.solana.com"
         );
     }
+
+    #[test]
+    fn test_parse_commitment() {
+        assert_eq!(
+            parse_commitment("processed").unwrap(),
+            CommitmentConfig::processed()
+        );
+        assert_eq!(
+            parse_commitment("finalized").unwrap(),
+            CommitmentConfig::finalized()
+        );
+        assert!(matches!(
+            parse_commitment("recent"),
+            Err(RouterError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_commitment_in_config_file() {
+        let path = std::env::temp_dir().join("router-bot-invalid-commitment.toml");
+        std::fs::write(&path, "[network]\ncommitment = \"fastest\"\n").unwrap();
+
+        let args = CliArgs::parse_from(["router-bot", "--config", path.to_str().unwrap()]);
+        let result = Config::from_args(args);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(RouterError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_load_keypair() {
+        let keypair = Keypair::new();
+        let path = std::env::temp_dir().join(format!("router-bot-{}.json", keypair.pubkey()));
+        write_keypair_file(&keypair, &path).unwrap();
+
+        let loaded = load_keypair(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.pubkey(), keypair.pubkey());
+    }
+
+    #[test]
+    fn test_load_keypair_missing_file() {
+        let path = std::env::temp_dir().join("router-bot-missing-keypair.json");
+        let result = load_keypair(&path);
+        assert!(matches!(result, Err(RouterError::ConfigError(_))));
+    }
 }
@@ -0,0 +1,299 @@
+//! Lifinity pool implementation
+//!
+//! Lifinity is a proactive market maker: instead of letting the reserve ratio
+//! set the price, it quotes around an oracle price and concentrates liquidity
+//! near it. We model this as a constant product curve over virtual reserves
+//! that are centered on the oracle price and scaled by a concentration factor,
+//! so larger concentrations dampen price impact relative to a plain x*y=k pool.
+
+use crate::calculator::{calculate_amount_out, calculate_price_impact};
+use crate::error::{Result, RouterError};
+use crate::types::pool::{Pool, PoolInfo};
+use solana_sdk::pubkey::Pubkey;
+use std::any::Any;
+use std::str::FromStr;
+use std::time::Instant;
+
+/// Lifinity v2 program ID
+pub const LIFINITY_V2_PROGRAM: &str = "2wT8Yq49kHgDzXuPxZSaeLaH1qbmGXtEyPy64bL7aD3c";
+
+/// Lifinity pool implementation
+#[derive(Debug, Clone)]
+pub struct LifinityPool {
+    info: PoolInfo,
+    /// Oracle price of token A denominated in token B (raw units)
+    oracle_price: f64,
+    /// Liquidity concentration around the oracle price (1 = constant product)
+    concentration: u64,
+}
+
+impl LifinityPool {
+    /// Create a new Lifinity pool
+    ///
+    /// `oracle_price` is the price of one raw unit of token A in raw units of
+    /// token B. A `concentration` of 0 is treated as 1.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        address: Pubkey,
+        token_a: Pubkey,
+        token_b: Pubkey,
+        reserve_a: u64,
+        reserve_b: u64,
+        fee_bps: u16,
+        oracle_price: f64,
+        concentration: u64,
+    ) -> Self {
+        Self {
+            info: PoolInfo::new(
+                address,
+                "Lifinity".to_string(),
+                token_a,
+                token_b,
+                reserve_a,
+                reserve_b,
+                fee_bps,
+            ),
+            oracle_price,
+            concentration: concentration.max(1),
+        }
+    }
+
+    pub fn oracle_price(&self) -> f64 {
+        self.oracle_price
+    }
+
+    pub fn concentration(&self) -> u64 {
+        self.concentration
+    }
+
+    /// Update the injected oracle price
+    pub fn set_oracle_price(&mut self, oracle_price: f64) {
+        self.oracle_price = oracle_price;
+    }
+
+    /// Virtual (input, output) reserves for a swap direction
+    ///
+    /// The virtual reserves keep the pool's invariant `k = reserve_a * reserve_b`
+    /// but are rebalanced so their ratio equals the oracle price, then scaled
+    /// by the concentration factor.
+    fn virtual_reserves(&self, a_to_b: bool) -> Result<(u64, u64)> {
+        if self.info.reserve_a == 0 || self.info.reserve_b == 0 {
+            return Err(RouterError::InvalidReserves);
+        }
+        if !self.oracle_price.is_finite() || self.oracle_price <= 0.0 {
+            return Err(RouterError::InvalidQuote(format!(
+                "Invalid oracle price {} for Lifinity pool {}",
+                self.oracle_price, self.info.address
+            )));
+        }
+
+        let k = self.info.reserve_a as f64 * self.info.reserve_b as f64;
+        let concentration = self.concentration as f64;
+        let virtual_a = ((k / self.oracle_price).sqrt() * concentration) as u64;
+        let virtual_b = ((k * self.oracle_price).sqrt() * concentration) as u64;
+
+        if virtual_a == 0 || virtual_b == 0 {
+            return Err(RouterError::InvalidReserves);
+        }
+
+        Ok(if a_to_b {
+            (virtual_a, virtual_b)
+        } else {
+            (virtual_b, virtual_a)
+        })
+    }
+
+    /// Parse Lifinity pool account data
+    pub fn from_account_data(_address: Pubkey, _data: &[u8]) -> Result<Self> {
+        // TODO: Implement actual Lifinity account and oracle parsing
+        Err(RouterError::PoolParseError(
+            "Lifinity pool parsing not yet implemented - use new() for testing".to_string(),
+        ))
+    }
+
+    /// Get the Lifinity v2 program ID
+    pub fn program_id() -> Pubkey {
+        Pubkey::from_str(LIFINITY_V2_PROGRAM).unwrap()
+    }
+}
+
+impl Pool for LifinityPool {
+    fn address(&self) -> &Pubkey {
+        &self.info.address
+    }
+
+    fn dex_name(&self) -> &str {
+        &self.info.dex
+    }
+
+    fn token_a(&self) -> &Pubkey {
+        &self.info.token_a
+    }
+
+    fn token_b(&self) -> &Pubkey {
+        &self.info.token_b
+    }
+
+    fn reserve_a(&self) -> u64 {
+        self.info.reserve_a
+    }
+
+    fn reserve_b(&self) -> u64 {
+        self.info.reserve_b
+    }
+
+    fn fee_bps(&self) -> u16 {
+        self.info.fee_bps
+    }
+
+    fn calculate_output(&self, input_amount: u64, a_to_b: bool) -> Result<(u64, u16)> {
+        let (virtual_in, virtual_out) = self.virtual_reserves(a_to_b)?;
+        let (_, reserve_out) = self.info.get_reserves(a_to_b);
+
+        let output_amount =
+            calculate_amount_out(input_amount, virtual_in, virtual_out, self.fee_bps())?;
+
+        // The curve is virtual, but the pool can only pay out what it holds
+        if output_amount >= reserve_out {
+            return Err(RouterError::InsufficientLiquidity);
+        }
+
+        let price_impact =
+            calculate_price_impact(input_amount, output_amount, virtual_in, virtual_out)?;
+
+        Ok((output_amount, price_impact))
+    }
+
+    fn calculate_price_impact(&self, input_amount: u64, a_to_b: bool) -> Result<u16> {
+        let (_, price_impact) = self.calculate_output(input_amount, a_to_b)?;
+        Ok(price_impact)
+    }
+
+    fn has_sufficient_liquidity(&self, input_amount: u64, a_to_b: bool) -> bool {
+        let (_, reserve_out) = self.info.get_reserves(a_to_b);
+        match self.calculate_output(input_amount, a_to_b) {
+            Ok((output, _)) => output < reserve_out / 2,
+            Err(_) => false,
+        }
+    }
+
+    fn update_reserves(&mut self, reserve_a: u64, reserve_b: u64) -> Result<()> {
+        self.info.set_reserves(reserve_a, reserve_b)
+    }
+
+    fn last_updated(&self) -> Option<Instant> {
+        self.info.last_updated
+    }
+
+    fn clone_box(&self) -> Box<dyn Pool> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn program_id(&self) -> Option<Pubkey> {
+        Some(Self::program_id())
+    }
+
+    fn mid_price(&self, a_to_b: bool) -> f64 {
+        if a_to_b {
+            self.oracle_price
+        } else {
+            1.0 / self.oracle_price
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::raydium::RaydiumPool;
+
+    const RESERVE_A: u64 = 1_000_000_000;
+    const RESERVE_B: u64 = 50_000_000_000;
+
+    fn create_test_pool(concentration: u64) -> LifinityPool {
+        LifinityPool::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            RESERVE_A,
+            RESERVE_B,
+            25,
+            50.0,
+            concentration,
+        )
+    }
+
+    #[test]
+    fn test_lifinity_pool_creation() {
+        let pool = create_test_pool(10);
+
+        assert_eq!(pool.dex_name(), "Lifinity");
+        assert_eq!(pool.fee_bps(), 25);
+        assert_eq!(pool.concentration(), 10);
+        assert!((pool.mid_price(true) - 50.0).abs() < 1e-9);
+        assert!((pool.mid_price(false) - 0.02).abs() < 1e-9);
+        assert_eq!(create_test_pool(0).concentration(), 1);
+    }
+
+    #[test]
+    fn test_lifinity_lower_impact_than_raydium() {
+        let lifinity = create_test_pool(10);
+        let raydium = RaydiumPool::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            RESERVE_A,
+            RESERVE_B,
+        );
+
+        // 5% of the pool
+        let input = 50_000_000;
+        let (lifinity_out, lifinity_impact) = lifinity.calculate_output(input, true).unwrap();
+        let (raydium_out, raydium_impact) = raydium.calculate_output(input, true).unwrap();
+
+        assert!(lifinity_impact < raydium_impact);
+        assert!(lifinity_out > raydium_out);
+
+        let (_, lifinity_impact) = lifinity.calculate_output(input, false).unwrap();
+        let (_, raydium_impact) = raydium.calculate_output(input, false).unwrap();
+        assert!(lifinity_impact < raydium_impact);
+    }
+
+    #[test]
+    fn test_lifinity_quotes_around_oracle_price() {
+        // Reserves imply 50 B per A, but the oracle says 55
+        let mut pool = create_test_pool(1);
+        pool.set_oracle_price(55.0);
+
+        let input = 1_000;
+        let (output, _) = pool.calculate_output(input, true).unwrap();
+        // ~55 B per A less the 0.25% fee
+        assert!(output > 54_000 && output < 55_000);
+    }
+
+    #[test]
+    fn test_lifinity_output_capped_by_real_reserves() {
+        let pool = create_test_pool(100);
+
+        // The concentrated curve would pay out more than the pool holds
+        let input = 2 * RESERVE_A;
+        let result = pool.calculate_output(input, true);
+        assert!(matches!(result, Err(RouterError::InsufficientLiquidity)));
+        assert!(!pool.has_sufficient_liquidity(input, true));
+    }
+
+    #[test]
+    fn test_lifinity_invalid_oracle_price() {
+        let mut pool = create_test_pool(10);
+        pool.set_oracle_price(0.0);
+
+        assert!(matches!(
+            pool.calculate_output(1_000, true),
+            Err(RouterError::InvalidQuote(_))
+        ));
+    }
+}
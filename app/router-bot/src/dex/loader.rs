@@ -0,0 +1,340 @@
+//! Load pool definitions from a JSON or TOML file
+//!
+//! JSON files hold a top-level array of pool entries; TOML files use an array of
+//! `[[pools]]` tables. For example:
+//!
+//! ```toml
+//! [[pools]]
+//! dex = "Orca"
+//! pool_type = "whirlpool"
+//! address = "..."
+//! token_a = "..."
+//! token_b = "..."
+//! reserve_a = 1000000000
+//! reserve_b = 50000000000
+//! fee_bps = 10
+//! ```
+
+use crate::dex::meteora::MeteoraPool;
+use crate::dex::orca::{OrcaPool, OrcaPoolType};
+use crate::dex::phoenix::PhoenixPool;
+use crate::dex::raydium::{DEFAULT_RAYDIUM_FEE_BPS, RaydiumPool};
+use crate::error::{Result, RouterError};
+use crate::types::pool::Pool;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Default Orca constant product fee (0.3%)
+const DEFAULT_ORCA_FEE_BPS: u16 = 30;
+
+/// Default Meteora fee (0.25%)
+const DEFAULT_METEORA_FEE_BPS: u16 = 25;
+
+/// A single pool definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolEntry {
+    /// DEX name: Raydium, Orca, Meteora, or Phoenix (case-insensitive)
+    pub dex: String,
+    pub address: String,
+    pub token_a: String,
+    pub token_b: String,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    /// Fee in basis points; defaults to the DEX's standard tier
+    pub fee_bps: Option<u16>,
+    /// Orca: `constant_product` (default) or `whirlpool`;
+    /// Meteora: `dynamic` (default) or `stable`
+    pub pool_type: Option<String>,
+    /// Amplification coefficient for Meteora stable pools
+    pub amp: Option<u64>,
+    /// Best bid for Phoenix markets, scaled by `phoenix::PRICE_SCALE`
+    pub best_bid: Option<u64>,
+    /// Best ask for Phoenix markets, scaled by `phoenix::PRICE_SCALE`
+    pub best_ask: Option<u64>,
+}
+
+/// TOML pool file layout (`[[pools]]` tables)
+#[derive(Debug, Deserialize)]
+struct TomlPoolFile {
+    pools: Vec<PoolEntry>,
+}
+
+/// Load pools from a `.json` or `.toml` file
+///
+/// Unknown DEX names, unknown pool types, malformed addresses, and fees over
+/// 10000 bps are reported as `RouterError::ConfigError`.
+pub fn load_pools_from_file(path: &Path) -> Result<Vec<Box<dyn Pool>>> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        RouterError::ConfigError(format!("Failed to read pools file {}: {}", path.display(), e))
+    })?;
+
+    let entries = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str::<Vec<PoolEntry>>(&contents)
+            .map_err(|e| RouterError::ConfigError(format!("Failed to parse pools file: {}", e)))?,
+        Some("toml") => toml::from_str::<TomlPoolFile>(&contents)
+            .map_err(|e| RouterError::ConfigError(format!("Failed to parse pools file: {}", e)))?
+            .pools,
+        _ => {
+            return Err(RouterError::ConfigError(format!(
+                "Unsupported pools file {} (expected .json or .toml)",
+                path.display()
+            )));
+        }
+    };
+
+    entries.iter().map(build_pool).collect()
+}
+
+/// Construct the concrete pool described by `entry`
+fn build_pool(entry: &PoolEntry) -> Result<Box<dyn Pool>> {
+    let address = parse_pubkey(&entry.address, "address")?;
+    let token_a = parse_pubkey(&entry.token_a, "token_a")?;
+    let token_b = parse_pubkey(&entry.token_b, "token_b")?;
+    let pool_type = entry.pool_type.as_deref().map(str::to_lowercase);
+
+    // A fee over 100% would underflow the `10000 - fee_bps` in every quote
+    if let Some(fee_bps) = entry.fee_bps.filter(|&fee_bps| fee_bps > 10000) {
+        return Err(RouterError::ConfigError(format!(
+            "Invalid fee for {} pool {}: {} bps exceeds 10000",
+            entry.dex, entry.address, fee_bps
+        )));
+    }
+
+    let pool: Box<dyn Pool> = match entry.dex.to_lowercase().as_str() {
+        "raydium" => Box::new(RaydiumPool::new_with_fee(
+            address,
+            token_a,
+            token_b,
+            entry.reserve_a,
+            entry.reserve_b,
+            entry.fee_bps.unwrap_or(DEFAULT_RAYDIUM_FEE_BPS),
+        )?),
+        "orca" => {
+            let orca_type = match pool_type.as_deref() {
+                None | Some("constant_product") => OrcaPoolType::ConstantProduct,
+                Some("whirlpool") => OrcaPoolType::ConcentratedLiquidity,
+                Some(other) => return Err(unknown_pool_type(&entry.dex, other)),
+            };
+            Box::new(OrcaPool::new(
+                address,
+                token_a,
+                token_b,
+                entry.reserve_a,
+                entry.reserve_b,
+                orca_type,
+                entry.fee_bps.unwrap_or(DEFAULT_ORCA_FEE_BPS),
+            )?)
+        }
+        "meteora" => {
+            let fee_bps = entry.fee_bps.unwrap_or(DEFAULT_METEORA_FEE_BPS);
+            match pool_type.as_deref() {
+                None | Some("dynamic") => Box::new(MeteoraPool::new(
+                    address,
+                    token_a,
+                    token_b,
+                    entry.reserve_a,
+                    entry.reserve_b,
+                    fee_bps,
+                )),
+                Some("stable") => {
+                    let amp = entry.amp.ok_or_else(|| {
+                        RouterError::ConfigError(format!(
+                            "Meteora stable pool {} requires amp",
+                            entry.address
+                        ))
+                    })?;
+                    Box::new(MeteoraPool::new_stable(
+                        address,
+                        token_a,
+                        token_b,
+                        entry.reserve_a,
+                        entry.reserve_b,
+                        fee_bps,
+                        amp,
+                    ))
+                }
+                Some(other) => return Err(unknown_pool_type(&entry.dex, other)),
+            }
+        }
+        "phoenix" => {
+            let (Some(best_bid), Some(best_ask)) = (entry.best_bid, entry.best_ask) else {
+                return Err(RouterError::ConfigError(format!(
+                    "Phoenix market {} requires best_bid and best_ask",
+                    entry.address
+                )));
+            };
+            Box::new(PhoenixPool::new(
+                address,
+                token_a,
+                token_b,
+                entry.reserve_a,
+                entry.reserve_b,
+                best_bid,
+                best_ask,
+            ))
+        }
+        _ => {
+            return Err(RouterError::ConfigError(format!(
+                "Unknown DEX in pools file: {}",
+                entry.dex
+            )));
+        }
+    };
+
+    Ok(pool)
+}
+
+fn parse_pubkey(value: &str, field: &str) -> Result<Pubkey> {
+    Pubkey::from_str(value)
+        .map_err(|e| RouterError::ConfigError(format!("Invalid {} {}: {}", field, value, e)))
+}
+
+fn unknown_pool_type(dex: &str, pool_type: &str) -> RouterError {
+    RouterError::ConfigError(format!("Unknown {} pool type: {}", dex, pool_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::SinglePoolRouter;
+
+    fn entry(dex: &str, token_a: Pubkey, token_b: Pubkey) -> PoolEntry {
+        PoolEntry {
+            dex: dex.to_string(),
+            address: Pubkey::new_unique().to_string(),
+            token_a: token_a.to_string(),
+            token_b: token_b.to_string(),
+            reserve_a: 1_000_000_000,
+            reserve_b: 50_000_000_000,
+            fee_bps: None,
+            pool_type: None,
+            amp: None,
+            best_bid: None,
+            best_ask: None,
+        }
+    }
+
+    #[test]
+    fn test_load_json_pools_into_quote() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let mut whirlpool = entry("orca", token_a, token_b);
+        whirlpool.pool_type = Some("whirlpool".to_string());
+        whirlpool.fee_bps = Some(5);
+        let entries = vec![entry("Raydium", token_a, token_b), whirlpool];
+
+        let path = std::env::temp_dir().join("router-bot-pools.json");
+        std::fs::write(&path, serde_json::to_string(&entries).unwrap()).unwrap();
+        let pools = load_pools_from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let pools = pools.unwrap();
+        assert_eq!(pools.len(), 2);
+        assert_eq!(pools[0].dex_name(), "Raydium");
+        assert_eq!(pools[0].fee_bps(), DEFAULT_RAYDIUM_FEE_BPS);
+        assert_eq!(pools[1].dex_name(), "Orca");
+        assert_eq!(pools[1].fee_bps(), 5);
+
+        let quote =
+            SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, 1_000_000).unwrap();
+        assert_eq!(quote.route.steps[0].dex, "Orca");
+        assert!(quote.amount_out > 0);
+    }
+
+    #[test]
+    fn test_load_toml_pools() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let contents = format!(
+            r#"
+[[pools]]
+dex = "Meteora"
+pool_type = "stable"
+address = "{}"
+token_a = "{}"
+token_b = "{}"
+reserve_a = 1000000000
+reserve_b = 1000000000
+amp = 100
+"#,
+            Pubkey::new_unique(),
+            token_a,
+            token_b
+        );
+        let path = std::env::temp_dir().join("router-bot-pools.toml");
+        std::fs::write(&path, contents).unwrap();
+        let pools = load_pools_from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let pools = pools.unwrap();
+        assert_eq!(pools.len(), 1);
+        assert_eq!(pools[0].dex_name(), "Meteora");
+        assert_eq!(pools[0].fee_bps(), DEFAULT_METEORA_FEE_BPS);
+    }
+
+    #[test]
+    fn test_reversed_pair_entries_route_both_ways() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let mut reversed = entry("Raydium", token_b, token_a);
+        reversed.reserve_a = 50_000_000_000;
+        reversed.reserve_b = 1_000_000_000;
+        let pools: Vec<Box<dyn Pool>> = [entry("Orca", token_a, token_b), reversed]
+            .iter()
+            .map(build_pool)
+            .collect::<Result<_>>()
+            .unwrap();
+
+        assert_eq!(pools[0].pair_key(), pools[1].pair_key());
+
+        let forward = SinglePoolRouter::find_all_routes(&pools, &token_a, &token_b, 1_000_000);
+        let backward = SinglePoolRouter::find_all_routes(&pools, &token_b, &token_a, 1_000_000);
+        assert_eq!(forward.len(), 2);
+        assert_eq!(backward.len(), 2);
+    }
+
+    #[test]
+    fn test_unknown_dex_is_config_error() {
+        let result = build_pool(&entry("Serum", Pubkey::new_unique(), Pubkey::new_unique()));
+        assert!(matches!(result, Err(RouterError::ConfigError(_))));
+
+        let mut orca = entry("Orca", Pubkey::new_unique(), Pubkey::new_unique());
+        orca.pool_type = Some("hybrid".to_string());
+        assert!(matches!(build_pool(&orca), Err(RouterError::ConfigError(_))));
+
+        let phoenix = entry("Phoenix", Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(matches!(build_pool(&phoenix), Err(RouterError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_fee_over_100_percent_is_config_error() {
+        let mut stable = entry("Meteora", Pubkey::new_unique(), Pubkey::new_unique());
+        stable.pool_type = Some("stable".to_string());
+        stable.amp = Some(100);
+        let mut phoenix = entry("Phoenix", Pubkey::new_unique(), Pubkey::new_unique());
+        phoenix.best_bid = Some(1);
+        phoenix.best_ask = Some(2);
+
+        let entries = [
+            entry("Raydium", Pubkey::new_unique(), Pubkey::new_unique()),
+            entry("Orca", Pubkey::new_unique(), Pubkey::new_unique()),
+            entry("Meteora", Pubkey::new_unique(), Pubkey::new_unique()),
+            stable,
+            phoenix,
+        ];
+        for mut entry in entries {
+            entry.fee_bps = Some(10_001);
+            assert!(
+                matches!(build_pool(&entry), Err(RouterError::ConfigError(_))),
+                "{} accepted a fee over 10000 bps",
+                entry.dex
+            );
+
+            // 100% is the largest valid fee
+            entry.fee_bps = Some(10_000);
+            assert!(build_pool(&entry).is_ok());
+        }
+    }
+}
@@ -1,16 +1,46 @@
 //! Meteora pool implementation
 //!
 //! Meteora offers dynamic pools with multiple pool types
+//!
+//! DLMM pools hold liquidity in discrete price bins. Bin `id` trades at
+//! `(1 + bin_step)^id` token B per token A; bins at or below the active bin
+//! hold token B and bins above it hold token A. A swap drains bins outward from
+//! the active bin, so each bin crossed fills at a worse price.
 
-use crate::calculator::{calculate_amount_out, calculate_price_impact};
+use crate::calculator::{calculate_amount_out, calculate_price_impact, calculate_stable_amount_out};
 use crate::error::{Result, RouterError};
 use crate::types::pool::{Pool, PoolInfo};
 use solana_sdk::pubkey::Pubkey;
+use std::any::Any;
+use std::str::FromStr;
+use std::time::Instant;
+
+/// Meteora Dynamic AMM program ID (constant product and stable pools)
+pub const METEORA_DYNAMIC_AMM_PROGRAM: &str = "Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB";
+
+/// Meteora DLMM program ID
+pub const METEORA_DLMM_PROGRAM: &str = "LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo";
 
 /// Meteora pool implementation
 #[derive(Debug, Clone)]
 pub struct MeteoraPool {
     info: PoolInfo,
+    pool_type: MeteoraPoolType,
+}
+
+#[derive(Debug, Clone)]
+pub enum MeteoraPoolType {
+    /// Dynamic AMM (constant product)
+    Dynamic,
+    /// Stable pool for correlated assets (StableSwap with amplification coefficient)
+    Stable { amp: u64 },
+    /// Dynamic liquidity market maker: `(bin id, liquidity)` pairs sorted by bin id,
+    /// with liquidity in the token the bin holds
+    Dlmm {
+        active_bin_id: i32,
+        bin_step_bps: u16,
+        bins: Vec<(i32, u64)>,
+    },
 }
 
 impl MeteoraPool {
@@ -33,7 +63,152 @@ impl MeteoraPool {
                 reserve_b,
                 fee_bps,
             ),
+            pool_type: MeteoraPoolType::Dynamic,
+        }
+    }
+
+    /// Create a new Meteora stable pool
+    pub fn new_stable(
+        address: Pubkey,
+        token_a: Pubkey,
+        token_b: Pubkey,
+        reserve_a: u64,
+        reserve_b: u64,
+        fee_bps: u16,
+        amp: u64,
+    ) -> Self {
+        let mut pool = Self::new(address, token_a, token_b, reserve_a, reserve_b, fee_bps);
+        pool.pool_type = MeteoraPoolType::Stable { amp };
+        pool
+    }
+
+    /// Create a new Meteora DLMM pool
+    ///
+    /// Reserves are the totals of the token A bins (above `active_bin_id`) and
+    /// token B bins (at or below it).
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_dlmm(
+        address: Pubkey,
+        token_a: Pubkey,
+        token_b: Pubkey,
+        fee_bps: u16,
+        active_bin_id: i32,
+        bin_step_bps: u16,
+        mut bins: Vec<(i32, u64)>,
+    ) -> Self {
+        bins.sort_by_key(|(bin_id, _)| *bin_id);
+        let side_total = |token_a_side: bool| {
+            bins.iter()
+                .filter(|(bin_id, _)| (*bin_id > active_bin_id) == token_a_side)
+                .fold(0u64, |total, (_, liquidity)| total.saturating_add(*liquidity))
+        };
+        let (reserve_a, reserve_b) = (side_total(true), side_total(false));
+
+        let mut pool = Self::new(address, token_a, token_b, reserve_a, reserve_b, fee_bps);
+        pool.pool_type = MeteoraPoolType::Dlmm {
+            active_bin_id,
+            bin_step_bps,
+            bins,
+        };
+        pool
+    }
+
+    pub fn pool_type(&self) -> &MeteoraPoolType {
+        &self.pool_type
+    }
+
+    /// Quote a stable-pool swap
+    ///
+    /// Price impact is measured against the marginal price of a tiny fee-free
+    /// trade, since the spot price of a stable pool is not the reserve ratio.
+    fn calculate_stable_output(
+        &self,
+        input_amount: u64,
+        a_to_b: bool,
+        amp: u64,
+    ) -> Result<(u64, u16)> {
+        let (reserve_in, reserve_out) = self.info.get_reserves(a_to_b);
+
+        let output_amount = calculate_stable_amount_out(
+            input_amount,
+            reserve_in,
+            reserve_out,
+            amp,
+            self.fee_bps(),
+        )?;
+
+        if input_amount == 0 {
+            return Ok((0, 0));
+        }
+
+        let reference_in = (reserve_in / 10_000).max(1);
+        let reference_out =
+            calculate_stable_amount_out(reference_in, reserve_in, reserve_out, amp, 0)?;
+
+        let price_impact = if reference_out == 0 {
+            0
+        } else {
+            let price_ratio = (output_amount as u128 * reference_in as u128 * 10000)
+                / (input_amount as u128 * reference_out as u128);
+            10000u128.saturating_sub(price_ratio) as u16
+        };
+
+        Ok((output_amount, price_impact))
+    }
+
+    /// Quote a DLMM swap by draining bins outward from the active bin
+    ///
+    /// A→B sells token A into the token B bins at and below the active bin;
+    /// B→A buys from the token A bins above it. Input left over once the bins
+    /// run dry is not swapped, so the output is capped at that side's liquidity.
+    /// Price impact is measured against the active bin's price.
+    fn calculate_dlmm_output(
+        &self,
+        input_amount: u64,
+        a_to_b: bool,
+        active_bin_id: i32,
+        bin_step_bps: u16,
+        bins: &[(i32, u64)],
+    ) -> Result<(u64, u16)> {
+        if input_amount == 0 {
+            return Ok((0, 0));
+        }
+
+        // Output per unit of input when filling at a bin
+        let rate = |bin_id: i32| {
+            let price = bin_price(bin_id, bin_step_bps);
+            if a_to_b { price } else { 1.0 / price }
+        };
+        let crossed: Vec<(i32, u64)> = if a_to_b {
+            bins.iter().rev().filter(|(id, _)| *id <= active_bin_id).copied().collect()
+        } else {
+            bins.iter().filter(|(id, _)| *id > active_bin_id).copied().collect()
+        };
+
+        let fee_bps = self.fee_bps().min(10000);
+        let mut remaining = input_amount as f64 * (10000 - fee_bps) as f64 / 10000.0;
+        let mut output_amount = 0u64;
+
+        for (bin_id, liquidity) in crossed {
+            let bin_rate = rate(bin_id);
+            let bin_output = (remaining * bin_rate).floor();
+            if bin_output < liquidity as f64 {
+                output_amount = output_amount.saturating_add(bin_output as u64);
+                break;
+            }
+
+            output_amount = output_amount.saturating_add(liquidity);
+            remaining -= liquidity as f64 / bin_rate;
         }
+
+        let spot_output = input_amount as f64 * rate(active_bin_id);
+        let price_impact = if spot_output <= 0.0 {
+            0
+        } else {
+            ((1.0 - output_amount as f64 / spot_output) * 10000.0).clamp(0.0, 10000.0) as u16
+        };
+
+        Ok((output_amount, price_impact))
     }
 
     /// Parse Meteora pool account data
@@ -43,6 +218,16 @@ impl MeteoraPool {
             "Meteora pool parsing not yet implemented - use new() for testing".to_string(),
         ))
     }
+
+    /// Get the Meteora Dynamic AMM program ID
+    pub fn dynamic_amm_program_id() -> Pubkey {
+        Pubkey::from_str(METEORA_DYNAMIC_AMM_PROGRAM).unwrap()
+    }
+
+    /// Get the Meteora DLMM program ID
+    pub fn dlmm_program_id() -> Pubkey {
+        Pubkey::from_str(METEORA_DLMM_PROGRAM).unwrap()
+    }
 }
 
 impl Pool for MeteoraPool {
@@ -75,6 +260,26 @@ impl Pool for MeteoraPool {
     }
 
     fn calculate_output(&self, input_amount: u64, a_to_b: bool) -> Result<(u64, u16)> {
+        match &self.pool_type {
+            MeteoraPoolType::Stable { amp } => {
+                return self.calculate_stable_output(input_amount, a_to_b, *amp);
+            }
+            MeteoraPoolType::Dlmm {
+                active_bin_id,
+                bin_step_bps,
+                bins,
+            } => {
+                return self.calculate_dlmm_output(
+                    input_amount,
+                    a_to_b,
+                    *active_bin_id,
+                    *bin_step_bps,
+                    bins,
+                );
+            }
+            MeteoraPoolType::Dynamic => {}
+        }
+
         let (reserve_in, reserve_out) = self.info.get_reserves(a_to_b);
 
         let output_amount = calculate_amount_out(
@@ -106,6 +311,54 @@ impl Pool for MeteoraPool {
             Err(_) => false,
         }
     }
+
+    fn update_reserves(&mut self, reserve_a: u64, reserve_b: u64) -> Result<()> {
+        self.info.set_reserves(reserve_a, reserve_b)
+    }
+
+    fn last_updated(&self) -> Option<Instant> {
+        self.info.last_updated
+    }
+
+    fn clone_box(&self) -> Box<dyn Pool> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn program_id(&self) -> Option<Pubkey> {
+        Some(match self.pool_type {
+            MeteoraPoolType::Dynamic | MeteoraPoolType::Stable { .. } => {
+                Self::dynamic_amm_program_id()
+            }
+            MeteoraPoolType::Dlmm { .. } => Self::dlmm_program_id(),
+        })
+    }
+
+    fn mid_price(&self, a_to_b: bool) -> f64 {
+        if let MeteoraPoolType::Dlmm {
+            active_bin_id,
+            bin_step_bps,
+            ..
+        } = self.pool_type
+        {
+            let price = bin_price(active_bin_id, bin_step_bps);
+            return if a_to_b { price } else { 1.0 / price };
+        }
+
+        let (reserve_in, reserve_out) = self.info.get_reserves(a_to_b);
+        if reserve_in == 0 {
+            return 0.0;
+        }
+        reserve_out as f64 / reserve_in as f64
+    }
+}
+
+/// Price of a DLMM bin in token B per token A: `(1 + bin_step)^bin_id`
+fn bin_price(bin_id: i32, bin_step_bps: u16) -> f64 {
+    (1.0 + bin_step_bps as f64 / 10000.0).powi(bin_id)
 }
 
 #[cfg(test)]
@@ -144,4 +397,119 @@ mod tests {
         assert!(output > 0);
         assert!(price_impact < 100);
     }
+
+    #[test]
+    fn test_meteora_stable_pool_near_parity() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        let stable = MeteoraPool::new_stable(
+            Pubkey::new_unique(),
+            token_a,
+            token_b,
+            1_000_000_000_000,
+            1_000_000_000_000,
+            4,
+            100,
+        );
+        let dynamic = MeteoraPool::new(
+            Pubkey::new_unique(),
+            token_a,
+            token_b,
+            1_000_000_000_000,
+            1_000_000_000_000,
+            4,
+        );
+
+        assert!(matches!(stable.pool_type(), MeteoraPoolType::Stable { amp: 100 }));
+        assert!(matches!(dynamic.pool_type(), MeteoraPoolType::Dynamic));
+
+        // 1% of the pool
+        let input = 10_000_000_000;
+        let (stable_out, stable_impact) = stable.calculate_output(input, true).unwrap();
+        let (dynamic_out, dynamic_impact) = dynamic.calculate_output(input, true).unwrap();
+
+        let stable_deviation = input.abs_diff(stable_out);
+        let dynamic_deviation = input.abs_diff(dynamic_out);
+
+        assert!(stable_deviation * 10 < dynamic_deviation);
+        assert!(stable_impact < dynamic_impact);
+    }
+
+    fn create_dlmm_pool() -> MeteoraPool {
+        // 1% bins, 1000 units in each of two token A bins and three token B bins
+        MeteoraPool::new_dlmm(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            0,
+            0,
+            100,
+            vec![(2, 1000), (-2, 1000), (1, 1000), (0, 1000), (-1, 1000)],
+        )
+    }
+
+    #[test]
+    fn test_dlmm_swap_spans_bins() {
+        let pool = create_dlmm_pool();
+        assert_eq!(pool.reserve_a(), 2000);
+        assert_eq!(pool.reserve_b(), 3000);
+        assert_eq!(pool.mid_price(true), 1.0);
+
+        // Bin 0 fills 1000 at 1.0, bin -1 fills 1000 for 1010 at 1/1.01, and bin -2
+        // fills the last 490 at 1/1.0201
+        let (output, impact) = pool.calculate_output(2500, true).unwrap();
+        assert_eq!(output, 1000 + 1000 + 480);
+        assert_eq!(impact, 80);
+
+        // Buying token A starts at bin 1, priced above the active bin
+        let (output, _) = pool.calculate_output(2000, false).unwrap();
+        assert_eq!(output, 1000 + 970);
+    }
+
+    #[test]
+    fn test_dlmm_price_degrades_per_bin() {
+        let pool = create_dlmm_pool();
+
+        let average_price = |input: u64| {
+            let (output, _) = pool.calculate_output(input, true).unwrap();
+            output as f64 / input as f64
+        };
+        let within_active = average_price(500);
+        let one_crossed = average_price(1500);
+        let two_crossed = average_price(2500);
+
+        assert_eq!(within_active, 1.0);
+        assert!(one_crossed < within_active);
+        assert!(two_crossed < one_crossed);
+    }
+
+    #[test]
+    fn test_dlmm_output_capped_when_bins_exhausted() {
+        let pool = create_dlmm_pool();
+
+        let (output, impact) = pool.calculate_output(1_000_000, true).unwrap();
+        assert_eq!(output, pool.reserve_b());
+        assert!(impact > 9000);
+    }
+
+    #[test]
+    fn test_meteora_update_reserves() {
+        let mut pool = MeteoraPool::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+            20,
+        );
+
+        pool.update_reserves(2_000_000_000, 50_000_000_000).unwrap();
+
+        let (output, _) = pool.calculate_output(1_000_000, true).unwrap();
+        assert_eq!(
+            output,
+            calculate_amount_out(1_000_000, 2_000_000_000, 50_000_000_000, 20).unwrap()
+        );
+    }
 }
@@ -1,10 +1,143 @@
 pub mod raydium;
 pub mod orca;
 pub mod meteora;
+pub mod orderbook;
 pub mod phoenix;
+pub mod openbook;
+pub mod lifinity;
+pub mod weighted;
+pub mod loader;
 
 // Re-export pool implementations
 pub use raydium::RaydiumPool;
 pub use orca::OrcaPool;
 pub use meteora::MeteoraPool;
 pub use phoenix::PhoenixPool;
+pub use openbook::OpenBookMarket;
+pub use lifinity::LifinityPool;
+pub use weighted::WeightedPool;
+pub use loader::load_pools_from_file;
+
+use crate::types::pool::Pool;
+
+/// The Phoenix market behind `pool`, if it is one
+pub fn downcast_phoenix(pool: &dyn Pool) -> Option<&PhoenixPool> {
+    pool.as_any().downcast_ref::<PhoenixPool>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_downcast_boxed_phoenix_pool() {
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                1_000_000_000,
+                50_000_000_000,
+            )),
+            Box::new(PhoenixPool::new(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                1_000_000_000,
+                50_000_000_000,
+                49_000_000,
+                51_000_000,
+            )),
+        ];
+
+        assert!(downcast_phoenix(pools[0].as_ref()).is_none());
+        let phoenix = downcast_phoenix(pools[1].as_ref()).unwrap();
+        assert_eq!(phoenix.best_bid(), 49_000_000);
+        assert!(pools[0].as_any().downcast_ref::<RaydiumPool>().is_some());
+    }
+
+    #[test]
+    fn test_pool_program_ids() {
+        let key = Pubkey::new_unique;
+        let cases: Vec<(Box<dyn Pool>, &str)> = vec![
+            (
+                Box::new(RaydiumPool::new(key(), key(), key(), 1_000, 1_000)),
+                raydium::RAYDIUM_AMM_PROGRAM,
+            ),
+            (
+                Box::new(OrcaPool::new_constant_product(key(), key(), key(), 1_000, 1_000)),
+                orca::ORCA_TOKEN_SWAP_PROGRAM,
+            ),
+            (
+                Box::new(OrcaPool::new_whirlpool(key(), key(), key(), 1_000, 1_000, 30).unwrap()),
+                orca::ORCA_WHIRLPOOL_PROGRAM,
+            ),
+            (
+                Box::new(MeteoraPool::new(key(), key(), key(), 1_000, 1_000, 25)),
+                meteora::METEORA_DYNAMIC_AMM_PROGRAM,
+            ),
+            (
+                Box::new(MeteoraPool::new_stable(key(), key(), key(), 1_000, 1_000, 4, 100)),
+                meteora::METEORA_DYNAMIC_AMM_PROGRAM,
+            ),
+            (
+                Box::new(MeteoraPool::new_dlmm(key(), key(), key(), 25, 0, 10, vec![(0, 1_000)])),
+                meteora::METEORA_DLMM_PROGRAM,
+            ),
+            (
+                Box::new(PhoenixPool::new(key(), key(), key(), 1_000, 1_000, 99, 101)),
+                phoenix::PHOENIX_PROGRAM,
+            ),
+            (
+                Box::new(OpenBookMarket::new(key(), key(), key(), 1_000, 1_000, 99, 101)),
+                openbook::OPENBOOK_V2_PROGRAM,
+            ),
+            (
+                Box::new(LifinityPool::new(key(), key(), key(), 1_000, 1_000, 30, 1.0, 10)),
+                lifinity::LIFINITY_V2_PROGRAM,
+            ),
+        ];
+
+        for (pool, expected) in cases {
+            let program_id = pool.program_id().expect("executable pool");
+            assert_eq!(program_id.to_string(), expected, "{}", pool.dex_name());
+        }
+    }
+
+    #[test]
+    fn test_reserves_and_constant_product_k() {
+        let key = Pubkey::new_unique;
+        let amm_pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(key(), key(), key(), 2_000, 3_000)),
+            Box::new(OrcaPool::new_constant_product(key(), key(), key(), 2_000, 3_000)),
+            Box::new(OrcaPool::new_whirlpool(key(), key(), key(), 2_000, 3_000, 30).unwrap()),
+            Box::new(MeteoraPool::new(key(), key(), key(), 2_000, 3_000, 25)),
+            Box::new(MeteoraPool::new_stable(key(), key(), key(), 2_000, 3_000, 4, 100)),
+            Box::new(LifinityPool::new(key(), key(), key(), 2_000, 3_000, 30, 1.0, 10)),
+            Box::new(
+                WeightedPool::new(key(), key(), key(), 2_000, 3_000, 8_000, 2_000, 30).unwrap(),
+            ),
+        ];
+        for pool in &amm_pools {
+            assert_eq!(pool.reserves(), (2_000, 3_000), "{}", pool.dex_name());
+            assert_eq!(pool.constant_product_k(), 6_000_000, "{}", pool.dex_name());
+        }
+
+        let dlmm = MeteoraPool::new_dlmm(key(), key(), key(), 25, 0, 10, vec![(0, 1_000)]);
+        assert_eq!(dlmm.reserves(), (dlmm.reserve_a(), dlmm.reserve_b()));
+
+        let orderbooks: Vec<Box<dyn Pool>> = vec![
+            Box::new(PhoenixPool::new(key(), key(), key(), 2_000, 3_000, 99, 101)),
+            Box::new(OpenBookMarket::new(key(), key(), key(), 2_000, 3_000, 99, 101)),
+        ];
+        for pool in &orderbooks {
+            assert_eq!(pool.reserves(), (2_000, 3_000), "{}", pool.dex_name());
+            assert_eq!(pool.constant_product_k(), 0, "{}", pool.dex_name());
+        }
+
+        // Large reserves don't overflow
+        let deep = RaydiumPool::new(key(), key(), key(), u64::MAX, u64::MAX);
+        assert_eq!(deep.constant_product_k(), u64::MAX as u128 * u64::MAX as u128);
+    }
+}
@@ -0,0 +1,111 @@
+//! OpenBook market implementation
+//!
+//! OpenBook (the community fork of Serum) is an orderbook-based DEX; like
+//! Phoenix, swaps are priced by the shared orderbook adapter in `dex::orderbook`
+
+use crate::dex::orderbook::{OrderbookPool, Venue};
+use crate::error::{Result, RouterError};
+use solana_sdk::pubkey::Pubkey;
+
+/// OpenBook v2 program ID
+pub const OPENBOOK_V2_PROGRAM: &str = "opnb2LAfJYbRMAHHvqjCwQxanZn7ReEHp1k81EohpZb";
+
+/// The OpenBook v2 DEX
+#[derive(Debug, Clone)]
+pub struct OpenBook;
+
+impl Venue for OpenBook {
+    const DEX_NAME: &'static str = "OpenBook";
+    const PROGRAM_ID: &'static str = OPENBOOK_V2_PROGRAM;
+}
+
+/// OpenBook market adapter
+pub type OpenBookMarket = OrderbookPool<OpenBook>;
+
+impl OpenBookMarket {
+    /// Parse OpenBook v2 market account data
+    pub fn from_account_data(_address: Pubkey, _data: &[u8]) -> Result<Self> {
+        // TODO: Implement actual OpenBook market and bookside parsing
+        Err(RouterError::PoolParseError(
+            "OpenBook market parsing not yet implemented - use new() for testing".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::orderbook::PRICE_SCALE;
+    use crate::types::pool::Pool;
+
+    #[test]
+    fn test_openbook_market_creation() {
+        let market = OpenBookMarket::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+            49_500_000, // best bid (49.5 per unit)
+            50_500_000, // best ask (50.5 per unit)
+        );
+
+        assert_eq!(market.dex_name(), "OpenBook");
+        assert_eq!(market.best_bid(), 49_500_000);
+        assert_eq!(market.best_ask(), 50_500_000);
+    }
+
+    #[test]
+    fn test_openbook_spread() {
+        let market = OpenBookMarket::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+            49_000_000, // bid
+            51_000_000, // ask
+        );
+
+        // Spread = (51 - 49) / 49 * 10000 ≈ 408 bps, charged as the fee
+        let spread = market.spread_bps();
+        assert!(spread > 400 && spread < 420);
+        assert_eq!(market.fee_bps(), spread);
+    }
+
+    #[test]
+    fn test_openbook_output_on_swept_book() {
+        let market = OpenBookMarket::new_with_levels(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            vec![(49_000_000, 10_000_000), (50_000_000, 1_000_000)],
+            vec![(51_000_000, 1_000_000), (52_000_000, 10_000_000)],
+        );
+
+        // Selling 2 A fills the top bid and sweeps into the next level
+        let input = 2_000_000;
+        let (output, price_impact) = market.calculate_output(input, true).unwrap();
+        assert_eq!(output, 50_000_000 + 49_000_000);
+        assert_eq!(price_impact, 200);
+        assert!(output as u128 * PRICE_SCALE / (input as u128) < market.best_bid() as u128);
+
+        // Buying 2 A sweeps both ask levels
+        let input = 51_000_000 + 52_000_000;
+        let (output, price_impact) = market.calculate_output(input, false).unwrap();
+        assert_eq!(output, 2_000_000);
+        assert!(price_impact > 0);
+
+        // More than the whole book can fill
+        assert!(market.calculate_output(20_000_000, true).is_err());
+    }
+
+    #[test]
+    fn test_openbook_program_id_and_parse_stub() {
+        let program_id = OpenBookMarket::program_id();
+        assert_eq!(program_id.to_string(), OPENBOOK_V2_PROGRAM);
+
+        let result = OpenBookMarket::from_account_data(Pubkey::new_unique(), &[0u8; 64]);
+        assert!(matches!(result, Err(RouterError::PoolParseError(_))));
+    }
+}
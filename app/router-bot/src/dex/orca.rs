@@ -2,20 +2,35 @@
 //!
 //! Orca supports both constant product and concentrated liquidity pools
 
-use crate::calculator::{calculate_amount_out, calculate_price_impact};
+use crate::calculator::{RoundingMode, calculate_amount_out, calculate_price_impact, mul_div};
 use crate::error::{Result, RouterError};
 use crate::types::pool::{Pool, PoolInfo};
 use solana_sdk::pubkey::Pubkey;
+use std::any::Any;
+use std::time::Instant;
 use std::str::FromStr;
 
 /// Orca Whirlpool program ID (concentrated liquidity)
 pub const ORCA_WHIRLPOOL_PROGRAM: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
 
+/// Orca Token Swap v2 program ID (constant product)
+pub const ORCA_TOKEN_SWAP_PROGRAM: &str = "9W959DqEETiGZocYWCQPaJ6sBmUzgfxXfqGeTEdp3aQP";
+
+/// 1.0 as a Q64.64 fixed-point number, the scale of sqrt prices
+const Q64: u128 = 1 << 64;
+
 /// Orca pool implementation
 #[derive(Debug, Clone)]
 pub struct OrcaPool {
     info: PoolInfo,
     pool_type: OrcaPoolType,
+    /// Current sqrt price as Q64.64 (0 if unknown)
+    sqrt_price_x64: u128,
+    /// Liquidity in the active range (0 if unknown)
+    liquidity: u128,
+    /// Sqrt price bounds of the active range as Q64.64 (0 if unknown)
+    sqrt_price_lower_x64: u128,
+    sqrt_price_upper_x64: u128,
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +43,8 @@ pub enum OrcaPoolType {
 
 impl OrcaPool {
     /// Create a new Orca pool
+    ///
+    /// Returns `RouterError::ConfigError` if `fee_bps` exceeds 10000 (100%).
     pub fn new(
         address: Pubkey,
         token_a: Pubkey,
@@ -36,8 +53,15 @@ impl OrcaPool {
         reserve_b: u64,
         pool_type: OrcaPoolType,
         fee_bps: u16,
-    ) -> Self {
-        Self {
+    ) -> Result<Self> {
+        if fee_bps > 10000 {
+            return Err(RouterError::ConfigError(format!(
+                "Invalid Orca fee: {} bps exceeds 10000",
+                fee_bps
+            )));
+        }
+
+        Ok(Self {
             info: PoolInfo::new(
                 address,
                 "Orca".to_string(),
@@ -48,7 +72,11 @@ impl OrcaPool {
                 fee_bps,
             ),
             pool_type,
-        }
+            sqrt_price_x64: 0,
+            liquidity: 0,
+            sqrt_price_lower_x64: 0,
+            sqrt_price_upper_x64: 0,
+        })
     }
 
     /// Create a new Orca constant product pool with default 0.3% fee
@@ -68,9 +96,14 @@ impl OrcaPool {
             OrcaPoolType::ConstantProduct,
             30, // 0.3% fee
         )
+        .expect("default Orca fee is a valid fee tier")
     }
 
     /// Create a new Orca Whirlpool (concentrated liquidity)
+    ///
+    /// Without range information the pool behaves as a full-range position,
+    /// which is equivalent to constant product on the given reserves.
+    /// Returns `RouterError::ConfigError` if `fee_bps` exceeds 10000 (100%).
     pub fn new_whirlpool(
         address: Pubkey,
         token_a: Pubkey,
@@ -78,7 +111,7 @@ impl OrcaPool {
         reserve_a: u64,
         reserve_b: u64,
         fee_bps: u16,
-    ) -> Self {
+    ) -> Result<Self> {
         Self::new(
             address,
             token_a,
@@ -90,6 +123,43 @@ impl OrcaPool {
         )
     }
 
+    /// Create a new Orca Whirlpool with its active-range state
+    ///
+    /// Reserves are the real token amounts available inside the active range;
+    /// `sqrt_price_x64` is the current sqrt price (token B per token A) as Q64.64
+    /// and `liquidity` is the liquidity `L` of the active range.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_whirlpool_with_liquidity(
+        address: Pubkey,
+        token_a: Pubkey,
+        token_b: Pubkey,
+        reserve_a: u64,
+        reserve_b: u64,
+        fee_bps: u16,
+        sqrt_price_x64: u128,
+        liquidity: u128,
+    ) -> Result<Self> {
+        let mut pool =
+            Self::new_whirlpool(address, token_a, token_b, reserve_a, reserve_b, fee_bps)?;
+        pool.sqrt_price_x64 = sqrt_price_x64;
+        pool.liquidity = liquidity;
+        Ok(pool)
+    }
+
+    /// Bound the active range by its lower and upper sqrt prices (Q64.64)
+    ///
+    /// The reserves may then be the pool's nominal balances: swaps can only use
+    /// what the range between the bounds holds at the current liquidity.
+    pub fn with_active_range(
+        mut self,
+        sqrt_price_lower_x64: u128,
+        sqrt_price_upper_x64: u128,
+    ) -> Self {
+        self.sqrt_price_lower_x64 = sqrt_price_lower_x64;
+        self.sqrt_price_upper_x64 = sqrt_price_upper_x64;
+        self
+    }
+
     /// Parse Orca pool account data
     pub fn from_account_data(_address: Pubkey, _data: &[u8]) -> Result<Self> {
         // TODO: Implement actual Orca account parsing
@@ -103,9 +173,120 @@ impl OrcaPool {
         Pubkey::from_str(ORCA_WHIRLPOOL_PROGRAM).unwrap()
     }
 
+    /// Get the Orca Token Swap v2 program ID
+    pub fn token_swap_program_id() -> Pubkey {
+        Pubkey::from_str(ORCA_TOKEN_SWAP_PROGRAM).unwrap()
+    }
+
     pub fn pool_type(&self) -> &OrcaPoolType {
         &self.pool_type
     }
+
+    pub fn sqrt_price_x64(&self) -> u128 {
+        self.sqrt_price_x64
+    }
+
+    pub fn liquidity(&self) -> u128 {
+        self.liquidity
+    }
+
+    /// Whether concentrated-liquidity math applies to this pool
+    fn uses_concentrated_math(&self) -> bool {
+        matches!(self.pool_type, OrcaPoolType::ConcentratedLiquidity)
+            && self.sqrt_price_x64 > 0
+            && self.liquidity > 0
+    }
+
+    /// Most output the active range can provide in the given direction
+    ///
+    /// A to B can move the sqrt price down to the lower bound, releasing
+    /// `L * (sqrt_p - sqrt_lower)` of token B; B to A can move it up to the upper
+    /// bound, releasing `L * (1 / sqrt_p - 1 / sqrt_upper)` of token A. Without
+    /// known bounds (or beyond the output reserve) the output reserve is the limit.
+    fn in_range_output_capacity(&self, a_to_b: bool) -> u64 {
+        let (_, reserve_out) = self.info.get_reserves(a_to_b);
+        let (sqrt_price, liquidity) = (self.sqrt_price_x64, self.liquidity);
+
+        let range_capacity = if a_to_b && self.sqrt_price_lower_x64 > 0 {
+            let sqrt_lower = self.sqrt_price_lower_x64;
+            mul_div(liquidity, sqrt_price.saturating_sub(sqrt_lower), Q64, RoundingMode::Down)
+        } else if !a_to_b && self.sqrt_price_upper_x64 > 0 {
+            // L * (1 / sqrt_p - 1 / sqrt_upper) = L * (sqrt_upper - sqrt_p) / (sqrt_p * sqrt_upper)
+            let sqrt_upper = self.sqrt_price_upper_x64;
+            let delta = sqrt_upper.saturating_sub(sqrt_price);
+            mul_div(liquidity, delta, sqrt_upper, RoundingMode::Down)
+                .and_then(|scaled| mul_div(scaled, Q64, sqrt_price, RoundingMode::Down))
+        } else {
+            return reserve_out;
+        };
+
+        // A capacity beyond u128 is beyond the reserve too
+        range_capacity.map_or(reserve_out, |capacity| capacity.min(reserve_out as u128) as u64)
+    }
+
+    /// Swap within the active range using concentrated-liquidity math
+    ///
+    /// A to B moves the sqrt price down: `sqrt_p' = L * sqrt_p / (L + dx * sqrt_p)`,
+    /// `dy = L * (sqrt_p - sqrt_p')`. B to A moves it up: `sqrt_p' = sqrt_p + dy / L`,
+    /// `dx = L * (1 / sqrt_p - 1 / sqrt_p')`. The math is done in Q64.64 fixed
+    /// point, rounding every step in the pool's favour. Output is capped at what
+    /// the active range holds (see `in_range_output_capacity`), and the price
+    /// impact reflects the capped amount.
+    fn calculate_concentrated_output(&self, input_amount: u64, a_to_b: bool) -> Result<(u64, u16)> {
+        let (_, reserve_out) = self.info.get_reserves(a_to_b);
+
+        if reserve_out == 0 {
+            return Err(RouterError::InvalidReserves);
+        }
+
+        if input_amount == 0 {
+            return Ok((0, 0));
+        }
+
+        let (sqrt_price, liquidity) = (self.sqrt_price_x64, self.liquidity);
+        let amount_in = input_amount as u128 * (10000 - self.fee_bps()) as u128 / 10000;
+
+        let output = if a_to_b {
+            let scaled_input = mul_div(amount_in, sqrt_price, Q64, RoundingMode::Down)?;
+            let denominator = liquidity
+                .checked_add(scaled_input)
+                .ok_or(RouterError::MathOverflow)?;
+            let next_sqrt_price = mul_div(liquidity, sqrt_price, denominator, RoundingMode::Up)?;
+            mul_div(liquidity, sqrt_price - next_sqrt_price, Q64, RoundingMode::Down)?
+        } else {
+            let next_sqrt_price = sqrt_price
+                .checked_add(mul_div(amount_in, Q64, liquidity, RoundingMode::Down)?)
+                .ok_or(RouterError::MathOverflow)?;
+            let scaled = mul_div(
+                liquidity,
+                next_sqrt_price - sqrt_price,
+                next_sqrt_price,
+                RoundingMode::Down,
+            )?;
+            mul_div(scaled, Q64, sqrt_price, RoundingMode::Down)?
+        };
+
+        // Crossing out of the active range: fill what the range holds
+        let output = output.min(self.in_range_output_capacity(a_to_b) as u128);
+        let output_amount = output as u64;
+
+        // What the whole input, fee included, would buy at the current price
+        let spot_output = if a_to_b {
+            let half = mul_div(input_amount as u128, sqrt_price, Q64, RoundingMode::Down)?;
+            mul_div(half, sqrt_price, Q64, RoundingMode::Down)?
+        } else {
+            let half = mul_div(input_amount as u128, Q64, sqrt_price, RoundingMode::Down)?;
+            mul_div(half, Q64, sqrt_price, RoundingMode::Down)?
+        };
+        let price_impact = if spot_output == 0 {
+            0
+        } else {
+            let shortfall = spot_output.saturating_sub(output);
+            mul_div(shortfall, 10000, spot_output, RoundingMode::Down)? as u16
+        };
+
+        Ok((output_amount, price_impact))
+    }
 }
 
 impl Pool for OrcaPool {
@@ -138,10 +319,13 @@ impl Pool for OrcaPool {
     }
 
     fn calculate_output(&self, input_amount: u64, a_to_b: bool) -> Result<(u64, u16)> {
+        if self.uses_concentrated_math() {
+            return self.calculate_concentrated_output(input_amount, a_to_b);
+        }
+
         let (reserve_in, reserve_out) = self.info.get_reserves(a_to_b);
 
-        // For concentrated liquidity, we'd use a different formula
-        // For now, we'll use constant product for both types
+        // Constant product (also a full-range whirlpool without range state)
         let output_amount = calculate_amount_out(
             input_amount,
             reserve_in,
@@ -165,12 +349,55 @@ impl Pool for OrcaPool {
     }
 
     fn has_sufficient_liquidity(&self, input_amount: u64, a_to_b: bool) -> bool {
-        let (_, reserve_out) = self.info.get_reserves(a_to_b);
+        // Only the active range's liquidity is usable by a whirlpool swap
+        let available = if self.uses_concentrated_math() {
+            self.in_range_output_capacity(a_to_b)
+        } else {
+            self.info.get_reserves(a_to_b).1
+        };
         match self.calculate_output(input_amount, a_to_b) {
-            Ok((output, _)) => output < reserve_out / 2,
+            Ok((output, _)) => output < available / 2,
             Err(_) => false,
         }
     }
+
+    fn update_reserves(&mut self, reserve_a: u64, reserve_b: u64) -> Result<()> {
+        self.info.set_reserves(reserve_a, reserve_b)
+    }
+
+    fn last_updated(&self) -> Option<Instant> {
+        self.info.last_updated
+    }
+
+    fn clone_box(&self) -> Box<dyn Pool> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn program_id(&self) -> Option<Pubkey> {
+        Some(match self.pool_type {
+            OrcaPoolType::ConstantProduct => Self::token_swap_program_id(),
+            OrcaPoolType::ConcentratedLiquidity => Self::whirlpool_program_id(),
+        })
+    }
+
+    fn mid_price(&self, a_to_b: bool) -> f64 {
+        if !self.uses_concentrated_math() {
+            let (reserve_in, reserve_out) = self.info.get_reserves(a_to_b);
+            if reserve_in == 0 {
+                return 0.0;
+            }
+            return reserve_out as f64 / reserve_in as f64;
+        }
+
+        // The sqrt price is token B per token A
+        let sqrt_price = self.sqrt_price_x64 as f64 / Q64 as f64;
+        let price = sqrt_price * sqrt_price;
+        if a_to_b { price } else { 1.0 / price }
+    }
 }
 
 #[cfg(test)]
@@ -201,7 +428,8 @@ mod tests {
             1_000_000_000,
             50_000_000_000,
             10, // 0.1% fee
-        );
+        )
+        .unwrap();
 
         assert_eq!(pool.dex_name(), "Orca");
         assert_eq!(pool.fee_bps(), 10);
@@ -242,7 +470,8 @@ mod tests {
             1_000_000_000,
             50_000_000_000,
             10,
-        );
+        )
+        .unwrap();
 
         let input = 1_000_000;
         let (output_high_fee, _) = pool_high_fee.calculate_output(input, true).unwrap();
@@ -257,4 +486,174 @@ mod tests {
         let program_id = OrcaPool::whirlpool_program_id();
         assert_eq!(program_id.to_string(), ORCA_WHIRLPOOL_PROGRAM);
     }
+
+    fn create_concentrated_pool() -> OrcaPool {
+        let reserve_a = 1_000_000_000u64;
+        let reserve_b = 50_000_000_000u64;
+
+        // Price of 50 B per A, with 10x the liquidity of a full-range position
+        let sqrt_price_x64 = (50f64.sqrt() * Q64 as f64) as u128;
+        let liquidity = ((reserve_a as f64 * reserve_b as f64).sqrt() * 10.0) as u128;
+
+        OrcaPool::new_whirlpool_with_liquidity(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            reserve_a,
+            reserve_b,
+            10,
+            sqrt_price_x64,
+            liquidity,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_concentrated_vs_constant_product() {
+        let cl_pool = create_concentrated_pool();
+        let cp_pool = OrcaPool::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+            OrcaPoolType::ConstantProduct,
+            10,
+        )
+        .unwrap();
+
+        let input = 10_000_000;
+        let (cl_output, cl_impact) = cl_pool.calculate_output(input, true).unwrap();
+        let (cp_output, cp_impact) = cp_pool.calculate_output(input, true).unwrap();
+
+        // Concentrated liquidity quotes a tighter price for the same nominal reserves
+        assert_ne!(cl_output, cp_output);
+        assert!(cl_output > cp_output);
+        assert!(cl_impact < cp_impact);
+
+        // Reverse direction uses the same range
+        let (cl_reverse, _) = cl_pool.calculate_output(500_000_000, false).unwrap();
+        let (cp_reverse, _) = cp_pool.calculate_output(500_000_000, false).unwrap();
+        assert!(cl_reverse > cp_reverse);
+    }
+
+    #[test]
+    fn test_concentrated_mid_price_uses_sqrt_price() {
+        let pool = create_concentrated_pool();
+
+        assert!((pool.mid_price(true) - 50.0).abs() < 1e-6);
+        assert!((pool.mid_price(false) - 0.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_concentrated_output_capped_at_range() {
+        let pool = create_concentrated_pool();
+
+        // Large enough to push the price out of the active range
+        let (output, price_impact) = pool.calculate_output(5_000_000_000, true).unwrap();
+        assert_eq!(output, pool.reserve_b());
+
+        // Impact is measured on the capped output: 5e9 A would buy 2.5e11 B at
+        // spot, so receiving the 5e10 reserve is about an 80% shortfall
+        assert!((7990..=8000).contains(&price_impact));
+
+        // Just inside the range the swap fills uncapped
+        let (output, _) = pool.calculate_output(100_000_000, true).unwrap();
+        assert!(output < pool.reserve_b());
+    }
+
+    #[test]
+    fn test_orca_rejects_fee_over_100_percent() {
+        let result = OrcaPool::new_whirlpool(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+            10_001,
+        );
+        assert!(matches!(result, Err(RouterError::ConfigError(_))));
+
+        // 100% is the largest valid fee
+        assert!(OrcaPool::new_whirlpool(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+            10_000,
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_narrow_range_limits_sufficient_liquidity() {
+        // Deep nominal reserves at a price of 1, with a +/-0.1% active range
+        let one = 1u128 << 64;
+        let pool = OrcaPool::new_whirlpool_with_liquidity(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000_000,
+            1_000_000_000_000,
+            10,
+            one,
+            1_000_000_000,
+        )
+        .unwrap();
+        let narrow = pool
+            .clone()
+            .with_active_range((0.999 * Q64 as f64) as u128, (1.001 * Q64 as f64) as u128);
+
+        // The range holds about L * 0.001 = 1,000,000 of either token
+        let large = 10_000_000;
+        assert!(pool.has_sufficient_liquidity(large, true));
+        assert!(!narrow.has_sufficient_liquidity(large, true));
+        assert!(!narrow.has_sufficient_liquidity(large, false));
+
+        let (output, _) = narrow.calculate_output(large, true).unwrap();
+        assert!(output <= 1_000_000);
+        let (output, _) = narrow.calculate_output(large, false).unwrap();
+        assert!(output <= 1_000_000);
+
+        // Small swaps stay inside the range
+        assert!(narrow.has_sufficient_liquidity(100_000, true));
+        assert!(narrow.has_sufficient_liquidity(100_000, false));
+    }
+
+    #[test]
+    fn test_whirlpool_without_range_is_full_range() {
+        let pool = OrcaPool::new_whirlpool(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+            10,
+        )
+        .unwrap();
+
+        let expected = calculate_amount_out(1_000_000, 1_000_000_000, 50_000_000_000, 10).unwrap();
+        let (output, _) = pool.calculate_output(1_000_000, true).unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_orca_update_reserves() {
+        let mut pool = OrcaPool::new_constant_product(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+        );
+
+        pool.update_reserves(500_000_000, 25_000_000_000).unwrap();
+
+        let (output, _) = pool.calculate_output(1_000_000, true).unwrap();
+        assert_eq!(
+            output,
+            calculate_amount_out(1_000_000, 500_000_000, 25_000_000_000, 30).unwrap()
+        );
+    }
 }
@@ -0,0 +1,502 @@
+//! Orderbook market adapter shared by Phoenix and OpenBook
+//!
+//! Orderbook DEXes are not AMMs, so swaps are priced by walking the bid/ask
+//! levels. `OrderbookPool` holds the book and the fill logic; each DEX module
+//! only supplies a `Venue` (its name and program ID) and its account parsing.
+
+use crate::error::{Result, RouterError};
+use crate::types::pool::{Pool, PoolInfo};
+use solana_sdk::pubkey::Pubkey;
+use std::any::Any;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::str::FromStr;
+use std::time::Instant;
+
+/// Fixed-point scale for orderbook prices (token B per token A)
+pub const PRICE_SCALE: u128 = 1_000_000;
+
+/// The orderbook DEX an `OrderbookPool` trades on
+pub trait Venue: Debug + Clone + Send + Sync + 'static {
+    /// Name reported by `Pool::dex_name`
+    const DEX_NAME: &'static str;
+    /// Base58 ID of the program that executes swaps on this DEX
+    const PROGRAM_ID: &'static str;
+}
+
+/// Orderbook market adapter
+/// Note: this is a simplified model of the book, not an on-chain market parser
+#[derive(Debug, Clone)]
+pub struct OrderbookPool<V: Venue> {
+    info: PoolInfo,
+    /// Bid levels as (price, size in token A), best (highest) price first
+    bids: Vec<(u64, u64)>,
+    /// Ask levels as (price, size in token A), best (lowest) price first
+    asks: Vec<(u64, u64)>,
+    venue: PhantomData<V>,
+}
+
+impl<V: Venue> OrderbookPool<V> {
+    /// Create a market adapter
+    ///
+    /// For orderbook markets, reserves represent available liquidity at best prices.
+    /// A single level per side is synthesized from the best bid/ask.
+    pub fn new(
+        address: Pubkey,
+        token_a: Pubkey,
+        token_b: Pubkey,
+        liquidity_a: u64,
+        liquidity_b: u64,
+        best_bid: u64,
+        best_ask: u64,
+    ) -> Self {
+        // Bid size is however much token A the token B liquidity can buy at the bid
+        let bid_size = if best_bid == 0 {
+            0
+        } else {
+            (liquidity_b as u128 * PRICE_SCALE / best_bid as u128).min(u64::MAX as u128) as u64
+        };
+
+        Self {
+            info: PoolInfo::new(
+                address,
+                V::DEX_NAME.to_string(),
+                token_a,
+                token_b,
+                liquidity_a,
+                liquidity_b,
+                0, // No fixed fee, spread is the "fee"
+            ),
+            bids: vec![(best_bid, bid_size)],
+            asks: vec![(best_ask, liquidity_a)],
+            venue: PhantomData,
+        }
+    }
+
+    /// Create a market adapter from full orderbook levels
+    ///
+    /// Each level is (price, size in token A). Reserves are derived from the book:
+    /// token A is the total ask size and token B is the total bid notional.
+    pub fn new_with_levels(
+        address: Pubkey,
+        token_a: Pubkey,
+        token_b: Pubkey,
+        mut bids: Vec<(u64, u64)>,
+        mut asks: Vec<(u64, u64)>,
+    ) -> Self {
+        bids.sort_by(|a, b| b.0.cmp(&a.0));
+        asks.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let liquidity_a = asks
+            .iter()
+            .fold(0u64, |total, (_, size)| total.saturating_add(*size));
+        let liquidity_b = bids.iter().fold(0u64, |total, (price, size)| {
+            let notional = (*price as u128 * *size as u128 / PRICE_SCALE).min(u64::MAX as u128);
+            total.saturating_add(notional as u64)
+        });
+
+        Self {
+            info: PoolInfo::new(
+                address,
+                V::DEX_NAME.to_string(),
+                token_a,
+                token_b,
+                liquidity_a,
+                liquidity_b,
+                0,
+            ),
+            bids,
+            asks,
+            venue: PhantomData,
+        }
+    }
+
+    /// Get the venue's program ID
+    pub fn program_id() -> Pubkey {
+        Pubkey::from_str(V::PROGRAM_ID).unwrap()
+    }
+
+    pub fn best_bid(&self) -> u64 {
+        self.bids.first().map(|(price, _)| *price).unwrap_or(0)
+    }
+
+    pub fn best_ask(&self) -> u64 {
+        self.asks.first().map(|(price, _)| *price).unwrap_or(0)
+    }
+
+    pub fn bids(&self) -> &[(u64, u64)] {
+        &self.bids
+    }
+
+    pub fn asks(&self) -> &[(u64, u64)] {
+        &self.asks
+    }
+
+    /// Calculate spread in basis points
+    pub fn spread_bps(&self) -> u16 {
+        let best_bid = self.best_bid();
+        if best_bid == 0 {
+            return 10000; // 100% spread if no bid
+        }
+        let spread = self.best_ask().saturating_sub(best_bid);
+        ((spread as u128 * 10000) / best_bid as u128)
+            .min(10000) as u16
+    }
+
+    /// Sell token A into the bids, best price first
+    ///
+    /// Returns (token B received, worst price touched)
+    fn fill_bids(&self, input_amount: u64) -> Result<(u64, u64)> {
+        let mut remaining = input_amount as u128;
+        let mut output = 0u128;
+        let mut worst_price = 0u64;
+
+        for &(price, size) in &self.bids {
+            if remaining == 0 {
+                break;
+            }
+            if price == 0 || size == 0 {
+                continue;
+            }
+
+            let fill = remaining.min(size as u128);
+            output += fill * price as u128 / PRICE_SCALE;
+            remaining -= fill;
+            worst_price = price;
+        }
+
+        if remaining > 0 {
+            return Err(RouterError::InsufficientLiquidity);
+        }
+
+        let output = output.try_into().map_err(|_| RouterError::MathOverflow)?;
+        Ok((output, worst_price))
+    }
+
+    /// Buy token A from the asks with token B, best price first
+    ///
+    /// Returns (token A received, worst price touched)
+    fn fill_asks(&self, input_amount: u64) -> Result<(u64, u64)> {
+        let mut remaining = input_amount as u128;
+        let mut output = 0u128;
+        let mut worst_price = 0u64;
+
+        for &(price, size) in &self.asks {
+            if remaining == 0 {
+                break;
+            }
+            if price == 0 || size == 0 {
+                continue;
+            }
+
+            let level_cost = size as u128 * price as u128 / PRICE_SCALE;
+            if remaining >= level_cost {
+                output += size as u128;
+                remaining -= level_cost;
+            } else {
+                output += remaining * PRICE_SCALE / price as u128;
+                remaining = 0;
+            }
+            worst_price = price;
+        }
+
+        if remaining > 0 {
+            return Err(RouterError::InsufficientLiquidity);
+        }
+
+        let output = output.try_into().map_err(|_| RouterError::MathOverflow)?;
+        Ok((output, worst_price))
+    }
+}
+
+impl<V: Venue> Pool for OrderbookPool<V> {
+    fn address(&self) -> &Pubkey {
+        &self.info.address
+    }
+
+    fn dex_name(&self) -> &str {
+        &self.info.dex
+    }
+
+    fn token_a(&self) -> &Pubkey {
+        &self.info.token_a
+    }
+
+    fn token_b(&self) -> &Pubkey {
+        &self.info.token_b
+    }
+
+    fn reserve_a(&self) -> u64 {
+        self.info.reserve_a
+    }
+
+    fn reserve_b(&self) -> u64 {
+        self.info.reserve_b
+    }
+
+    fn fee_bps(&self) -> u16 {
+        // For orderbooks, the "fee" is the spread
+        self.spread_bps()
+    }
+
+    fn calculate_output(&self, input_amount: u64, a_to_b: bool) -> Result<(u64, u16)> {
+        // Selling A walks the bids (highest first); buying A walks the asks (lowest first)
+        let (top_price, (output_amount, worst_price)) = if a_to_b {
+            (self.best_bid(), self.fill_bids(input_amount)?)
+        } else {
+            (self.best_ask(), self.fill_asks(input_amount)?)
+        };
+
+        if top_price == 0 {
+            return Err(RouterError::InsufficientLiquidity);
+        }
+
+        // Price impact is how far the fill walked away from the top of book
+        let price_impact = if worst_price == 0 {
+            0
+        } else {
+            ((top_price.abs_diff(worst_price) as u128 * 10000) / top_price as u128).min(10000)
+                as u16
+        };
+
+        Ok((output_amount, price_impact))
+    }
+
+    fn calculate_price_impact(&self, input_amount: u64, a_to_b: bool) -> Result<u16> {
+        let (_, price_impact) = self.calculate_output(input_amount, a_to_b)?;
+        Ok(price_impact)
+    }
+
+    fn has_sufficient_liquidity(&self, input_amount: u64, a_to_b: bool) -> bool {
+        self.calculate_output(input_amount, a_to_b).is_ok()
+    }
+
+    /// Collapses the book to a single level per side at the current best bid/ask,
+    /// sized by the new liquidity (the same shape `new()` synthesizes)
+    fn update_reserves(&mut self, reserve_a: u64, reserve_b: u64) -> Result<()> {
+        self.info.set_reserves(reserve_a, reserve_b)?;
+
+        let best_bid = self.best_bid();
+        let best_ask = self.best_ask();
+        let rebuilt = Self::new(
+            self.info.address,
+            self.info.token_a,
+            self.info.token_b,
+            reserve_a,
+            reserve_b,
+            best_bid,
+            best_ask,
+        );
+        self.bids = rebuilt.bids;
+        self.asks = rebuilt.asks;
+        Ok(())
+    }
+
+    fn last_updated(&self) -> Option<Instant> {
+        self.info.last_updated
+    }
+
+    fn clone_box(&self) -> Box<dyn Pool> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn program_id(&self) -> Option<Pubkey> {
+        Some(Self::program_id())
+    }
+
+    fn constant_product_k(&self) -> u128 {
+        // No constant-product invariant on an orderbook
+        0
+    }
+
+    fn mid_price(&self, a_to_b: bool) -> f64 {
+        let (bid, ask) = (self.best_bid(), self.best_ask());
+        let mid = match (bid, ask) {
+            (0, 0) => return 0.0,
+            (0, ask) => ask as f64,
+            (bid, 0) => bid as f64,
+            (bid, ask) => (bid as f64 + ask as f64) / 2.0,
+        };
+
+        // Book prices are token B per token A, scaled by PRICE_SCALE
+        let price = mid / PRICE_SCALE as f64;
+        if a_to_b { price } else { 1.0 / price }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    struct TestVenue;
+
+    impl Venue for TestVenue {
+        const DEX_NAME: &'static str = "TestBook";
+        const PROGRAM_ID: &'static str = "11111111111111111111111111111111";
+    }
+
+    type TestMarket = OrderbookPool<TestVenue>;
+
+    #[test]
+    fn test_orderbook_market_creation() {
+        let market = TestMarket::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000, // liquidity A
+            50_000_000_000, // liquidity B
+            49_500, // best bid (49.5 per unit)
+            50_500, // best ask (50.5 per unit)
+        );
+
+        assert_eq!(market.dex_name(), "TestBook");
+        assert_eq!(market.best_bid(), 49_500);
+        assert_eq!(market.best_ask(), 50_500);
+    }
+
+    #[test]
+    fn test_orderbook_mid_price() {
+        let market = TestMarket::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+            49_500_000, // 49.5 B per A
+            50_500_000, // 50.5 B per A
+        );
+
+        assert!((market.mid_price(true) - 50.0).abs() < 1e-9);
+        assert!((market.mid_price(false) - 0.02).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_orderbook_spread_calculation() {
+        let market = TestMarket::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+            49_000, // bid
+            51_000, // ask
+        );
+
+        let spread = market.spread_bps();
+        // Spread = (51000 - 49000) / 49000 * 10000 ≈ 408 bps (4.08%)
+        assert!(spread > 400 && spread < 420);
+        assert_eq!(market.fee_bps(), spread);
+    }
+
+    #[test]
+    fn test_orderbook_calculate_output() {
+        let market = TestMarket::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+            50_000_000, // bid (price in microunits)
+            50_000_000, // ask (same for simplicity)
+        );
+
+        let input = 1_000_000; // 1 unit of A
+        let (output, _) = market.calculate_output(input, true).unwrap();
+
+        // Should get approximately 50 units of B
+        assert!(output > 0);
+    }
+
+    #[test]
+    fn test_orderbook_insufficient_liquidity() {
+        let market = TestMarket::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            100, // very low liquidity
+            100,
+            50_000_000,
+            50_000_000,
+        );
+
+        let input = 1_000_000_000; // huge amount
+        let result = market.calculate_output(input, true);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_orderbook_sweeps_multiple_bid_levels() {
+        let market = TestMarket::new_with_levels(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            vec![(49_000_000, 10_000_000), (50_000_000, 1_000_000)],
+            vec![(51_000_000, 1_000_000), (52_000_000, 10_000_000)],
+        );
+
+        assert_eq!(market.best_bid(), 50_000_000);
+        assert_eq!(market.best_ask(), 51_000_000);
+
+        // Top level only
+        let (small_output, small_impact) = market.calculate_output(1_000_000, true).unwrap();
+        assert_eq!(small_output, 50_000_000);
+        assert_eq!(small_impact, 0);
+
+        // Sweeps into the second level
+        let input = 2_000_000;
+        let (output, price_impact) = market.calculate_output(input, true).unwrap();
+        assert_eq!(output, 50_000_000 + 49_000_000);
+
+        let effective_price = output as u128 * PRICE_SCALE / input as u128;
+        assert!(effective_price < market.best_bid() as u128);
+        assert_eq!(price_impact, 200); // Worst level is 2% below top of book
+    }
+
+    #[test]
+    fn test_orderbook_sweeps_multiple_ask_levels() {
+        let market = TestMarket::new_with_levels(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            vec![(50_000_000, 1_000_000)],
+            vec![(51_000_000, 1_000_000), (52_000_000, 10_000_000)],
+        );
+
+        // 51 B buys the first level, the next 52 B buys one more unit at the worse price
+        let input = 51_000_000 + 52_000_000;
+        let (output, price_impact) = market.calculate_output(input, false).unwrap();
+        assert_eq!(output, 2_000_000);
+        assert!(price_impact > 0);
+
+        let effective_price = input as u128 * PRICE_SCALE / output as u128;
+        assert!(effective_price > market.best_ask() as u128);
+    }
+
+    #[test]
+    fn test_orderbook_update_reserves() {
+        let mut market = TestMarket::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            100,
+            100,
+            50_000_000,
+            50_000_000,
+        );
+
+        // Too little liquidity before the update
+        assert!(market.calculate_output(1_000_000, true).is_err());
+
+        market.update_reserves(1_000_000_000, 50_000_000_000).unwrap();
+
+        let (output, _) = market.calculate_output(1_000_000, true).unwrap();
+        assert_eq!(output, 50_000_000);
+        assert_eq!(market.best_bid(), 50_000_000);
+    }
+}
@@ -6,11 +6,97 @@ use crate::calculator::{calculate_amount_out, calculate_price_impact};
 use crate::error::{Result, RouterError};
 use crate::types::pool::{Pool, PoolInfo};
 use solana_sdk::pubkey::Pubkey;
+use std::any::Any;
+use std::time::Instant;
 use std::str::FromStr;
 
 /// Raydium AMM program ID
 pub const RAYDIUM_AMM_PROGRAM: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
 
+/// Standard Raydium AMM fee tier (0.25%)
+pub const DEFAULT_RAYDIUM_FEE_BPS: u16 = 25;
+
+/// Size of a Raydium AMM v4 pool account (`LIQUIDITY_STATE_LAYOUT_V4`)
+pub const RAYDIUM_AMM_V4_ACCOUNT_SIZE: usize = 752;
+
+// Byte offsets into the AMM v4 account layout
+const BASE_DECIMAL_OFFSET: usize = 32;
+const QUOTE_DECIMAL_OFFSET: usize = 40;
+const SWAP_FEE_NUMERATOR_OFFSET: usize = 176;
+const SWAP_FEE_DENOMINATOR_OFFSET: usize = 184;
+const BASE_VAULT_OFFSET: usize = 336;
+const QUOTE_VAULT_OFFSET: usize = 368;
+/// Offset of the base (coin) mint, used for `getProgramAccounts` memcmp filters
+pub const BASE_MINT_OFFSET: usize = 400;
+/// Offset of the quote (pc) mint, used for `getProgramAccounts` memcmp filters
+pub const QUOTE_MINT_OFFSET: usize = 432;
+
+/// Fields of interest from a Raydium AMM v4 pool account
+#[derive(Debug, Clone)]
+pub struct RaydiumAmmInfo {
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+    pub swap_fee_numerator: u64,
+    pub swap_fee_denominator: u64,
+    /// Token account holding the base (coin) reserve
+    pub base_vault: Pubkey,
+    /// Token account holding the quote (pc) reserve
+    pub quote_vault: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+}
+
+impl RaydiumAmmInfo {
+    /// Parse the AMM v4 account layout
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() != RAYDIUM_AMM_V4_ACCOUNT_SIZE {
+            return Err(RouterError::PoolParseError(format!(
+                "Invalid Raydium AMM v4 account size: expected {} bytes, got {}",
+                RAYDIUM_AMM_V4_ACCOUNT_SIZE,
+                data.len()
+            )));
+        }
+
+        let swap_fee_denominator = read_u64(data, SWAP_FEE_DENOMINATOR_OFFSET);
+        if swap_fee_denominator == 0 {
+            return Err(RouterError::PoolParseError(
+                "Raydium AMM v4 account has zero swap fee denominator".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            base_decimals: read_u64(data, BASE_DECIMAL_OFFSET) as u8,
+            quote_decimals: read_u64(data, QUOTE_DECIMAL_OFFSET) as u8,
+            swap_fee_numerator: read_u64(data, SWAP_FEE_NUMERATOR_OFFSET),
+            swap_fee_denominator,
+            base_vault: read_pubkey(data, BASE_VAULT_OFFSET),
+            quote_vault: read_pubkey(data, QUOTE_VAULT_OFFSET),
+            base_mint: read_pubkey(data, BASE_MINT_OFFSET),
+            quote_mint: read_pubkey(data, QUOTE_MINT_OFFSET),
+        })
+    }
+
+    /// Swap fee in basis points
+    pub fn fee_bps(&self) -> u16 {
+        (self.swap_fee_numerator as u128 * 10000 / self.swap_fee_denominator as u128)
+            .min(10000) as u16
+    }
+}
+
+/// Read a little-endian u64 (caller has validated the length)
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&data[offset..offset + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+/// Read a pubkey (caller has validated the length)
+fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&data[offset..offset + 32]);
+    Pubkey::new_from_array(bytes)
+}
+
 /// Raydium pool implementation
 #[derive(Debug, Clone)]
 pub struct RaydiumPool {
@@ -18,7 +104,7 @@ pub struct RaydiumPool {
 }
 
 impl RaydiumPool {
-    /// Create a new Raydium pool
+    /// Create a new Raydium pool at the standard 0.25% fee tier
     pub fn new(
         address: Pubkey,
         token_a: Pubkey,
@@ -26,7 +112,36 @@ impl RaydiumPool {
         reserve_a: u64,
         reserve_b: u64,
     ) -> Self {
-        Self {
+        Self::new_with_fee(
+            address,
+            token_a,
+            token_b,
+            reserve_a,
+            reserve_b,
+            DEFAULT_RAYDIUM_FEE_BPS,
+        )
+        .expect("default Raydium fee is a valid fee tier")
+    }
+
+    /// Create a new Raydium pool with a custom fee tier
+    ///
+    /// Returns `RouterError::ConfigError` if `fee_bps` exceeds 10000 (100%).
+    pub fn new_with_fee(
+        address: Pubkey,
+        token_a: Pubkey,
+        token_b: Pubkey,
+        reserve_a: u64,
+        reserve_b: u64,
+        fee_bps: u16,
+    ) -> Result<Self> {
+        if fee_bps > 10000 {
+            return Err(RouterError::ConfigError(format!(
+                "Invalid Raydium fee: {} bps exceeds 10000",
+                fee_bps
+            )));
+        }
+
+        Ok(Self {
             info: PoolInfo::new(
                 address,
                 "Raydium".to_string(),
@@ -34,25 +149,32 @@ impl RaydiumPool {
                 token_b,
                 reserve_a,
                 reserve_b,
-                25, // Raydium uses 0.25% fee
+                fee_bps,
             ),
-        }
+        })
     }
 
-    /// Parse Raydium pool account data
+    /// Parse Raydium AMM v4 pool account data
     ///
-    /// Note: This is a simplified version. In production, you'd need to parse
-    /// the actual Raydium account layout which includes:
-    /// - Pool state
-    /// - Coin vault address
-    /// - PC vault address
-    /// - LP mint, etc.
-    pub fn from_account_data(_address: Pubkey, _data: &[u8]) -> Result<Self> {
-        // TODO: Implement actual Raydium account parsing
-        // For now, return error indicating not implemented
-        Err(RouterError::PoolParseError(
-            "Raydium pool parsing not yet implemented - use new() for testing".to_string(),
-        ))
+    /// Token A is the base (coin) mint and token B the quote (pc) mint. Reserves
+    /// live in the separate vault token accounts (see `RaydiumAmmInfo::base_vault`
+    /// and `quote_vault`), so the caller fetches them and passes them in.
+    pub fn from_account_data(
+        address: Pubkey,
+        data: &[u8],
+        reserve_a: u64,
+        reserve_b: u64,
+    ) -> Result<Self> {
+        let amm = RaydiumAmmInfo::parse(data)?;
+
+        Self::new_with_fee(
+            address,
+            amm.base_mint,
+            amm.quote_mint,
+            reserve_a,
+            reserve_b,
+            amm.fee_bps(),
+        )
     }
 
     /// Get the Raydium program ID
@@ -123,6 +245,26 @@ impl Pool for RaydiumPool {
             Err(_) => false,
         }
     }
+
+    fn update_reserves(&mut self, reserve_a: u64, reserve_b: u64) -> Result<()> {
+        self.info.set_reserves(reserve_a, reserve_b)
+    }
+
+    fn last_updated(&self) -> Option<Instant> {
+        self.info.last_updated
+    }
+
+    fn clone_box(&self) -> Box<dyn Pool> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn program_id(&self) -> Option<Pubkey> {
+        Some(Self::program_id())
+    }
 }
 
 #[cfg(test)]
@@ -199,9 +341,132 @@ mod tests {
         assert!(!pool.has_sufficient_liquidity(u64::MAX, true));
     }
 
+    #[test]
+    fn test_raydium_custom_fee_tier() {
+        let default_pool = create_test_pool();
+        let low_fee_pool = RaydiumPool::new_with_fee(
+            Pubkey::new_unique(),
+            *default_pool.token_a(),
+            *default_pool.token_b(),
+            1_000_000_000,
+            50_000_000_000,
+            5,
+        )
+        .unwrap();
+
+        assert_eq!(low_fee_pool.fee_bps(), 5);
+
+        let input = 1_000_000;
+        let (default_output, _) = default_pool.calculate_output(input, true).unwrap();
+        let (low_fee_output, _) = low_fee_pool.calculate_output(input, true).unwrap();
+        assert!(low_fee_output > default_output);
+    }
+
+    #[test]
+    fn test_raydium_invalid_fee_tier() {
+        let result = RaydiumPool::new_with_fee(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+            10001,
+        );
+        assert!(matches!(result, Err(RouterError::ConfigError(_))));
+    }
+
     #[test]
     fn test_raydium_program_id() {
         let program_id = RaydiumPool::program_id();
         assert_eq!(program_id.to_string(), RAYDIUM_AMM_PROGRAM);
     }
+
+    /// Synthetic AMM v4 account with SOL/USDC mints and made-up vaults
+    ///
+    /// Not captured chain data: the bytes are written at the offsets of Raydium's
+    /// published `LIQUIDITY_STATE_LAYOUT_V4`, spelled out here rather than taken
+    /// from the parser's constants, so a wrong constant fails the test.
+    fn synthetic_amm_account() -> (Vec<u8>, Pubkey, Pubkey, Pubkey, Pubkey) {
+        let base_mint = Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap();
+        let quote_mint = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
+        let base_vault = Pubkey::new_unique();
+        let quote_vault = Pubkey::new_unique();
+
+        let mut data = vec![0u8; 752];
+        let mut write_u64 = |offset: usize, value: u64| {
+            data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+        };
+        write_u64(0, 6); // status
+        write_u64(32, 9); // baseDecimal
+        write_u64(40, 6); // quoteDecimal
+        write_u64(144, 25); // tradeFeeNumerator
+        write_u64(152, 10000); // tradeFeeDenominator
+        write_u64(176, 25); // swapFeeNumerator
+        write_u64(184, 10000); // swapFeeDenominator
+
+        for (offset, pubkey) in [
+            (336, base_vault),  // baseVault
+            (368, quote_vault), // quoteVault
+            (400, base_mint),   // baseMint
+            (432, quote_mint),  // quoteMint
+        ] {
+            data[offset..offset + 32].copy_from_slice(pubkey.as_ref());
+        }
+
+        (data, base_mint, quote_mint, base_vault, quote_vault)
+    }
+
+    #[test]
+    fn test_raydium_from_account_data() {
+        let (data, base_mint, quote_mint, base_vault, quote_vault) = synthetic_amm_account();
+        let address = Pubkey::new_unique();
+
+        let amm = RaydiumAmmInfo::parse(&data).unwrap();
+        assert_eq!(amm.base_decimals, 9);
+        assert_eq!(amm.quote_decimals, 6);
+        assert_eq!(amm.base_vault, base_vault);
+        assert_eq!(amm.quote_vault, quote_vault);
+
+        let pool =
+            RaydiumPool::from_account_data(address, &data, 1_000_000_000, 50_000_000_000).unwrap();
+
+        assert_eq!(pool.address(), &address);
+        assert_eq!(pool.token_a(), &base_mint);
+        assert_eq!(pool.token_b(), &quote_mint);
+        assert_eq!(pool.reserve_a(), 1_000_000_000);
+        assert_eq!(pool.reserve_b(), 50_000_000_000);
+        assert_eq!(pool.fee_bps(), 25);
+    }
+
+    #[test]
+    fn test_raydium_from_account_data_invalid_length() {
+        let result = RaydiumPool::from_account_data(Pubkey::new_unique(), &[0u8; 100], 1, 1);
+
+        match result {
+            Err(RouterError::PoolParseError(msg)) => assert!(msg.contains("expected 752 bytes")),
+            _ => panic!("expected PoolParseError"),
+        }
+    }
+
+    #[test]
+    fn test_raydium_update_reserves() {
+        let mut pools: Vec<Box<dyn Pool>> = vec![Box::new(create_test_pool())];
+        let (before, _) = pools[0].calculate_output(1_000_000, true).unwrap();
+
+        // Double the output reserve through the trait object
+        pools[0].update_reserves(1_000_000_000, 100_000_000_000).unwrap();
+
+        let (after, _) = pools[0].calculate_output(1_000_000, true).unwrap();
+        assert_eq!(pools[0].reserve_b(), 100_000_000_000);
+        assert_eq!(
+            after,
+            calculate_amount_out(1_000_000, 1_000_000_000, 100_000_000_000, 25).unwrap()
+        );
+        assert!(after > before);
+
+        assert!(matches!(
+            pools[0].update_reserves(0, 1),
+            Err(RouterError::InvalidReserves)
+        ));
+    }
 }
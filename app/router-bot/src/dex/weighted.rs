@@ -0,0 +1,291 @@
+//! Weighted pool implementation
+//!
+//! Balancer-style pools hold two tokens at unequal weights (e.g. 80/20) and
+//! keep the weighted geometric mean `reserve_a^w_a * reserve_b^w_b` constant.
+//! With equal weights this reduces to the constant product formula.
+//!
+//! The out-given-in formula raises a reserve ratio to the fractional power
+//! `w_in / w_out`, which has no cheap exact integer form, so quotes are
+//! computed in `f64`. Reserves above 2^53 lose precision and `powf` carries a
+//! relative error around 1e-15; outputs are rounded down after shaving off
+//! `OUTPUT_SAFETY_MARGIN` so a quote never exceeds what the exact math gives.
+
+use crate::error::{Result, RouterError};
+use crate::types::pool::{Pool, PoolInfo};
+use solana_sdk::pubkey::Pubkey;
+use std::any::Any;
+use std::time::Instant;
+
+/// Relative amount shaved off every quote to absorb floating point error
+const OUTPUT_SAFETY_MARGIN: f64 = 1e-12;
+
+/// Weighted (Balancer-style) pool implementation
+#[derive(Debug, Clone)]
+pub struct WeightedPool {
+    info: PoolInfo,
+    /// Weight of token A in basis points
+    weight_a: u16,
+    /// Weight of token B in basis points
+    weight_b: u16,
+}
+
+impl WeightedPool {
+    /// Create a new weighted pool
+    ///
+    /// `weight_a` and `weight_b` are in basis points, must be non-zero, and must
+    /// sum to 10000.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        address: Pubkey,
+        token_a: Pubkey,
+        token_b: Pubkey,
+        reserve_a: u64,
+        reserve_b: u64,
+        weight_a: u16,
+        weight_b: u16,
+        fee_bps: u16,
+    ) -> Result<Self> {
+        if weight_a == 0 || weight_b == 0 || weight_a as u32 + weight_b as u32 != 10000 {
+            return Err(RouterError::ConfigError(format!(
+                "Weighted pool weights must be non-zero and sum to 10000 bps, got {} and {}",
+                weight_a, weight_b
+            )));
+        }
+
+        if fee_bps > 10000 {
+            return Err(RouterError::ConfigError(format!(
+                "Weighted pool fee of {} bps exceeds 100%",
+                fee_bps
+            )));
+        }
+
+        Ok(Self {
+            info: PoolInfo::new(
+                address,
+                "Weighted".to_string(),
+                token_a,
+                token_b,
+                reserve_a,
+                reserve_b,
+                fee_bps,
+            ),
+            weight_a,
+            weight_b,
+        })
+    }
+
+    pub fn weight_a(&self) -> u16 {
+        self.weight_a
+    }
+
+    pub fn weight_b(&self) -> u16 {
+        self.weight_b
+    }
+
+    /// (input weight, output weight) for a swap direction
+    fn weights(&self, a_to_b: bool) -> (f64, f64) {
+        if a_to_b {
+            (self.weight_a as f64, self.weight_b as f64)
+        } else {
+            (self.weight_b as f64, self.weight_a as f64)
+        }
+    }
+}
+
+impl Pool for WeightedPool {
+    fn address(&self) -> &Pubkey {
+        &self.info.address
+    }
+
+    fn dex_name(&self) -> &str {
+        &self.info.dex
+    }
+
+    fn token_a(&self) -> &Pubkey {
+        &self.info.token_a
+    }
+
+    fn token_b(&self) -> &Pubkey {
+        &self.info.token_b
+    }
+
+    fn reserve_a(&self) -> u64 {
+        self.info.reserve_a
+    }
+
+    fn reserve_b(&self) -> u64 {
+        self.info.reserve_b
+    }
+
+    fn fee_bps(&self) -> u16 {
+        self.info.fee_bps
+    }
+
+    /// Balancer out-given-in, with `in` already net of fees:
+    /// `out = reserve_out * (1 - (reserve_in / (reserve_in + in))^(w_in / w_out))`
+    fn calculate_output(&self, input_amount: u64, a_to_b: bool) -> Result<(u64, u16)> {
+        let (reserve_in, reserve_out) = self.info.get_reserves(a_to_b);
+        if reserve_in == 0 || reserve_out == 0 {
+            return Err(RouterError::InvalidReserves);
+        }
+
+        if input_amount == 0 {
+            return Ok((0, 0));
+        }
+
+        let (weight_in, weight_out) = self.weights(a_to_b);
+        let reserve_in = reserve_in as f64;
+        let reserve_out = reserve_out as f64;
+        let input_after_fee = input_amount as f64 * (10000 - self.fee_bps()) as f64 / 10000.0;
+
+        let ratio = reserve_in / (reserve_in + input_after_fee);
+        let exact_output = reserve_out * (1.0 - ratio.powf(weight_in / weight_out));
+        let output_amount = (exact_output * (1.0 - OUTPUT_SAFETY_MARGIN)).floor().max(0.0) as u64;
+
+        // Spot price (output per input) is (reserve_out / w_out) / (reserve_in / w_in)
+        let spot_output =
+            input_amount as f64 * (reserve_out * weight_in) / (reserve_in * weight_out);
+        let price_impact = if spot_output <= 0.0 {
+            0
+        } else {
+            ((1.0 - output_amount as f64 / spot_output) * 10000.0).clamp(0.0, 10000.0) as u16
+        };
+
+        Ok((output_amount, price_impact))
+    }
+
+    fn calculate_price_impact(&self, input_amount: u64, a_to_b: bool) -> Result<u16> {
+        let (_, price_impact) = self.calculate_output(input_amount, a_to_b)?;
+        Ok(price_impact)
+    }
+
+    fn has_sufficient_liquidity(&self, input_amount: u64, a_to_b: bool) -> bool {
+        let (_, reserve_out) = self.info.get_reserves(a_to_b);
+        match self.calculate_output(input_amount, a_to_b) {
+            Ok((output, _)) => output < reserve_out / 2,
+            Err(_) => false,
+        }
+    }
+
+    fn update_reserves(&mut self, reserve_a: u64, reserve_b: u64) -> Result<()> {
+        self.info.set_reserves(reserve_a, reserve_b)
+    }
+
+    fn last_updated(&self) -> Option<Instant> {
+        self.info.last_updated
+    }
+
+    fn clone_box(&self) -> Box<dyn Pool> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn program_id(&self) -> Option<Pubkey> {
+        // Weighted pools are modelled for routing only; no program executes them
+        None
+    }
+
+    fn mid_price(&self, a_to_b: bool) -> f64 {
+        let (reserve_in, reserve_out) = self.info.get_reserves(a_to_b);
+        if reserve_in == 0 {
+            return 0.0;
+        }
+
+        let (weight_in, weight_out) = self.weights(a_to_b);
+        (reserve_out as f64 * weight_in) / (reserve_in as f64 * weight_out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculator::calculate_amount_out;
+
+    const RESERVE_A: u64 = 1_000_000_000;
+    const RESERVE_B: u64 = 50_000_000_000;
+
+    fn create_test_pool(weight_a: u16, weight_b: u16) -> WeightedPool {
+        WeightedPool::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            RESERVE_A,
+            RESERVE_B,
+            weight_a,
+            weight_b,
+            30,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_weighted_pool_creation() {
+        let pool = create_test_pool(8000, 2000);
+
+        assert_eq!(pool.dex_name(), "Weighted");
+        assert_eq!(pool.weight_a(), 8000);
+        assert_eq!(pool.weight_b(), 2000);
+        // 80/20 spot price is 4x the reserve ratio
+        assert!((pool.mid_price(true) - 200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_equal_weights_match_constant_product() {
+        let pool = create_test_pool(5000, 5000);
+
+        for input in [1_000, 1_000_000, 100_000_000] {
+            let (output, _) = pool.calculate_output(input, true).unwrap();
+            let expected = calculate_amount_out(input, RESERVE_A, RESERVE_B, 30).unwrap();
+            assert!(output <= expected && expected - output <= 1);
+        }
+    }
+
+    #[test]
+    fn test_80_20_pool_differs_from_constant_product() {
+        let weighted = create_test_pool(8000, 2000);
+        let balanced = create_test_pool(5000, 5000);
+        let input = 1_000_000;
+
+        // Token A carries more weight, so each unit of A buys more B...
+        let (weighted_out, _) = weighted.calculate_output(input, true).unwrap();
+        let (balanced_out, _) = balanced.calculate_output(input, true).unwrap();
+        assert!(weighted_out > balanced_out * 3);
+
+        // ...and each unit of B buys less A
+        let (weighted_out, _) = weighted.calculate_output(input * 50, false).unwrap();
+        let (balanced_out, _) = balanced.calculate_output(input * 50, false).unwrap();
+        assert!(weighted_out < balanced_out / 3);
+    }
+
+    #[test]
+    fn test_weighted_price_impact_grows_with_size() {
+        let pool = create_test_pool(8000, 2000);
+
+        let (_, small_impact) = pool.calculate_output(10_000, true).unwrap();
+        let (_, large_impact) = pool.calculate_output(100_000_000, true).unwrap();
+
+        // Small trades only pay the 0.3% fee
+        assert!((30..=31).contains(&small_impact));
+        assert!(large_impact > small_impact);
+    }
+
+    #[test]
+    fn test_invalid_weights() {
+        for (weight_a, weight_b) in [(8000, 1000), (10000, 0), (0, 10000)] {
+            let result = WeightedPool::new(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                RESERVE_A,
+                RESERVE_B,
+                weight_a,
+                weight_b,
+                30,
+            );
+            assert!(matches!(result, Err(RouterError::ConfigError(_))));
+        }
+    }
+}
@@ -1,9 +1,35 @@
 //! Error types for the router bot
 
+use std::fmt;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, RouterError>;
 
+/// Why a router could not produce a route
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoRouteReason {
+    /// No pool trades the requested token pair
+    NoMatchingPool,
+    /// Matching pools exist but none could fill the amount
+    InsufficientLiquidity,
+    /// The requested amount was zero
+    ZeroAmount,
+    /// Candidate paths were found but every one failed to quote
+    AllPathsExhausted,
+}
+
+impl fmt::Display for NoRouteReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            NoRouteReason::NoMatchingPool => "no pool matches the token pair",
+            NoRouteReason::InsufficientLiquidity => "all pools had insufficient liquidity",
+            NoRouteReason::ZeroAmount => "amount must be greater than zero",
+            NoRouteReason::AllPathsExhausted => "all candidate paths failed to quote",
+        };
+        f.write_str(reason)
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum RouterError {
     #[error("RPC client error: {0}")]
@@ -24,21 +50,30 @@ pub enum RouterError {
     #[error("Insufficient liquidity")]
     InsufficientLiquidity,
 
-    #[error("No route found for token pair")]
-    NoRouteFound,
+    #[error("No route found: {reason}")]
+    NoRouteFound { reason: NoRouteReason },
 
     #[error("Configuration error: {0}")]
     ConfigError(String),
 
+    #[error("Invalid quote: {0}")]
+    InvalidQuote(String),
+
     #[error("Transaction build error: {0}")]
     TransactionError(String),
 
     #[error("Math overflow in calculation")]
     MathOverflow,
 
+    #[error("Math overflow in calculation for batch item {index}")]
+    BatchMathOverflow { index: usize },
+
     #[error("Invalid pool reserves")]
     InvalidReserves,
 
+    #[error("Input and output tokens are identical")]
+    IdenticalTokens,
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -53,6 +88,25 @@ mod tests {
         assert_eq!(err.to_string(), "Account not found: test");
     }
 
+    #[test]
+    fn test_no_route_found_display() {
+        let err = RouterError::NoRouteFound {
+            reason: NoRouteReason::InsufficientLiquidity,
+        };
+        assert_eq!(
+            err.to_string(),
+            "No route found: all pools had insufficient liquidity"
+        );
+    }
+
+    #[test]
+    fn test_identical_tokens_display() {
+        assert_eq!(
+            RouterError::IdenticalTokens.to_string(),
+            "Input and output tokens are identical"
+        );
+    }
+
     #[test]
     fn test_error_conversion() {
         let anyhow_err = anyhow::anyhow!("test error");
@@ -1,18 +1,66 @@
 //! Transaction executor for swap routes
 
 use crate::client::SolanaClient;
+use crate::config::validate_referral_fee;
+use crate::dex::{LifinityPool, MeteoraPool, OpenBookMarket, OrcaPool, PhoenixPool, RaydiumPool};
 use crate::error::{Result, RouterError};
+use crate::types::pool::Pool;
 use crate::types::route::SwapQuote;
 use log::{info, warn};
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_message::{AddressLookupTableAccount, Message, VersionedMessage, v0};
 use solana_sdk::{
+    hash::Hash,
     instruction::Instruction,
-    signature::Signature,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::{Transaction, VersionedTransaction},
 };
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// How long `execute` waits for a sent transaction to be confirmed
+pub const DEFAULT_CONFIRM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Interval between signature status polls while confirming
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Default age after which live execution refuses a quote (10 seconds)
+pub const DEFAULT_MAX_QUOTE_AGE_MS: u64 = 10_000;
+
+/// Default slippage tolerance in basis points (1%)
+pub const DEFAULT_SLIPPAGE_BPS: u16 = 100;
+
+/// Most account keys a legacy transaction is built with before lookup tables are
+/// required (an approximation of the 1232-byte packet limit)
+pub const MAX_LEGACY_ACCOUNT_KEYS: usize = 32;
+
+/// Associated Token Account program, which derives a wallet's token account per mint
+const ASSOCIATED_TOKEN_PROGRAM: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+/// Allowed gap between a quote's output and the output recomputed from its steps
+const RECOMPUTE_TOLERANCE_BPS: u64 = 1;
 
 /// Transaction executor
 pub struct Executor {
-    _client: SolanaClient,
+    client: Option<SolanaClient>,
     dry_run: bool,
+    simulate_on_chain: bool,
+    slippage_bps: u16,
+    priority_fee_micro_lamports: Option<u64>,
+    compute_unit_limit: Option<u32>,
+    signer: Option<Keypair>,
+    /// Referral fee skimmed from the swap output, in basis points
+    referral_fee_bps: Option<u16>,
+    /// `token_a` of each known pool, used to orient simulated reserve changes
+    pool_token_a: HashMap<Pubkey, Pubkey>,
+    /// Program ID of each known pool, used to pick its swap instruction builder;
+    /// `None` for routing-only pools
+    pool_programs: HashMap<Pubkey, Option<Pubkey>>,
+    /// Live execution refuses quotes older than this
+    max_quote_age_ms: u64,
 }
 
 /// Result of a swap execution
@@ -22,21 +70,245 @@ pub struct ExecutionResult {
     pub signature: Option<Signature>,
     pub error: Option<String>,
     pub simulated_output: Option<u64>,
+    /// Output actually received on chain, when known
+    pub actual_output: Option<u64>,
+    /// Network fee charged for the transaction, in lamports
+    pub fee_lamports: Option<u64>,
+    /// Referral fee taken from the swap output, when a referral fee is configured;
+    /// only dry runs report it, and `simulated_output` is already net of it
+    pub referral_fee_amount: Option<u64>,
+    /// Minimum output the swap was required to produce, if enforced
+    pub min_output_enforced: Option<u64>,
+    /// Slippage tolerance applied, in basis points
+    pub slippage_bps: u16,
+    /// Compute units consumed, when simulated on chain
+    pub compute_units_consumed: Option<u64>,
+    /// Program logs, when simulated on chain
+    pub logs: Vec<String>,
+    /// Simulated `(pool, Δreserve_a, Δreserve_b)` for each step through a pool
+    /// registered with `Executor::with_pools`
+    pub reserve_deltas: Vec<(Pubkey, i64, i64)>,
+}
+
+impl ExecutionResult {
+    /// Multi-line, human-readable summary of the execution
+    ///
+    /// A result with a signature was sent on chain; one without was only
+    /// simulated. Fields that are unknown for the execution mode are reported
+    /// as such rather than omitted, so reports always have the same shape.
+    pub fn explain(&self) -> String {
+        let mut lines = Vec::new();
+
+        lines.push(if self.success {
+            "✅ Swap succeeded".to_string()
+        } else {
+            "❌ Swap failed".to_string()
+        });
+
+        match &self.signature {
+            Some(signature) => {
+                lines.push("   Mode:             live".to_string());
+                lines.push(format!("   Signature:        {}", signature));
+            }
+            None => {
+                lines.push("   Mode:             dry run".to_string());
+                lines.push("   Signature:        none (not sent)".to_string());
+            }
+        }
+
+        lines.push(format!(
+            "   Simulated Output: {}",
+            display_or(self.simulated_output, "not simulated")
+        ));
+        lines.push(format!(
+            "   Actual Output:    {}",
+            display_or(self.actual_output, "unknown")
+        ));
+        if let (Some(simulated), Some(actual)) = (self.simulated_output, self.actual_output) {
+            lines.push(format!(
+                "   Difference:       {}",
+                actual as i128 - simulated as i128
+            ));
+        }
+        if let Some(referral_fee) = self.referral_fee_amount {
+            lines.push(format!("   Referral Fee:     {}", referral_fee));
+        }
+        if let Some(min_output) = self.min_output_enforced {
+            lines.push(format!(
+                "   Minimum Output:   {} ({:.2}% slippage)",
+                min_output,
+                self.slippage_bps as f64 / 100.0
+            ));
+        }
+
+        lines.push(format!(
+            "   Network Fee:      {}",
+            self.fee_lamports
+                .map(|fee| format!("{} lamports", fee))
+                .unwrap_or_else(|| "unknown".to_string())
+        ));
+        if let Some(units) = self.compute_units_consumed {
+            lines.push(format!("   Compute Units:    {}", units));
+        }
+
+        for (pool, delta_a, delta_b) in &self.reserve_deltas {
+            lines.push(format!("   Pool {}: Δa {:+}, Δb {:+}", pool, delta_a, delta_b));
+        }
+
+        if let Some(error) = &self.error {
+            lines.push(format!("   Error:            {}", error));
+        }
+        if !self.logs.is_empty() {
+            lines.push(format!("   Program Logs:     {} lines", self.logs.len()));
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Format an optional value, falling back to `missing`
+fn display_or(value: Option<u64>, missing: &str) -> String {
+    value.map_or_else(|| missing.to_string(), |value| value.to_string())
 }
 
 impl Executor {
     /// Create a new executor
     pub fn new(client: SolanaClient, dry_run: bool) -> Self {
         Self {
-            _client: client,
+            client: Some(client),
             dry_run,
+            simulate_on_chain: false,
+            slippage_bps: DEFAULT_SLIPPAGE_BPS,
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+            signer: None,
+            referral_fee_bps: None,
+            pool_token_a: HashMap::new(),
+            pool_programs: HashMap::new(),
+            max_quote_age_ms: DEFAULT_MAX_QUOTE_AGE_MS,
         }
     }
 
+    /// Create an executor without an RPC client
+    ///
+    /// It always runs in dry-run mode and only simulates locally; anything that
+    /// needs the network (on-chain simulation, transaction building) fails.
+    pub fn offline() -> Self {
+        Self {
+            client: None,
+            dry_run: true,
+            simulate_on_chain: false,
+            slippage_bps: DEFAULT_SLIPPAGE_BPS,
+            priority_fee_micro_lamports: None,
+            compute_unit_limit: None,
+            signer: None,
+            referral_fee_bps: None,
+            pool_token_a: HashMap::new(),
+            pool_programs: HashMap::new(),
+            max_quote_age_ms: DEFAULT_MAX_QUOTE_AGE_MS,
+        }
+    }
+
+    /// Set the slippage tolerance used to derive the minimum output
+    pub fn with_slippage(mut self, slippage_bps: u16) -> Self {
+        self.slippage_bps = slippage_bps;
+        self
+    }
+
+    /// Attach a priority fee (micro-lamports per compute unit) and compute unit limit
+    pub fn with_priority_fee(mut self, micro_lamports: u64, compute_unit_limit: u32) -> Self {
+        self.priority_fee_micro_lamports = Some(micro_lamports);
+        self.compute_unit_limit = Some(compute_unit_limit);
+        self
+    }
+
+    /// Simulate dry runs against the RPC node (`simulateTransaction`) instead of
+    /// only computing the expected output locally
+    pub fn with_on_chain_simulation(mut self, enabled: bool) -> Self {
+        self.simulate_on_chain = enabled;
+        self
+    }
+
+    /// Register the pools quotes are routed through, so local simulation can
+    /// report how each pool's reserves would change and live execution knows
+    /// which program each step swaps through
+    pub fn with_pools(mut self, pools: &[Box<dyn Pool>]) -> Self {
+        self.pool_token_a = pools
+            .iter()
+            .map(|pool| (*pool.address(), *pool.token_a()))
+            .collect();
+        self.pool_programs = pools
+            .iter()
+            .map(|pool| (*pool.address(), pool.program_id()))
+            .collect();
+        self
+    }
+
+    /// Refuse to execute live quotes older than `max_age_ms`
+    pub fn with_max_quote_age(mut self, max_age_ms: u64) -> Self {
+        self.max_quote_age_ms = max_age_ms;
+        self
+    }
+
+    /// Attach the keypair used to sign live transactions
+    pub fn with_signer(mut self, keypair: Keypair) -> Self {
+        self.signer = Some(keypair);
+        self
+    }
+
+    /// Skim a referral fee of `referral_fee_bps` from every swap's output
+    ///
+    /// Routing still maximizes the gross output; the fee is only taken when the
+    /// quote is finalized. Fails with `RouterError::ConfigError` above
+    /// `config::MAX_REFERRAL_FEE_BPS`.
+    pub fn with_referral_fee(mut self, referral_fee_bps: u16) -> Result<Self> {
+        validate_referral_fee(referral_fee_bps)?;
+        self.referral_fee_bps = Some(referral_fee_bps);
+        Ok(self)
+    }
+
+    /// Split a quote's gross output into `(net output, referral fee)`
+    ///
+    /// The referral fee is rounded down, so the two always add up to
+    /// `quote.amount_out`. Without a referral fee the whole output is net.
+    pub fn finalize_output(&self, quote: &SwapQuote) -> (u64, u64) {
+        let fee_bps = self.referral_fee_bps.unwrap_or(0) as u128;
+        let referral_fee = (quote.amount_out as u128 * fee_bps / 10000) as u64;
+        (quote.amount_out - referral_fee, referral_fee)
+    }
+
+    /// Referral fee for `quote`, if a referral fee is configured
+    fn referral_fee_amount(&self, quote: &SwapQuote) -> Option<u64> {
+        self.referral_fee_bps.map(|_| self.finalize_output(quote).1)
+    }
+
+    /// Minimum acceptable output of `quote` under each slippage tolerance
+    ///
+    /// Returns `(slippage_bps, minimum_out)` pairs in the order given, to help
+    /// pick a tolerance before executing.
+    pub fn simulate_slippage_scenarios(
+        &self,
+        quote: &SwapQuote,
+        bps_values: &[u16],
+    ) -> Vec<(u16, u64)> {
+        bps_values
+            .iter()
+            .map(|&slippage_bps| (slippage_bps, quote.minimum_out(slippage_bps)))
+            .collect()
+    }
+
     /// Execute a swap quote
+    ///
+    /// The quote is validated first, so zero-output or extreme-impact routes are
+    /// never simulated or sent.
     pub fn execute(&self, quote: &SwapQuote) -> Result<ExecutionResult> {
+        quote.validate()?;
+
         if self.dry_run {
             info!("🔍 DRY RUN MODE - Simulating execution");
+            if self.simulate_on_chain && self.client.is_some() {
+                return self.simulate_on_chain(quote);
+            }
             return self.simulate(quote);
         }
 
@@ -45,7 +317,12 @@ impl Executor {
     }
 
     /// Simulate execution without sending transaction
+    ///
+    /// Fails with `RouterError::InvalidQuote` if the route's steps do not add up
+    /// to `quote.amount_out` (see `check_recomputed_output`).
     fn simulate(&self, quote: &SwapQuote) -> Result<ExecutionResult> {
+        check_recomputed_output(quote)?;
+
         info!("📊 Simulating swap:");
         info!("   Strategy: {}", quote.strategy);
         info!("   Input: {} ({})", quote.amount_in, quote.token_in);
@@ -53,6 +330,13 @@ impl Executor {
         info!("   Price Impact: {:.2}%", quote.price_impact_bps as f64 / 100.0);
         info!("   Hops: {}", quote.route.hop_count());
 
+        let min_output = quote.minimum_out(self.slippage_bps);
+        info!(
+            "   Minimum Output: {} ({:.2}% slippage)",
+            min_output,
+            self.slippage_bps as f64 / 100.0
+        );
+
         for (idx, step) in quote.route.steps.iter().enumerate() {
             info!("   Step {}: {} on {}", idx + 1, step.amount_in, step.dex);
             info!("      → Output: {}", step.amount_out);
@@ -60,58 +344,370 @@ impl Executor {
             info!("      → Price Impact: {:.2}%", step.price_impact_bps as f64 / 100.0);
         }
 
+        let (net_output, _) = self.finalize_output(quote);
+
         Ok(ExecutionResult {
             success: true,
             signature: None,
             error: None,
-            simulated_output: Some(quote.amount_out),
+            simulated_output: Some(net_output),
+            actual_output: None,
+            fee_lamports: None,
+            referral_fee_amount: self.referral_fee_amount(quote),
+            min_output_enforced: Some(min_output),
+            slippage_bps: self.slippage_bps,
+            compute_units_consumed: None,
+            logs: Vec::new(),
+            reserve_deltas: self.reserve_deltas(quote),
+        })
+    }
+
+    /// Reserve changes implied by each step: the input reserve grows by
+    /// `amount_in` and the output reserve shrinks by `amount_out`
+    ///
+    /// Steps through pools not registered with `with_pools` are skipped, since
+    /// their `token_a` side is unknown.
+    fn reserve_deltas(&self, quote: &SwapQuote) -> Vec<(Pubkey, i64, i64)> {
+        quote
+            .route
+            .steps
+            .iter()
+            .filter_map(|step| {
+                let token_a = self.pool_token_a.get(&step.pool_address)?;
+                let added = i64::try_from(step.amount_in).unwrap_or(i64::MAX);
+                let removed = -i64::try_from(step.amount_out).unwrap_or(i64::MAX);
+
+                Some(if step.token_in == *token_a {
+                    (step.pool_address, added, removed)
+                } else {
+                    (step.pool_address, removed, added)
+                })
+            })
+            .collect()
+    }
+
+    /// Simulate the swap transaction on chain via `simulateTransaction`
+    ///
+    /// The transaction is paid for by the signer and simulated without signature
+    /// verification against the latest blockhash. A failed simulation is reported
+    /// through `ExecutionResult::error` together with the program logs.
+    pub fn simulate_on_chain(&self, quote: &SwapQuote) -> Result<ExecutionResult> {
+        if quote.route.steps.is_empty() {
+            return Err(RouterError::TransactionError(
+                "Cannot simulate a quote without swap instructions".to_string(),
+            ));
+        }
+
+        let Some(signer) = &self.signer else {
+            return Err(RouterError::ConfigError(
+                "On-chain simulation requires a fee payer - pass --keypair-path or set execution.keypair_path"
+                    .to_string(),
+            ));
+        };
+
+        let client = self.rpc_client()?;
+        let instructions = self.build_instructions(quote)?;
+        let transaction = Transaction::new_with_payer(&instructions, Some(&signer.pubkey()));
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(client.commitment()),
+            ..RpcSimulateTransactionConfig::default()
+        };
+
+        let fee_lamports = self.network_fee(&VersionedMessage::Legacy(transaction.message.clone()));
+        let simulation = client
+            .rpc()
+            .simulate_transaction_with_config(&transaction, config)?
+            .value;
+
+        info!(
+            "📊 On-chain simulation consumed {} compute units",
+            simulation.units_consumed.unwrap_or(0)
+        );
+
+        Ok(ExecutionResult {
+            success: simulation.err.is_none(),
+            signature: None,
+            error: simulation.err.map(|e| format!("{:?}", e)),
+            simulated_output: None,
+            actual_output: None,
+            fee_lamports,
+            // No referral fee transfer is built into the transaction yet
+            referral_fee_amount: None,
+            // The swap instructions don't encode a minimum output yet
+            min_output_enforced: None,
+            slippage_bps: self.slippage_bps,
+            compute_units_consumed: simulation.units_consumed,
+            logs: simulation.logs.unwrap_or_default(),
+            reserve_deltas: Vec::new(),
         })
     }
 
     /// Execute live transaction
+    ///
+    /// Sends the signed transaction and waits up to `DEFAULT_CONFIRM_TIMEOUT` for
+    /// it to reach the client's commitment; `success` reflects the confirmation.
+    /// `actual_output` is the change in the signer's associated token account for
+    /// the output mint across the swap.
     fn execute_live(&self, quote: &SwapQuote) -> Result<ExecutionResult> {
-        // Build instructions for each step
-        let _instructions = self.build_instructions(quote)?;
+        if quote.is_stale(self.max_quote_age_ms) {
+            return Err(RouterError::InvalidQuote(format!(
+                "quote is stale: made at {} ms, more than {} ms ago - re-quote before executing",
+                quote.quoted_at, self.max_quote_age_ms
+            )));
+        }
 
-        // TODO: Implement actual transaction building and sending
-        // For now, return error indicating not implemented
-        Err(RouterError::TransactionError(
-            "Live transaction execution not yet implemented - use dry-run mode".to_string(),
-        ))
+        let Some(signer) = &self.signer else {
+            return Err(RouterError::ConfigError(
+                "Live execution requires a signer - pass --keypair-path or set execution.keypair_path"
+                    .to_string(),
+            ));
+        };
+
+        // TODO: Enforce `quote.minimum_out(self.slippage_bps)` in the swap
+        // instructions once the DEX instruction builders are implemented
+        let transaction = self.build_versioned_transaction(quote, &[])?;
+        let fee_lamports = self.network_fee(&transaction.message);
+        let output_account = self.associated_token_account(&signer.pubkey(), &quote.token_out)?;
+        let balance_before = self.token_balance(&output_account)?;
+        let signature = self.rpc_client()?.rpc().send_transaction(&transaction)?;
+        info!("📤 Sent transaction {}", signature);
+
+        let (success, error) = match self.confirm(&signature, DEFAULT_CONFIRM_TIMEOUT) {
+            Ok(true) => (true, None),
+            Ok(false) => (
+                false,
+                Some(format!(
+                    "Transaction not confirmed within {}s",
+                    DEFAULT_CONFIRM_TIMEOUT.as_secs()
+                )),
+            ),
+            Err(RouterError::TransactionError(e)) => (false, Some(e)),
+            Err(e) => return Err(e),
+        };
+
+        // The swap has already landed, so a failed balance read only loses the figure
+        let actual_output = if success {
+            match self.token_balance(&output_account) {
+                Ok(balance_after) => Some(balance_after.saturating_sub(balance_before)),
+                Err(e) => {
+                    warn!("Failed to read the output token balance: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        Ok(ExecutionResult {
+            success,
+            signature: Some(signature),
+            error,
+            simulated_output: None,
+            actual_output,
+            fee_lamports,
+            // No referral fee transfer is built into the transaction yet
+            referral_fee_amount: None,
+            // The swap instructions don't encode a minimum output yet
+            min_output_enforced: None,
+            slippage_bps: self.slippage_bps,
+            compute_units_consumed: None,
+            logs: Vec::new(),
+            reserve_deltas: Vec::new(),
+        })
+    }
+
+    /// Wait for `signature` to reach the client's commitment level
+    ///
+    /// Polls `getSignatureStatuses` until the transaction is confirmed or `timeout`
+    /// elapses, returning whether it was confirmed in time. RPC errors while
+    /// polling are treated as transient. A transaction that landed but failed is
+    /// reported as `RouterError::TransactionError`.
+    pub fn confirm(&self, signature: &Signature, timeout: Duration) -> Result<bool> {
+        let client = self.rpc_client()?;
+        let commitment = client.commitment();
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            match client.rpc().get_signature_statuses(&[*signature]) {
+                Ok(response) => {
+                    if let Some(Some(status)) = response.value.first() {
+                        if let Some(err) = &status.err {
+                            return Err(RouterError::TransactionError(format!(
+                                "Transaction {} failed: {:?}",
+                                signature, err
+                            )));
+                        }
+                        if status.satisfies_commitment(commitment) {
+                            info!("✅ Transaction {} confirmed", signature);
+                            return Ok(true);
+                        }
+                    }
+                }
+                Err(e) => warn!("Failed to fetch status of {}: {}", signature, e),
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                warn!("⏱️  Transaction {} not confirmed within {:?}", signature, timeout);
+                return Ok(false);
+            }
+            std::thread::sleep(CONFIRM_POLL_INTERVAL.min(deadline - now));
+        }
+    }
+
+    /// Build a signed versioned transaction for a quote
+    ///
+    /// With lookup tables the transaction is compiled into a v0 message so the
+    /// accounts they contain are referenced by index. Without lookup tables a
+    /// legacy message is used, as long as it stays within `MAX_LEGACY_ACCOUNT_KEYS`.
+    pub fn build_versioned_transaction(
+        &self,
+        quote: &SwapQuote,
+        lookup_tables: &[AddressLookupTableAccount],
+    ) -> Result<VersionedTransaction> {
+        let Some(signer) = &self.signer else {
+            return Err(RouterError::ConfigError(
+                "Building a transaction requires a signer - pass --keypair-path or set execution.keypair_path"
+                    .to_string(),
+            ));
+        };
+
+        let client = self.rpc_client()?;
+        let instructions = self.build_instructions(quote)?;
+        let recent_blockhash = client.rpc().get_latest_blockhash()?;
+        let message =
+            compile_message(&signer.pubkey(), &instructions, lookup_tables, recent_blockhash)?;
+
+        VersionedTransaction::try_new(message, &[signer])
+            .map_err(|e| RouterError::TransactionError(e.to_string()))
+    }
+
+    /// Network fee for a message, or `None` if it can't be fetched
+    fn network_fee(&self, message: &VersionedMessage) -> Option<u64> {
+        let rpc = self.client.as_ref()?.rpc();
+        let fee = match message {
+            VersionedMessage::Legacy(message) => rpc.get_fee_for_message(message),
+            VersionedMessage::V0(message) => rpc.get_fee_for_message(message),
+        };
+
+        fee.map_err(|e| warn!("Failed to fetch network fee: {}", e)).ok()
+    }
+
+    /// `owner`'s associated token account for `mint`
+    ///
+    /// The token program is read from the mint's owner, so Token-2022 mints
+    /// resolve to their own accounts.
+    fn associated_token_account(&self, owner: &Pubkey, mint: &Pubkey) -> Result<Pubkey> {
+        let token_program = self.rpc_client()?.fetch_account(mint)?.owner;
+        Ok(associated_token_address(owner, &token_program, mint))
+    }
+
+    /// Raw balance of a token account; one that doesn't exist yet holds nothing
+    fn token_balance(&self, token_account: &Pubkey) -> Result<u64> {
+        let client = self.rpc_client()?;
+        let commitment = client.commitment();
+
+        if client
+            .rpc()
+            .get_account_with_commitment(token_account, commitment)?
+            .value
+            .is_none()
+        {
+            return Ok(0);
+        }
+
+        let balance = client
+            .rpc()
+            .get_token_account_balance_with_commitment(token_account, commitment)?
+            .value;
+        balance.amount.parse().map_err(|_| {
+            RouterError::InvalidAccountData(format!(
+                "Token account {} reported a non-numeric balance {}",
+                token_account, balance.amount
+            ))
+        })
+    }
+
+    /// RPC client, or an error for offline executors
+    fn rpc_client(&self) -> Result<&SolanaClient> {
+        self.client.as_ref().ok_or_else(|| {
+            RouterError::ConfigError("Executor is offline and has no RPC client".to_string())
+        })
     }
 
     /// Build swap instructions for a quote
     fn build_instructions(&self, quote: &SwapQuote) -> Result<Vec<Instruction>> {
-        let mut instructions = Vec::new();
+        let mut instructions = self.build_compute_budget_instructions();
 
         for step in &quote.route.steps {
+            let program_id = *self.pool_programs.get(&step.pool_address).ok_or_else(|| {
+                RouterError::TransactionError(format!(
+                    "Unknown pool {} ({}) - register it with with_pools",
+                    step.pool_address, step.dex
+                ))
+            })?;
+            let Some(program_id) = program_id else {
+                return Err(RouterError::TransactionError(format!(
+                    "Pool {} ({}) is modelled for routing only and cannot be executed",
+                    step.pool_address, step.dex
+                )));
+            };
+
             // TODO: Build actual swap instructions based on DEX
-            // Each DEX has different instruction format
-            match step.dex.as_str() {
-                "Raydium" => {
-                    instructions.push(self.build_raydium_swap_instruction(step)?);
+            // Each DEX has different instruction format, and each should enforce
+            // its own entry from `quote.route.per_step_minimums(self.slippage_bps)`
+            let instruction = match program_id {
+                id if id == RaydiumPool::program_id() => {
+                    self.build_raydium_swap_instruction(step)?
+                }
+                id if id == OrcaPool::whirlpool_program_id()
+                    || id == OrcaPool::token_swap_program_id() =>
+                {
+                    self.build_orca_swap_instruction(step)?
+                }
+                id if id == MeteoraPool::dynamic_amm_program_id()
+                    || id == MeteoraPool::dlmm_program_id() =>
+                {
+                    self.build_meteora_swap_instruction(step)?
                 }
-                "Orca" => {
-                    instructions.push(self.build_orca_swap_instruction(step)?);
+                id if id == PhoenixPool::program_id() => {
+                    self.build_phoenix_swap_instruction(step)?
                 }
-                "Meteora" => {
-                    instructions.push(self.build_meteora_swap_instruction(step)?);
+                id if id == OpenBookMarket::program_id() => {
+                    self.build_openbook_swap_instruction(step)?
                 }
-                "Phoenix" => {
-                    instructions.push(self.build_phoenix_swap_instruction(step)?);
+                id if id == LifinityPool::program_id() => {
+                    self.build_lifinity_swap_instruction(step)?
                 }
-                _ => {
+                id => {
                     return Err(RouterError::TransactionError(format!(
-                        "Unknown DEX: {}",
-                        step.dex
+                        "Unsupported program {} for pool {} ({})",
+                        id, step.pool_address, step.dex
                     )));
                 }
-            }
+            };
+            instructions.push(instruction);
         }
 
         Ok(instructions)
     }
 
+    /// Build compute-budget instructions, which must precede the swap instructions
+    fn build_compute_budget_instructions(&self) -> Vec<Instruction> {
+        let mut instructions = Vec::new();
+
+        if let Some(limit) = self.compute_unit_limit {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(limit));
+        }
+        if let Some(price) = self.priority_fee_micro_lamports {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+        }
+
+        instructions
+    }
+
     /// Build Raydium swap instruction (stub)
     fn build_raydium_swap_instruction(
         &self,
@@ -155,13 +751,89 @@ impl Executor {
             "Phoenix instruction building not yet implemented".to_string(),
         ))
     }
+
+    /// Build OpenBook swap instruction (stub)
+    fn build_openbook_swap_instruction(
+        &self,
+        _step: &crate::types::route::RouteStep,
+    ) -> Result<Instruction> {
+        // TODO: Implement actual OpenBook v2 instruction building
+        Err(RouterError::TransactionError(
+            "OpenBook instruction building not yet implemented".to_string(),
+        ))
+    }
+
+    /// Build Lifinity swap instruction (stub)
+    fn build_lifinity_swap_instruction(
+        &self,
+        _step: &crate::types::route::RouteStep,
+    ) -> Result<Instruction> {
+        // TODO: Implement actual Lifinity v2 instruction building
+        Err(RouterError::TransactionError(
+            "Lifinity instruction building not yet implemented".to_string(),
+        ))
+    }
+}
+
+/// Compile instructions into a v0 message, or a legacy message when no lookup
+/// tables are supplied and the account count allows it
+fn compile_message(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+) -> Result<VersionedMessage> {
+    if lookup_tables.is_empty() {
+        let message = Message::new_with_blockhash(instructions, Some(payer), &recent_blockhash);
+        if message.account_keys.len() > MAX_LEGACY_ACCOUNT_KEYS {
+            return Err(RouterError::TransactionError(format!(
+                "Transaction references {} accounts (legacy limit {}) - supply address lookup tables",
+                message.account_keys.len(),
+                MAX_LEGACY_ACCOUNT_KEYS
+            )));
+        }
+        return Ok(VersionedMessage::Legacy(message));
+    }
+
+    v0::Message::try_compile(payer, instructions, lookup_tables, recent_blockhash)
+        .map(VersionedMessage::V0)
+        .map_err(|e| RouterError::TransactionError(format!("Failed to compile v0 message: {}", e)))
+}
+
+/// Address of `owner`'s associated token account for `mint` under `token_program`
+fn associated_token_address(owner: &Pubkey, token_program: &Pubkey, mint: &Pubkey) -> Pubkey {
+    let seeds = [owner.as_ref(), token_program.as_ref(), mint.as_ref()];
+    let program_id = Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM).unwrap();
+    Pubkey::find_program_address(&seeds, &program_id).0
+}
+
+/// Re-derive the quote's output from its steps and reject it on a mismatch
+///
+/// Catches router bugs that produce inconsistent quotes, such as split legs
+/// that don't sum to the quoted output. Differences up to
+/// `RECOMPUTE_TOLERANCE_BPS` of the quoted output (at least one unit) are
+/// allowed for rounding.
+fn check_recomputed_output(quote: &SwapQuote) -> Result<()> {
+    let recomputed = quote.route.recompute_output();
+    let tolerance = (quote.amount_out / 10000 * RECOMPUTE_TOLERANCE_BPS).max(1);
+
+    if recomputed.abs_diff(quote.amount_out) > tolerance {
+        return Err(RouterError::InvalidQuote(format!(
+            "route steps produce {} but the quote expects {}",
+            recomputed, quote.amount_out
+        )));
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::MAX_REFERRAL_FEE_BPS;
+    use crate::dex::WeightedPool;
     use crate::types::route::{Route, RouteStep};
-    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::instruction::AccountMeta;
 
     fn create_test_quote() -> SwapQuote {
         let token_in = Pubkey::new_unique();
@@ -176,6 +848,7 @@ mod tests {
             amount_out: 50_000_000,
             price_impact_bps: 25,
             fee_bps: 25,
+            fee_amount: 2_500,
         };
 
         let route = Route::single_step(step, 1_000_000, 50_000_000);
@@ -200,17 +873,457 @@ mod tests {
         assert!(result.success);
         assert!(result.signature.is_none());
         assert_eq!(result.simulated_output, Some(50_000_000));
+        assert_eq!(result.slippage_bps, DEFAULT_SLIPPAGE_BPS);
+        assert_eq!(result.min_output_enforced, Some(49_500_000));
+    }
+
+    #[test]
+    fn test_dry_run_reports_reserve_deltas() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pools: Vec<Box<dyn Pool>> = vec![Box::new(crate::dex::RaydiumPool::new(
+            Pubkey::new_unique(),
+            token_a,
+            token_b,
+            1_000_000_000,
+            50_000_000_000,
+        ))];
+        let executor = Executor::offline().with_pools(&pools);
+
+        for (token_in, token_out) in [(token_a, token_b), (token_b, token_a)] {
+            let quote = crate::router::SinglePoolRouter::find_best_route(
+                &pools, &token_in, &token_out, 1_000_000,
+            )
+            .unwrap();
+            let result = executor.execute(&quote).unwrap();
+
+            let (pool, delta_a, delta_b) = result.reserve_deltas[0];
+            let (delta_in, delta_out) = if token_in == token_a {
+                (delta_a, delta_b)
+            } else {
+                (delta_b, delta_a)
+            };
+            assert_eq!(result.reserve_deltas.len(), 1);
+            assert_eq!(pool, *pools[0].address());
+            assert_eq!(delta_in, quote.amount_in as i64);
+            assert_eq!(delta_out, -(quote.amount_out as i64));
+        }
+
+        // Pools the executor doesn't know about are skipped
+        let result = executor.execute(&create_test_quote()).unwrap();
+        assert!(result.reserve_deltas.is_empty());
+    }
+
+    #[test]
+    fn test_explain_dry_run() {
+        let result = Executor::offline().execute(&create_test_quote()).unwrap();
+        let report = result.explain();
+
+        assert!(report.starts_with("✅ Swap succeeded"));
+        assert!(report.contains("Mode:             dry run"));
+        assert!(report.contains("Signature:        none (not sent)"));
+        assert!(report.contains("Simulated Output: 50000000"));
+        assert!(report.contains("Actual Output:    unknown"));
+        assert!(report.contains("Minimum Output:   49500000 (1.00% slippage)"));
+        assert!(report.contains("Network Fee:      unknown"));
+        assert!(!report.contains("Error:"));
+    }
+
+    #[test]
+    fn test_dry_run_rejects_inconsistent_route() {
+        let mut quote = create_test_quote();
+        let mut second_leg = quote.route.steps[0].clone();
+        second_leg.pool_address = Pubkey::new_unique();
+        quote.route.steps.push(second_leg);
+
+        // The two split legs produce 100_000_000 but the quote claims 50_000_000
+        let result = Executor::offline().execute(&quote);
+        assert!(matches!(result, Err(RouterError::InvalidQuote(_))));
+
+        // Rounding within the tolerance is accepted
+        let mut quote = create_test_quote();
+        quote.amount_out += 1;
+        assert!(Executor::offline().execute(&quote).is_ok());
+    }
+
+    #[test]
+    fn test_explain_live_success() {
+        let signature = Signature::from([7u8; 64]);
+        let result = ExecutionResult {
+            success: true,
+            signature: Some(signature),
+            error: None,
+            simulated_output: Some(50_000_000),
+            actual_output: Some(49_900_000),
+            fee_lamports: Some(5_000),
+            referral_fee_amount: None,
+            min_output_enforced: Some(49_500_000),
+            slippage_bps: 100,
+            compute_units_consumed: Some(120_000),
+            logs: Vec::new(),
+            reserve_deltas: Vec::new(),
+        };
+        let report = result.explain();
+
+        assert!(report.starts_with("✅ Swap succeeded"));
+        assert!(report.contains("Mode:             live"));
+        assert!(report.contains(&format!("Signature:        {}", signature)));
+        assert!(report.contains("Actual Output:    49900000"));
+        assert!(report.contains("Difference:       -100000"));
+        assert!(report.contains("Network Fee:      5000 lamports"));
+        assert!(report.contains("Compute Units:    120000"));
+    }
+
+    #[test]
+    fn test_explain_failure() {
+        let result = ExecutionResult {
+            success: false,
+            signature: None,
+            error: Some("custom program error: 0x1".to_string()),
+            simulated_output: None,
+            actual_output: None,
+            fee_lamports: None,
+            referral_fee_amount: None,
+            min_output_enforced: Some(49_500_000),
+            slippage_bps: 100,
+            compute_units_consumed: Some(3_000),
+            logs: vec!["Program log: slippage exceeded".to_string()],
+            reserve_deltas: Vec::new(),
+        };
+        let report = result.explain();
+
+        assert!(report.starts_with("❌ Swap failed"));
+        assert!(report.contains("Simulated Output: not simulated"));
+        assert!(report.contains("Error:            custom program error: 0x1"));
+        assert!(report.contains("Program Logs:     1 lines"));
+        assert!(!report.contains("Difference:"));
+    }
+
+    #[test]
+    fn test_referral_fee_splits_gross_output() {
+        let quote = create_test_quote();
+        let executor = Executor::offline().with_referral_fee(30).unwrap();
+
+        let (net_output, referral_fee) = executor.finalize_output(&quote);
+        assert_eq!(referral_fee, 150_000);
+        assert_eq!(net_output + referral_fee, quote.amount_out);
+
+        let result = executor.execute(&quote).unwrap();
+        assert_eq!(result.simulated_output, Some(net_output));
+        assert_eq!(result.referral_fee_amount, Some(referral_fee));
+        assert!(result.explain().contains("Referral Fee:     150000"));
+
+        // Without a referral fee the whole output is net
+        let result = Executor::offline().execute(&quote).unwrap();
+        assert_eq!(result.simulated_output, Some(quote.amount_out));
+        assert_eq!(result.referral_fee_amount, None);
+    }
+
+    #[test]
+    fn test_slippage_scenarios() {
+        let quote = create_test_quote();
+        let scenarios = Executor::offline().simulate_slippage_scenarios(&quote, &[50, 100, 300]);
+
+        assert_eq!(
+            scenarios,
+            vec![(50, 49_750_000), (100, 49_500_000), (300, 48_500_000)]
+        );
+        assert!(scenarios.windows(2).all(|pair| pair[0].1 > pair[1].1));
+    }
+
+    #[test]
+    fn test_referral_fee_limit() {
+        assert!(Executor::offline().with_referral_fee(MAX_REFERRAL_FEE_BPS).is_ok());
+        assert!(matches!(
+            Executor::offline().with_referral_fee(MAX_REFERRAL_FEE_BPS + 1),
+            Err(RouterError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn test_executor_dry_run_records_slippage() {
+        let client = SolanaClient::new_devnet();
+        let executor = Executor::new(client, true).with_slippage(50);
+        let quote = create_test_quote();
+
+        let result = executor.execute(&quote).unwrap();
+
+        assert_eq!(result.slippage_bps, 50);
+        // 50_000_000 * (1 - 0.5%)
+        assert_eq!(result.min_output_enforced, Some(49_750_000));
+    }
+
+    #[test]
+    fn test_offline_executor_always_dry_runs() {
+        let executor = Executor::offline()
+            .with_on_chain_simulation(true)
+            .with_signer(Keypair::new());
+        let quote = create_test_quote();
+
+        let result = executor.execute(&quote).unwrap();
+        assert!(result.success);
+        assert_eq!(result.simulated_output, Some(50_000_000));
+
+        let result = executor.build_versioned_transaction(&quote, &[]);
+        assert!(matches!(result, Err(RouterError::ConfigError(_))));
     }
 
     #[test]
     fn test_executor_live_not_implemented() {
+        let quote = create_test_quote();
+        let step = &quote.route.steps[0];
+
+        // Register the quoted pool so execution gets as far as the Raydium builder
+        let pools: Vec<Box<dyn Pool>> = vec![Box::new(RaydiumPool::new(
+            step.pool_address,
+            step.token_in,
+            step.token_out,
+            1_000_000_000,
+            50_000_000_000,
+        ))];
+        let client = SolanaClient::new_devnet();
+        let executor = Executor::new(client, false)
+            .with_signer(Keypair::new())
+            .with_pools(&pools);
+
+        let result = executor.execute(&quote);
+
+        // Should fail because swap instruction building is not implemented yet
+        assert!(matches!(
+            result,
+            Err(RouterError::TransactionError(msg))
+                if msg == "Raydium instruction building not yet implemented"
+        ));
+    }
+
+    #[test]
+    fn test_executor_rejects_invalid_quote() {
+        let client = SolanaClient::new_devnet();
+        let executor = Executor::new(client, true);
+        let mut quote = create_test_quote();
+        quote.amount_out = 0;
+
+        let result = executor.execute(&quote);
+        assert!(matches!(result, Err(RouterError::InvalidQuote(_))));
+    }
+
+    #[test]
+    fn test_executor_live_requires_signer() {
         let client = SolanaClient::new_devnet();
         let executor = Executor::new(client, false);
         let quote = create_test_quote();
 
         let result = executor.execute(&quote);
+        assert!(matches!(result, Err(RouterError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_executor_live_refuses_stale_quote() {
+        let quote = create_test_quote();
+        let stale = quote.clone().with_quoted_at(quote.quoted_at - 60_000);
+
+        let executor = Executor::new(SolanaClient::new_devnet(), false);
+        let result = executor.execute(&stale);
+        assert!(matches!(result, Err(RouterError::InvalidQuote(msg)) if msg.contains("stale")));
+
+        // Dry runs still simulate old quotes, e.g. when backtesting snapshots
+        let result = Executor::offline().execute(&stale).unwrap();
+        assert!(result.success);
+
+        // A fresh quote gets past the staleness check to the signer check
+        let result = executor.execute(&quote);
+        assert!(matches!(result, Err(RouterError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_simulate_on_chain_rejects_empty_instructions() {
+        let client = SolanaClient::new_devnet();
+        let executor = Executor::new(client, true)
+            .with_on_chain_simulation(true)
+            .with_signer(Keypair::new());
+        let mut quote = create_test_quote();
+        quote.route.steps.clear();
+
+        let result = executor.simulate_on_chain(&quote);
+        assert!(matches!(result, Err(RouterError::TransactionError(_))));
+    }
+
+    #[test]
+    fn test_build_instructions_prepends_compute_budget() {
+        let client = SolanaClient::new_devnet();
+        let executor = Executor::new(client, false).with_priority_fee(10_000, 300_000);
+        let mut quote = create_test_quote();
+        quote.route.steps.clear();
+
+        let instructions = executor.build_instructions(&quote).unwrap();
+
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(
+            instructions[0],
+            ComputeBudgetInstruction::set_compute_unit_limit(300_000)
+        );
+        assert_eq!(
+            instructions[1],
+            ComputeBudgetInstruction::set_compute_unit_price(10_000)
+        );
+    }
+
+    #[test]
+    fn test_associated_token_address() {
+        let owner = Pubkey::from_str("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM").unwrap();
+        let usdc = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
+
+        // Matches spl-associated-token-account's get_associated_token_address
+        assert_eq!(
+            associated_token_address(&owner, &spl_token::id(), &usdc).to_string(),
+            "FGETo8T8wMcN2wCjav8VK6eh3dLk63evNDPxzLSJra8B"
+        );
+    }
+
+    #[test]
+    fn test_build_instructions_dispatches_on_program_id() {
+        let quote = create_test_quote();
+        let step = &quote.route.steps[0];
+
+        // Unregistered pools cannot be executed
+        let executor = Executor::new(SolanaClient::new_devnet(), false);
+        let err = executor.build_instructions(&quote).unwrap_err().to_string();
+        assert!(err.contains("Unknown pool"), "{}", err);
+
+        // The builder follows the pool's program, not the step's DEX label
+        let pools: Vec<Box<dyn Pool>> = vec![Box::new(PhoenixPool::new(
+            step.pool_address,
+            step.token_in,
+            step.token_out,
+            1_000_000_000,
+            50_000_000_000,
+            49_000_000,
+            51_000_000,
+        ))];
+        let executor = Executor::new(SolanaClient::new_devnet(), false).with_pools(&pools);
+        let err = executor.build_instructions(&quote).unwrap_err().to_string();
+        assert!(err.contains("Phoenix instruction building"), "{}", err);
+
+        let (address, token_in, token_out) = (step.pool_address, step.token_in, step.token_out);
+        let pools: Vec<Box<dyn Pool>> = vec![Box::new(LifinityPool::new(
+            address, token_in, token_out, 1_000_000_000, 50_000_000_000, 30, 50.0, 10,
+        ))];
+        let executor = Executor::new(SolanaClient::new_devnet(), false).with_pools(&pools);
+        let err = executor.build_instructions(&quote).unwrap_err().to_string();
+        assert!(err.contains("Lifinity instruction building"), "{}", err);
+
+        // Routing-only pools have no program to execute them
+        let weighted =
+            WeightedPool::new(address, token_in, token_out, 1_000, 50_000, 5_000, 5_000, 30);
+        let pools: Vec<Box<dyn Pool>> = vec![Box::new(weighted.unwrap())];
+        let executor = Executor::new(SolanaClient::new_devnet(), false).with_pools(&pools);
+        let err = executor.build_instructions(&quote).unwrap_err().to_string();
+        assert!(err.contains("routing only"), "{}", err);
+    }
+
+    #[test]
+    fn test_build_instructions_without_priority_fee() {
+        let client = SolanaClient::new_devnet();
+        let executor = Executor::new(client, false);
+        let mut quote = create_test_quote();
+        quote.route.steps.clear();
+
+        let instructions = executor.build_instructions(&quote).unwrap();
+        assert!(instructions.is_empty());
+    }
+
+    /// Swap-like instructions for a synthetic route: one per hop, each touching a
+    /// pool and its two vaults
+    fn synthetic_route_instructions(hops: usize) -> (Vec<Instruction>, Vec<Pubkey>) {
+        let program_id = Pubkey::new_unique();
+        let mut accounts = Vec::new();
+
+        let instructions = (0..hops)
+            .map(|_| {
+                let metas: Vec<AccountMeta> = (0..3)
+                    .map(|_| {
+                        let account = Pubkey::new_unique();
+                        accounts.push(account);
+                        AccountMeta::new(account, false)
+                    })
+                    .collect();
+                Instruction::new_with_bytes(program_id, &[9], metas)
+            })
+            .collect();
+
+        (instructions, accounts)
+    }
+
+    #[test]
+    fn test_compile_message_v0_with_lookup_table() {
+        let payer = Pubkey::new_unique();
+        let (instructions, accounts) = synthetic_route_instructions(3);
+        let lookup_table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: accounts.clone(),
+        };
+
+        let message =
+            compile_message(&payer, &instructions, &[lookup_table], Hash::default()).unwrap();
+
+        let VersionedMessage::V0(message) = message else {
+            panic!("expected a v0 message");
+        };
+        // Only the payer and the program stay static; the 9 pool accounts are looked up
+        assert_eq!(message.account_keys.len(), 2);
+        assert_eq!(message.account_keys[0], payer);
+        assert_eq!(message.address_table_lookups.len(), 1);
+        assert_eq!(
+            message.address_table_lookups[0].writable_indexes.len(),
+            accounts.len()
+        );
+    }
+
+    #[test]
+    fn test_compile_message_legacy_fallback() {
+        let payer = Pubkey::new_unique();
+
+        let (instructions, _) = synthetic_route_instructions(2);
+        let message = compile_message(&payer, &instructions, &[], Hash::default()).unwrap();
+        let VersionedMessage::Legacy(message) = message else {
+            panic!("expected a legacy message");
+        };
+        assert_eq!(message.account_keys.len(), 8);
+
+        // Too many accounts for a legacy transaction without lookup tables
+        let (instructions, _) = synthetic_route_instructions(11);
+        let result = compile_message(&payer, &instructions, &[], Hash::default());
+        assert!(matches!(result, Err(RouterError::TransactionError(_))));
+    }
+
+    #[test]
+    fn test_confirm_times_out_gracefully() {
+        // Unroutable RPC URL: every poll fails, so confirmation can only time out
+        let client = SolanaClient::new("http://127.0.0.1:1".to_string());
+        let executor = Executor::new(client, false);
+        let timeout = Duration::from_millis(300);
+
+        let started = Instant::now();
+        let confirmed = executor.confirm(&Signature::default(), timeout).unwrap();
+
+        assert!(!confirmed);
+        assert!(started.elapsed() >= timeout);
+        assert!(started.elapsed() < timeout + Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_confirm_requires_client() {
+        let result = Executor::offline().confirm(&Signature::default(), Duration::ZERO);
+        assert!(matches!(result, Err(RouterError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_build_versioned_transaction_requires_signer() {
+        let client = SolanaClient::new_devnet();
+        let executor = Executor::new(client, false);
+        let quote = create_test_quote();
 
-        // Should fail because live execution not implemented yet
-        assert!(result.is_err());
+        let result = executor.build_versioned_transaction(&quote, &[]);
+        assert!(matches!(result, Err(RouterError::ConfigError(_))));
     }
 }
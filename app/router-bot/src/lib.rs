@@ -11,9 +11,10 @@ pub mod router;
 pub mod executor;
 pub mod config;
 pub mod error;
+pub mod logging;
 
 // Re-export commonly used types
 pub use client::SolanaClient;
 pub use config::Config;
-pub use error::{RouterError, Result};
+pub use error::{NoRouteReason, RouterError, Result};
 pub use types::{Pool, Route, SwapQuote};
@@ -0,0 +1,158 @@
+//! Logger setup and structured (JSON lines) logging
+//!
+//! The default `pretty` format is the emoji-decorated output meant for a
+//! terminal. With `--log-format json` every log record is written as one JSON
+//! object per line, and quotes are logged as structured records whose fields
+//! (`strategy`, `amount_in`, `amount_out`, `impact_bps`, ...) can be queried by
+//! a log aggregator instead of being parsed out of a message.
+
+use crate::types::route::SwapQuote;
+use log::{Level, LevelFilter, info};
+use serde_json::{Map, Value, json};
+use std::io::Write;
+
+/// Log target for records whose message is already a JSON object of fields
+pub const STRUCTURED_TARGET: &str = "router_bot::structured";
+
+/// Initialize the global logger for `log_format` (`pretty` or `json`)
+pub fn init_logger(verbose: bool, log_format: &str) {
+    let level = if verbose { LevelFilter::Debug } else { LevelFilter::Info };
+    let mut builder = env_logger::Builder::from_default_env();
+    builder.filter_level(level);
+
+    if log_format == "json" {
+        builder.format(|buf, record| {
+            let message = record.args().to_string();
+            writeln!(buf, "{}", format_json_record(record.level(), record.target(), &message))
+        });
+    }
+
+    builder.init();
+}
+
+/// Render a log record as a single JSON line
+///
+/// Structured records (target `STRUCTURED_TARGET`) have their fields merged
+/// into the top-level object; anything else is wrapped as a `message` field.
+pub fn format_json_record(level: Level, target: &str, message: &str) -> String {
+    let mut fields = match serde_json::from_str::<Value>(message) {
+        Ok(Value::Object(fields)) if target == STRUCTURED_TARGET => fields,
+        _ => {
+            let mut fields = Map::new();
+            fields.insert("target".to_string(), json!(target));
+            fields.insert("message".to_string(), json!(message));
+            fields
+        }
+    };
+    fields.insert("level".to_string(), json!(level.as_str()));
+
+    Value::Object(fields).to_string()
+}
+
+/// Structured records for a quote: one `quote` record, then one `step` record per step
+pub fn quote_records(quote: &SwapQuote) -> Vec<Value> {
+    let summary = json!({
+        "event": "quote",
+        "strategy": quote.strategy,
+        "token_in": quote.token_in.to_string(),
+        "token_out": quote.token_out.to_string(),
+        "amount_in": quote.amount_in,
+        "amount_out": quote.amount_out,
+        "impact_bps": quote.price_impact_bps,
+        "hops": quote.route.hop_count(),
+    });
+
+    let steps = quote.route.steps.iter().enumerate().map(|(idx, step)| {
+        json!({
+            "event": "step",
+            "strategy": quote.strategy,
+            "step": idx + 1,
+            "dex": step.dex,
+            "pool": step.pool_address.to_string(),
+            "amount_in": step.amount_in,
+            "amount_out": step.amount_out,
+            "impact_bps": step.price_impact_bps,
+            "fee_bps": step.fee_bps,
+        })
+    });
+
+    std::iter::once(summary).chain(steps).collect()
+}
+
+/// Log a quote and each of its steps as structured records
+pub fn log_quote(quote: &SwapQuote) {
+    for record in quote_records(quote) {
+        info!(target: STRUCTURED_TARGET, "{}", record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::route::{Route, RouteStep};
+    use solana_sdk::pubkey::Pubkey;
+
+    fn create_test_quote() -> SwapQuote {
+        let step = RouteStep {
+            pool_address: Pubkey::new_unique(),
+            dex: "Raydium".to_string(),
+            token_in: Pubkey::new_unique(),
+            token_out: Pubkey::new_unique(),
+            amount_in: 1_000_000,
+            amount_out: 50_000_000,
+            price_impact_bps: 25,
+            fee_bps: 25,
+            fee_amount: 2_500,
+        };
+        let (token_in, token_out) = (step.token_in, step.token_out);
+        let route = Route::single_step(step, 1_000_000, 50_000_000);
+        SwapQuote::new(
+            token_in,
+            token_out,
+            1_000_000,
+            50_000_000,
+            route,
+            "single_pool".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_structured_quote_output_has_expected_keys() {
+        let quote = create_test_quote();
+        let lines: Vec<String> = quote_records(&quote)
+            .iter()
+            .map(|record| format_json_record(Level::Info, STRUCTURED_TARGET, &record.to_string()))
+            .collect();
+        assert_eq!(lines.len(), 2);
+
+        let summary: Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(summary["level"], "INFO");
+        assert_eq!(summary["event"], "quote");
+        assert_eq!(summary["strategy"], "single_pool");
+        assert_eq!(summary["amount_in"], 1_000_000);
+        assert_eq!(summary["amount_out"], 50_000_000);
+        assert_eq!(summary["impact_bps"], 25);
+
+        let step: Value = serde_json::from_str(&lines[1]).unwrap();
+        assert_eq!(step["event"], "step");
+        assert_eq!(step["dex"], "Raydium");
+        for key in ["strategy", "step", "pool", "amount_in", "amount_out", "impact_bps"] {
+            assert!(step.get(key).is_some(), "missing {}", key);
+        }
+    }
+
+    #[test]
+    fn test_plain_messages_are_wrapped() {
+        let line = format_json_record(Level::Warn, "router_bot", "📊 Found 3 pools");
+        let record: Value = serde_json::from_str(&line).unwrap();
+
+        assert_eq!(record["level"], "WARN");
+        assert_eq!(record["target"], "router_bot");
+        assert_eq!(record["message"], "📊 Found 3 pools");
+
+        // JSON-looking messages from other targets are not merged
+        let line = format_json_record(Level::Info, "router_bot", "{\"event\":\"quote\"}");
+        let record: Value = serde_json::from_str(&line).unwrap();
+        assert!(record.get("event").is_none());
+    }
+}
@@ -3,25 +3,19 @@
 //! A bot that finds optimal swap routes across multiple Solana DEXes
 
 use clap::Parser;
-use log::{error, info};
+use log::{error, info, warn};
+use router_bot::router::RouterOptions;
 use router_bot::*;
+use serde::Serialize;
 use solana_sdk::pubkey::Pubkey;
-use std::str::FromStr;
+use std::collections::{HashMap, HashSet};
 
 fn main() {
     // Parse CLI arguments
     let args = config::CliArgs::parse();
 
     // Initialize logger
-    if args.verbose {
-        env_logger::Builder::from_default_env()
-            .filter_level(log::LevelFilter::Debug)
-            .init();
-    } else {
-        env_logger::Builder::from_default_env()
-            .filter_level(log::LevelFilter::Info)
-            .init();
-    }
+    logging::init_logger(args.verbose, &args.log_format);
 
     // Run the bot
     if let Err(e) = run(args) {
@@ -35,36 +29,52 @@ fn run(args: config::CliArgs) -> Result<()> {
 
     // Load configuration
     let config = Config::from_args(args.clone())?;
-    info!("📡 Connecting to {}", config.network);
-
-    // Create client
-    let client = SolanaClient::new(config.rpc_url.clone());
 
-    // Test connection
-    match client.get_version() {
-        Ok(version) => info!("✅ Connected to Solana (version: {})", version),
-        Err(e) => {
-            error!("❌ Failed to connect to Solana: {}", e);
-            return Err(e.into());
+    // Create client and test the connection, unless quoting offline
+    let client = if config.offline {
+        info!("📴 Offline mode - using local pool data only");
+        None
+    } else {
+        info!("📡 Connecting to {}", config.network);
+        let client = SolanaClient::new_with_commitment(config.rpc_url.clone(), config.commitment);
+
+        match client.get_version() {
+            Ok(version) => info!("✅ Connected to Solana (version: {})", version),
+            Err(e) => {
+                error!("❌ Failed to connect to Solana: {}", e);
+                return Err(e.into());
+            }
         }
-    }
+
+        Some(client)
+    };
 
     // Check if we're running in demo mode or actual swap mode
     if args.token_in.is_some() && args.token_out.is_some() && args.amount.is_some() {
+        if args.compare_dexes {
+            // Report mode: compare DEXes without routing or executing
+            return run_compare_dexes(client.as_ref(), &config, &args);
+        }
+
         // Actual swap mode
-        run_swap(&client, &config, &args)
+        run_swap(client.as_ref(), &config, &args).map(|_| ())
     } else {
         // Demo mode - show example routes
-        run_demo(&client, &config)
+        run_demo(client.as_ref(), &config)
     }
 }
 
-fn run_swap(client: &SolanaClient, config: &Config, args: &config::CliArgs) -> Result<()> {
-    let token_in = Pubkey::from_str(args.token_in.as_ref().unwrap())
-        .map_err(|e| RouterError::InvalidAccountData(e.to_string()))?;
-    let token_out = Pubkey::from_str(args.token_out.as_ref().unwrap())
-        .map_err(|e| RouterError::InvalidAccountData(e.to_string()))?;
-    let amount_in = args.amount.unwrap();
+/// Find, print, and execute the best route; returns the quote that was used
+///
+/// `client` is `None` in offline mode, in which case no RPC calls are made.
+fn run_swap(
+    client: Option<&SolanaClient>,
+    config: &Config,
+    args: &config::CliArgs,
+) -> Result<types::SwapQuote> {
+    let token_in = types::parse_pubkey(args.token_in.as_ref().unwrap(), "token_in")?;
+    let token_out = types::parse_pubkey(args.token_out.as_ref().unwrap(), "token_out")?;
+    let amount_in = parse_amount(client, &token_in, args.amount.as_ref().unwrap())?;
 
     info!("💱 Finding routes for swap:");
     info!("   Token In:  {}", token_in);
@@ -72,68 +82,263 @@ fn run_swap(client: &SolanaClient, config: &Config, args: &config::CliArgs) -> R
     info!("   Amount:    {}", amount_in);
     info!("   Strategy:  {}", config.strategy);
 
-    // Create example pools (in production, these would be fetched from chain)
-    let pools = create_example_pools(&token_in, &token_out);
+    let pools = load_pools(config, &token_in, &token_out)?;
+
+    // Find best route based on strategy
+    let options = config.router_options();
+    let quote = match select_route(
+        &pools,
+        &token_in,
+        &token_out,
+        amount_in,
+        &config.strategy,
+        &options,
+    ) {
+        Ok(quote) => quote,
+        Err(e) => {
+            // Tell the user how far the impact limit is from the best available route
+            if let Some(max_impact_bps) = options.max_price_impact_bps {
+                let unlimited = RouterOptions {
+                    max_price_impact_bps: None,
+                    ..options.clone()
+                };
+                if let Ok(best) = select_route(
+                    &pools,
+                    &token_in,
+                    &token_out,
+                    amount_in,
+                    &config.strategy,
+                    &unlimited,
+                ) && best.price_impact_bps > max_impact_bps
+                {
+                    return Err(impact_limit_error(best.price_impact_bps, max_impact_bps));
+                }
+            }
+            return Err(e);
+        }
+    };
+
+    enforce_min_output(config, &quote)?;
+
+    // Display results
+    if config.output == "json" {
+        print_quote_json(&quote, config.slippage_bps)?;
+    }
+    if config.log_format == "json" {
+        logging::log_quote(&quote);
+    } else if config.output == "text" {
+        let decimals = client
+            .map(|client| route_token_decimals(client, &quote))
+            .unwrap_or_default();
+        print_quote(&quote, &decimals);
+    }
+
+    // Execute if not dry run
+    let mut executor = match client {
+        Some(client) => executor::Executor::new(client.clone(), config.dry_run)
+            .with_on_chain_simulation(config.simulate_on_chain),
+        None => executor::Executor::offline(),
+    }
+    .with_slippage(config.slippage_bps)
+    .with_pools(&pools);
+    if let Some(fee) = config.priority_fee_micro_lamports {
+        let limit = config
+            .compute_unit_limit
+            .unwrap_or_else(|| quote.estimated_compute_units());
+        executor = executor.with_priority_fee(fee, limit);
+    }
+    if let Some(bps) = config.referral_fee_bps {
+        executor = executor.with_referral_fee(bps)?;
+    }
+    if let Some(signer) = config.load_signer()? {
+        executor = executor.with_signer(signer);
+    }
+    let result = executor.execute(&quote)?;
+
+    if result.success {
+        info!("{}", result.explain());
+    } else {
+        error!("{}", result.explain());
+    }
+
+    Ok(quote)
+}
+
+/// Load pools from `--pools-file`, or fall back to example pools
+/// (in production, these would be fetched from chain)
+fn load_pools(
+    config: &Config,
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+) -> Result<Vec<Box<dyn types::Pool>>> {
+    let pools = match &config.pools_file {
+        Some(path) => {
+            info!("📂 Loading pools from {}", path.display());
+            dex::load_pools_from_file(path)?
+        }
+        None => create_example_pools(token_in, token_out),
+    };
 
     if pools.is_empty() {
         error!("❌ No pools found for this token pair");
-        return Err(RouterError::NoRouteFound.into());
+        return Err(RouterError::NoRouteFound {
+            reason: NoRouteReason::NoMatchingPool,
+        }
+        .into());
     }
 
     info!("📊 Found {} pools", pools.len());
+    Ok(pools)
+}
 
-    // Find best route based on strategy
-    let quote = match config.strategy.as_str() {
+/// Print each DEX's best single-pool quote for the requested swap, best first
+fn run_compare_dexes(
+    client: Option<&SolanaClient>,
+    config: &Config,
+    args: &config::CliArgs,
+) -> Result<()> {
+    let token_in = types::parse_pubkey(args.token_in.as_ref().unwrap(), "token_in")?;
+    let token_out = types::parse_pubkey(args.token_out.as_ref().unwrap(), "token_out")?;
+    let amount_in = parse_amount(client, &token_in, args.amount.as_ref().unwrap())?;
+
+    let pools = load_pools(config, &token_in, &token_out)?;
+    let by_dex = router::compare_by_dex(&pools, &token_in, &token_out, amount_in);
+    if by_dex.is_empty() {
+        return Err(RouterError::NoRouteFound {
+            reason: NoRouteReason::NoMatchingPool,
+        }
+        .into());
+    }
+
+    if config.output == "json" {
+        let rows: Vec<serde_json::Value> = by_dex
+            .iter()
+            .map(|(dex, quote)| serde_json::json!({ "dex": dex, "quote": quote }))
+            .collect();
+        let json = serde_json::to_string(&rows).map_err(|e| RouterError::Other(e.into()))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    info!("\n🏁 Best single-pool quote per DEX ({} in):", amount_in);
+    info!("   {:<10} {:>20} {:>8} {:>8}", "DEX", "Output", "Fee", "Impact");
+    for (dex, quote) in &by_dex {
+        let step = &quote.route.steps[0];
+        info!(
+            "   {:<10} {:>20} {:>7.2}% {:>7.2}%",
+            dex,
+            quote.amount_out,
+            step.fee_bps as f64 / 100.0,
+            quote.price_impact_bps as f64 / 100.0
+        );
+    }
+
+    Ok(())
+}
+
+/// Reject `quote` if its output is below the configured share of its input
+///
+/// Thin or broken pools can still produce a quote; without this check it would
+/// be reported (and executed) as if it were a sensible trade.
+fn enforce_min_output(config: &Config, quote: &types::SwapQuote) -> Result<()> {
+    let Some(min_output) = config.min_output_for(quote.amount_in) else {
+        return Ok(());
+    };
+
+    if quote.amount_out < min_output {
+        warn!(
+            "⚠️  Best quote returns {} for {} in, below the minimum of {} ({:.2}% of input)",
+            quote.amount_out,
+            quote.amount_in,
+            min_output,
+            config.min_output_bps_of_input.unwrap_or_default() as f64 / 100.0
+        );
+        return Err(RouterError::NoRouteFound {
+            reason: NoRouteReason::InsufficientLiquidity,
+        });
+    }
+
+    Ok(())
+}
+
+/// Find the best route with the named strategy (single, split, multihop, or all)
+fn select_route(
+    pools: &[Box<dyn types::Pool>],
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    amount_in: u64,
+    strategy: &str,
+    options: &RouterOptions,
+) -> Result<types::SwapQuote> {
+    match strategy {
         "single" => {
             info!("🔍 Using single pool strategy");
-            router::SinglePoolRouter::find_best_route(&pools, &token_in, &token_out, amount_in)?
+            router::SinglePoolRouter::find_best_route_with_options(
+                pools, token_in, token_out, amount_in, options,
+            )
         }
         "split" => {
             info!("🔍 Using split routing strategy");
-            router::SplitRouter::find_best_route(&pools, &token_in, &token_out, amount_in)?
+            router::SplitRouter::find_best_route_with_options(
+                pools, token_in, token_out, amount_in, options,
+            )
         }
         "multihop" => {
             info!("🔍 Using multi-hop routing strategy");
-            router::MultiHopRouter::find_best_route(
-                &pools,
-                &token_in,
-                &token_out,
-                amount_in,
-                config.max_hops,
-            )?
+            router::MultiHopRouter::find_best_route_with_options(
+                pools, token_in, token_out, amount_in, options,
+            )
         }
         "all" => {
             info!("🔍 Comparing all routing strategies");
-            find_best_overall_route(&pools, &token_in, &token_out, amount_in, config.max_hops)?
+            router::find_best_overall_route(pools, token_in, token_out, amount_in, options)
+        }
+        "best_of" => {
+            info!("🔍 Comparing all routing strategies in parallel");
+            router::find_best_overall_route_parallel(
+                pools, token_in, token_out, amount_in, options,
+            )
         }
         _ => {
-            error!("❌ Unknown strategy: {}", config.strategy);
-            return Err(
-                RouterError::ConfigError(format!("Unknown strategy: {}", config.strategy)).into(),
-            );
+            error!("❌ Unknown strategy: {}", strategy);
+            Err(RouterError::ConfigError(format!("Unknown strategy: {}", strategy)))
         }
-    };
-
-    // Display results
-    print_quote(&quote);
+    }
+}
 
-    // Execute if not dry run
-    let executor = executor::Executor::new(client.clone(), config.dry_run);
-    let result = executor.execute(&quote)?;
+/// Error for a swap whose every route exceeds `--max-impact-bps`
+fn impact_limit_error(best_impact_bps: u16, max_impact_bps: u16) -> RouterError {
+    RouterError::InvalidQuote(format!(
+        "best available route has {:.2}% price impact, above the {:.2}% limit \
+         (raise --max-impact-bps to at least {})",
+        best_impact_bps as f64 / 100.0,
+        max_impact_bps as f64 / 100.0,
+        best_impact_bps
+    ))
+}
 
-    if result.success {
-        info!("✅ Swap completed successfully!");
-        if let Some(sig) = result.signature {
-            info!("   Transaction: {}", sig);
-        }
-    } else {
-        error!("❌ Swap failed: {:?}", result.error);
+/// Parse `--amount`, treating decimal input as whole tokens of the input mint
+fn parse_amount(client: Option<&SolanaClient>, token_in: &Pubkey, amount: &str) -> Result<u64> {
+    if !amount.contains('.')
+        && let Ok(raw) = amount.parse::<u64>()
+    {
+        return Ok(raw);
     }
 
-    Ok(())
+    let Some(client) = client else {
+        return Err(RouterError::ConfigError(format!(
+            "Cannot resolve decimals for amount {} offline - pass raw units instead",
+            amount
+        ))
+        .into());
+    };
+    let decimals = client.fetch_mint_cached(token_in)?.decimals;
+    let raw = config::amount_to_raw(amount, decimals)?;
+    info!("   {} tokens = {} raw units ({} decimals)", amount, raw, decimals);
+    Ok(raw)
 }
 
-fn run_demo(_client: &SolanaClient, config: &Config) -> Result<()> {
+fn run_demo(_client: Option<&SolanaClient>, config: &Config) -> Result<()> {
     info!("🎯 Running in demo mode");
     info!("   Use --token-in, --token-out, and --amount for actual swaps");
 
@@ -147,6 +352,16 @@ fn run_demo(_client: &SolanaClient, config: &Config) -> Result<()> {
     let pools = create_example_pools(&token_a, &token_b);
     info!("   Created {} example pools", pools.len());
 
+    if config.output == "json" || config.min_output_bps_of_input.is_some() {
+        let options = config.router_options();
+        let quote = router::find_best_overall_route(&pools, &token_a, &token_b, amount, &options)?;
+        enforce_min_output(config, &quote)?;
+
+        if config.output == "json" {
+            return print_quote_json(&quote, config.slippage_bps);
+        }
+    }
+
     // Compare strategies
     info!("\n🔍 Comparing routing strategies:");
 
@@ -200,14 +415,17 @@ fn create_example_pools(token_a: &Pubkey, token_b: &Pubkey) -> Vec<Box<dyn types
             2_000_000_000,
             100_000_000_000,
         )),
-        Box::new(OrcaPool::new_whirlpool(
-            Pubkey::new_unique(),
-            *token_a,
-            *token_b,
-            1_500_000_000,
-            75_000_000_000,
-            10,
-        )),
+        Box::new(
+            OrcaPool::new_whirlpool(
+                Pubkey::new_unique(),
+                *token_a,
+                *token_b,
+                1_500_000_000,
+                75_000_000_000,
+                10,
+            )
+            .unwrap(),
+        ),
         Box::new(MeteoraPool::new(
             Pubkey::new_unique(),
             *token_a,
@@ -219,58 +437,24 @@ fn create_example_pools(token_a: &Pubkey, token_b: &Pubkey) -> Vec<Box<dyn types
     ]
 }
 
-fn find_best_overall_route(
-    pools: &[Box<dyn types::Pool>],
-    token_in: &Pubkey,
-    token_out: &Pubkey,
-    amount_in: u64,
-    max_hops: usize,
-) -> Result<types::SwapQuote> {
-    let mut best_quote: Option<types::SwapQuote> = None;
-
-    // Try single pool
-    if let Ok(quote) = router::SinglePoolRouter::find_best_route(pools, token_in, token_out, amount_in) {
-        info!("   Single pool: {} output", quote.amount_out);
-        best_quote = Some(quote);
-    }
-
-    // Try split routing
-    if let Ok(quote) = router::SplitRouter::find_best_route(pools, token_in, token_out, amount_in) {
-        info!("   Split routing: {} output", quote.amount_out);
-        best_quote = match best_quote {
-            None => Some(quote),
-            Some(current) => {
-                if quote.better_than(&current) {
-                    Some(quote)
-                } else {
-                    Some(current)
-                }
-            }
-        };
-    }
-
-    // Try multi-hop
-    if let Ok(quote) =
-        router::MultiHopRouter::find_best_route(pools, token_in, token_out, amount_in, max_hops)
-    {
-        info!("   Multi-hop: {} output", quote.amount_out);
-        best_quote = match best_quote {
-            None => Some(quote),
-            Some(current) => {
-                if quote.better_than(&current) {
-                    Some(quote)
-                } else {
-                    Some(current)
-                }
-            }
-        };
-    }
-
-    best_quote.ok_or_else(|| RouterError::NoRouteFound.into())
+/// Decimals of every mint along the route, skipping mints that cannot be fetched
+fn route_token_decimals(client: &SolanaClient, quote: &types::SwapQuote) -> HashMap<Pubkey, u8> {
+    let mints: HashSet<Pubkey> = quote
+        .route
+        .steps
+        .iter()
+        .flat_map(|step| [step.token_in, step.token_out])
+        .collect();
+
+    mints
+        .into_iter()
+        .filter_map(|mint| Some((mint, client.fetch_mint_cached(&mint).ok()?.decimals)))
+        .collect()
 }
 
-fn print_quote(quote: &types::SwapQuote) {
+fn print_quote(quote: &types::SwapQuote, decimals: &HashMap<Pubkey, u8>) {
     info!("\n💰 Best Route Found:");
+    info!("   Route:         {}", quote.to_compact_string(decimals));
     info!("   Strategy:      {}", quote.strategy);
     info!("   Input Amount:  {}", quote.amount_in);
     info!("   Output Amount: {}", quote.amount_out);
@@ -279,6 +463,8 @@ fn print_quote(quote: &types::SwapQuote) {
         quote.price_impact_bps as f64 / 100.0
     );
     info!("   Hops:          {}", quote.route.hop_count());
+    info!("   Input Fees:    {}", quote.route.total_fees_in_input_token());
+    info!("   Est. Compute:  {} CU", quote.estimated_compute_units());
 
     for (idx, step) in quote.route.steps.iter().enumerate() {
         info!("\n   Step {}:", idx + 1);
@@ -287,9 +473,141 @@ fn print_quote(quote: &types::SwapQuote) {
         info!("      Amount In:     {}", step.amount_in);
         info!("      Amount Out:    {}", step.amount_out);
         info!("      Fee:           {:.2}%", step.fee_bps as f64 / 100.0);
+        info!("      Fee Amount:    {}", step.fee_amount);
         info!(
             "      Price Impact:  {:.2}%",
             step.price_impact_bps as f64 / 100.0
         );
     }
 }
+
+/// Quote as emitted by `--output json`
+#[derive(Serialize)]
+struct QuoteOutput<'a> {
+    #[serde(flatten)]
+    quote: &'a types::SwapQuote,
+    minimum_out: u64,
+}
+
+fn print_quote_json(quote: &types::SwapQuote, slippage_bps: u16) -> Result<()> {
+    let output = QuoteOutput {
+        quote,
+        minimum_out: quote.minimum_out(slippage_bps),
+    };
+
+    let json = serde_json::to_string(&output).map_err(|e| RouterError::Other(e.into()))?;
+    println!("{}", json);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offline_run_swap_produces_quote() {
+        let token_in = Pubkey::new_unique().to_string();
+        let token_out = Pubkey::new_unique().to_string();
+        let args = config::CliArgs::parse_from([
+            "router-bot",
+            "--offline",
+            // Unroutable RPC URL: any network access would fail the test
+            "--rpc-url",
+            "http://127.0.0.1:1",
+            "--token-in",
+            &token_in,
+            "--token-out",
+            &token_out,
+            "--amount",
+            "10000000",
+        ]);
+        let config = Config::from_args(args.clone()).unwrap();
+        assert!(config.dry_run);
+
+        let quote = run_swap(None, &config, &args).unwrap();
+
+        assert_eq!(quote.amount_in, 10_000_000);
+        assert!(quote.amount_out > 0);
+    }
+
+    #[test]
+    fn test_max_impact_rejection_reports_best_impact() {
+        let token_in = Pubkey::new_unique().to_string();
+        let token_out = Pubkey::new_unique().to_string();
+        let args = config::CliArgs::parse_from([
+            "router-bot",
+            "--offline",
+            "--token-in",
+            &token_in,
+            "--token-out",
+            &token_out,
+            "--amount",
+            "10000000",
+            "--max-impact-bps",
+            "1",
+        ]);
+        let config = Config::from_args(args.clone()).unwrap();
+
+        let Err(RouterError::InvalidQuote(message)) = run_swap(None, &config, &args) else {
+            panic!("expected the impact limit to reject every route");
+        };
+        assert!(message.contains("best available route has"));
+        assert!(message.contains("--max-impact-bps"));
+    }
+
+    #[test]
+    fn test_offline_rejects_decimal_amount() {
+        let result = parse_amount(None, &Pubkey::new_unique(), "1.5");
+        assert!(matches!(result, Err(RouterError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_min_output_threshold_rejects_thin_pools() {
+        let token_in = Pubkey::new_unique();
+        let token_out = Pubkey::new_unique();
+        let dir = std::env::temp_dir();
+        let pools_path = dir.join("router-bot-thin-pools.toml");
+        let config_path = dir.join("router-bot-min-output.toml");
+        std::fs::write(
+            &pools_path,
+            format!(
+                "[[pools]]\ndex = \"Raydium\"\naddress = \"{}\"\ntoken_a = \"{}\"\n\
+                 token_b = \"{}\"\nreserve_a = 1000000\nreserve_b = 1000000\n",
+                Pubkey::new_unique(),
+                token_in,
+                token_out
+            ),
+        )
+        .unwrap();
+        std::fs::write(&config_path, "[routing]\nmin_output_bps_of_input = 5000\n").unwrap();
+
+        let (token_in, token_out) = (token_in.to_string(), token_out.to_string());
+        let args = config::CliArgs::parse_from([
+            "router-bot",
+            "--offline",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--pools-file",
+            pools_path.to_str().unwrap(),
+            "--token-in",
+            &token_in,
+            "--token-out",
+            &token_out,
+            "--amount",
+            "10000000",
+        ]);
+        let config = Config::from_args(args.clone());
+        let result = config.as_ref().ok().map(|config| run_swap(None, config, &args));
+        std::fs::remove_file(&pools_path).unwrap();
+        std::fs::remove_file(&config_path).unwrap();
+
+        assert_eq!(config.unwrap().min_output_bps_of_input, Some(5000));
+        // 10x the pool's depth can't return even half the input
+        assert!(matches!(
+            result.unwrap(),
+            Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::InsufficientLiquidity
+            })
+        ));
+    }
+}
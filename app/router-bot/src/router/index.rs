@@ -0,0 +1,219 @@
+//! Token-pair index over a pool set
+//!
+//! Routers otherwise scan every pool on each query to find the ones trading a
+//! pair. When routing is called repeatedly over a large, fixed pool set, build a
+//! `PoolIndex` once and pass it to the `find_best_route_indexed` variants.
+
+use crate::types::pool::{Pool, normalize_pair};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+
+/// Pool positions grouped by token pair
+#[derive(Debug, Clone, Default)]
+pub struct PoolIndex {
+    by_pair: HashMap<(Pubkey, Pubkey), Vec<usize>>,
+}
+
+impl PoolIndex {
+    /// Index `pools` by their (order-independent) token pair
+    ///
+    /// Like `dedupe_pools`, only the first pool with a given address is indexed.
+    pub fn new(pools: &[Box<dyn Pool>]) -> Self {
+        let mut by_pair: HashMap<(Pubkey, Pubkey), Vec<usize>> = HashMap::new();
+        let mut seen: HashSet<Pubkey> = HashSet::with_capacity(pools.len());
+
+        for (idx, pool) in pools.iter().enumerate() {
+            if !seen.insert(*pool.address()) {
+                continue;
+            }
+            by_pair
+                .entry(pool.pair_key())
+                .or_default()
+                .push(idx);
+        }
+
+        Self { by_pair }
+    }
+
+    /// Positions of the pools trading `token_in`/`token_out`, in either direction
+    pub fn matching(&self, token_in: &Pubkey, token_out: &Pubkey) -> &[usize] {
+        self.by_pair
+            .get(&normalize_pair(token_in, token_out))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Number of distinct token pairs
+    pub fn pair_count(&self) -> usize {
+        self.by_pair.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::{OrcaPool, RaydiumPool};
+    use crate::router::{RouterOptions, SinglePoolRouter, SplitRouter};
+
+    /// `count` pools over `tokens`, cycling through the pairs
+    fn create_pool_set(tokens: &[Pubkey], count: usize) -> Vec<Box<dyn Pool>> {
+        (0..count)
+            .map(|i| {
+                let token_a = tokens[i % tokens.len()];
+                // Offset in 1..len, so the two mints always differ
+                let offset = 1 + (i / tokens.len()) % (tokens.len() - 1);
+                let token_b = tokens[(i + offset) % tokens.len()];
+                let reserve = 1_000_000_000 + i as u64 * 1_000_000;
+                if i % 2 == 0 {
+                    Box::new(RaydiumPool::new(
+                        Pubkey::new_unique(),
+                        token_a,
+                        token_b,
+                        reserve,
+                        reserve * 50,
+                    )) as Box<dyn Pool>
+                } else {
+                    Box::new(OrcaPool::new_constant_product(
+                        Pubkey::new_unique(),
+                        token_b,
+                        token_a,
+                        reserve * 50,
+                        reserve,
+                    )) as Box<dyn Pool>
+                }
+            })
+            .collect()
+    }
+
+    fn linear_scan(pools: &[Box<dyn Pool>], token_in: &Pubkey, token_out: &Pubkey) -> Vec<usize> {
+        pools
+            .iter()
+            .enumerate()
+            .filter(|(_, pool)| {
+                (pool.token_a() == token_in && pool.token_b() == token_out)
+                    || (pool.token_b() == token_in && pool.token_a() == token_out)
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    #[test]
+    fn test_index_matches_linear_scan() {
+        let tokens: Vec<Pubkey> = (0..8).map(|_| Pubkey::new_unique()).collect();
+        let pools = create_pool_set(&tokens, 300);
+        let index = PoolIndex::new(&pools);
+
+        for token_in in &tokens {
+            for token_out in &tokens {
+                if token_in == token_out {
+                    continue;
+                }
+                assert_eq!(
+                    index.matching(token_in, token_out),
+                    linear_scan(&pools, token_in, token_out).as_slice()
+                );
+                assert_eq!(
+                    index.matching(token_in, token_out),
+                    index.matching(token_out, token_in)
+                );
+            }
+        }
+
+        assert!(index.matching(&tokens[0], &Pubkey::new_unique()).is_empty());
+    }
+
+    #[test]
+    fn test_indexed_routers_match_linear_scan() {
+        let tokens: Vec<Pubkey> = (0..8).map(|_| Pubkey::new_unique()).collect();
+        let pools = create_pool_set(&tokens, 300);
+        let index = PoolIndex::new(&pools);
+        let options = RouterOptions::default();
+        let (token_in, token_out) = (&tokens[0], &tokens[1]);
+        let amount_in = 100_000_000;
+
+        let linear = SinglePoolRouter::find_best_route_with_options(
+            &pools, token_in, token_out, amount_in, &options,
+        )
+        .unwrap();
+        let indexed = SinglePoolRouter::find_best_route_indexed(
+            &pools, &index, token_in, token_out, amount_in, &options,
+        )
+        .unwrap();
+        assert_eq!(indexed.amount_out, linear.amount_out);
+        assert_eq!(indexed.route.steps[0].pool_address, linear.route.steps[0].pool_address);
+
+        let linear = SplitRouter::find_best_route_with_options(
+            &pools, token_in, token_out, amount_in, &options,
+        )
+        .unwrap();
+        let indexed = SplitRouter::find_best_route_indexed(
+            &pools, &index, token_in, token_out, amount_in, &options,
+        )
+        .unwrap();
+        assert_eq!(indexed.amount_out, linear.amount_out);
+        assert_eq!(indexed.route.steps.len(), linear.route.steps.len());
+    }
+
+    #[test]
+    fn test_indexed_routers_honour_deepest_pool_per_dex() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let shallow = Pubkey::new_unique();
+        let deep = Pubkey::new_unique();
+        let pools: Vec<Box<dyn Pool>> = vec![
+            // Best price, but not the deepest Raydium pool for the pair
+            Box::new(RaydiumPool::new(shallow, token_a, token_b, 10_000_000, 600_000_000)),
+            Box::new(RaydiumPool::new(deep, token_a, token_b, 1_000_000_000, 50_000_000_000)),
+            Box::new(OrcaPool::new_constant_product(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                500_000_000,
+                25_000_000_000,
+            )),
+        ];
+        let index = PoolIndex::new(&pools);
+        let options = RouterOptions::default().with_deepest_pool_per_dex();
+        let amount_in = 100_000;
+
+        let linear = SinglePoolRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, amount_in, &options,
+        )
+        .unwrap();
+        let indexed = SinglePoolRouter::find_best_route_indexed(
+            &pools, &index, &token_a, &token_b, amount_in, &options,
+        )
+        .unwrap();
+        assert_eq!(indexed.amount_out, linear.amount_out);
+        assert_eq!(indexed.route.steps[0].pool_address, deep);
+
+        let linear = SplitRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, amount_in, &options,
+        )
+        .unwrap();
+        let indexed = SplitRouter::find_best_route_indexed(
+            &pools, &index, &token_a, &token_b, amount_in, &options,
+        )
+        .unwrap();
+        assert_eq!(indexed.amount_out, linear.amount_out);
+        assert!(indexed.route.steps.iter().all(|step| step.pool_address != shallow));
+    }
+
+    #[test]
+    fn test_index_skips_duplicate_addresses() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pool: Box<dyn Pool> = Box::new(RaydiumPool::new(
+            Pubkey::new_unique(),
+            token_a,
+            token_b,
+            1_000_000_000,
+            50_000_000_000,
+        ));
+        let pools = vec![pool.clone(), pool];
+
+        let index = PoolIndex::new(&pools);
+        assert_eq!(index.matching(&token_a, &token_b), &[0]);
+        assert_eq!(index.pair_count(), 1);
+    }
+}
@@ -1,7 +1,1449 @@
 pub mod single;
 pub mod split;
 pub mod multihop;
+pub mod index;
 
 pub use single::SinglePoolRouter;
 pub use split::SplitRouter;
 pub use multihop::MultiHopRouter;
+pub use index::PoolIndex;
+
+use crate::error::{NoRouteReason, Result, RouterError};
+use crate::router::multihop::RouteEdge;
+use crate::router::split::DEFAULT_MAX_SPLIT_POOLS;
+use crate::types::pool::Pool;
+use crate::types::route::SwapQuote;
+use log::debug;
+use solana_sdk::pubkey::Pubkey;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Options shared by all routers
+#[derive(Debug, Clone)]
+pub struct RouterOptions {
+    /// Maximum number of hops for multi-hop routing
+    pub max_hops: usize,
+    /// Discard candidate routes whose price impact exceeds this many basis points
+    pub max_price_impact_bps: Option<u16>,
+    /// Only route through pools on these DEXes (matched against `Pool::dex_name`);
+    /// `None` or an empty list allows every DEX
+    pub allowed_dexes: Option<Vec<String>>,
+    /// Never route through pools on these DEXes, even if allowlisted
+    pub excluded_dexes: Option<Vec<String>>,
+    /// Never route through these pool addresses
+    pub exclude_pools: Option<Vec<Pubkey>>,
+    /// Cap on the share of a split routed through any single pool, in basis points
+    /// of the input (only honoured by `SplitRouter`)
+    pub max_pool_fraction_bps: Option<u16>,
+    /// Skip pools whose reserves were last updated more than this many
+    /// milliseconds ago (pools with no update time are skipped too)
+    pub max_pool_age_ms: Option<u64>,
+    /// Treat single-pool quotes whose outputs are within this many basis points
+    /// of each other as tied, and prefer the lower-fee pool among them
+    pub tie_tolerance_bps: Option<u16>,
+    /// Only accept multi-hop routes that pass through this token (only honoured
+    /// by `MultiHopRouter`; its shortest-path search rejects it)
+    pub required_intermediate: Option<Pubkey>,
+    /// Split across at most this many pools, keeping the healthiest (only
+    /// honoured by `SplitRouter`); `DEFAULT_MAX_SPLIT_POOLS` by default, and
+    /// `None` splits across every matching pool
+    pub max_split_pools: Option<usize>,
+    /// Keep only the deepest pool per DEX and token pair (see `select_deepest_per_dex`)
+    pub deepest_pool_per_dex: bool,
+    /// Discount a quote's output by this many basis points per hop after the
+    /// first when comparing routes (see `SwapQuote::better_than_with_hop_penalty`)
+    pub hop_penalty_bps: u16,
+}
+
+impl Default for RouterOptions {
+    fn default() -> Self {
+        Self {
+            max_hops: 2,
+            max_price_impact_bps: None,
+            allowed_dexes: None,
+            excluded_dexes: None,
+            exclude_pools: None,
+            max_pool_fraction_bps: None,
+            max_pool_age_ms: None,
+            tie_tolerance_bps: None,
+            required_intermediate: None,
+            max_split_pools: Some(DEFAULT_MAX_SPLIT_POOLS),
+            deepest_pool_per_dex: false,
+            hop_penalty_bps: 0,
+        }
+    }
+}
+
+impl RouterOptions {
+    /// Set the maximum number of hops for multi-hop routing
+    pub fn with_max_hops(mut self, max_hops: usize) -> Self {
+        self.max_hops = max_hops;
+        self
+    }
+
+    /// Reject routes with more than `bps` price impact
+    pub fn with_max_price_impact(mut self, bps: u16) -> Self {
+        self.max_price_impact_bps = Some(bps);
+        self
+    }
+
+    /// Restrict routing to pools on the given DEXes
+    pub fn with_allowed_dexes(mut self, dexes: Vec<String>) -> Self {
+        self.allowed_dexes = Some(dexes);
+        self
+    }
+
+    /// Skip pools on the given DEXes
+    pub fn with_excluded_dexes(mut self, dexes: Vec<String>) -> Self {
+        self.excluded_dexes = Some(dexes);
+        self
+    }
+
+    /// Never route through the given pools
+    pub fn with_excluded_pools(mut self, pools: Vec<Pubkey>) -> Self {
+        self.exclude_pools = Some(pools);
+        self
+    }
+
+    /// Route at most `bps` of a split through any one pool
+    pub fn with_max_pool_fraction(mut self, bps: u16) -> Self {
+        self.max_pool_fraction_bps = Some(bps);
+        self
+    }
+
+    /// Skip pools with reserve data older than `max_age_ms`
+    pub fn with_max_pool_age(mut self, max_age_ms: u64) -> Self {
+        self.max_pool_age_ms = Some(max_age_ms);
+        self
+    }
+
+    /// Prefer the lower-fee pool when single-pool outputs are within `bps`
+    pub fn with_tie_tolerance(mut self, bps: u16) -> Self {
+        self.tie_tolerance_bps = Some(bps);
+        self
+    }
+
+    /// Only accept multi-hop routes that pass through `token`
+    pub fn with_required_intermediate(mut self, token: Pubkey) -> Self {
+        self.required_intermediate = Some(token);
+        self
+    }
+
+    /// Split across at most `max_pools` pools
+    pub fn with_max_split_pools(mut self, max_pools: usize) -> Self {
+        self.max_split_pools = Some(max_pools);
+        self
+    }
+
+    /// Route through only the deepest pool per DEX and token pair
+    pub fn with_deepest_pool_per_dex(mut self) -> Self {
+        self.deepest_pool_per_dex = true;
+        self
+    }
+
+    /// Make each hop after the first cost `bps` of output when comparing routes
+    pub fn with_hop_penalty(mut self, bps: u16) -> Self {
+        self.hop_penalty_bps = bps;
+        self
+    }
+
+    /// Whether a route with the given price impact is acceptable
+    pub(crate) fn allows_price_impact(&self, price_impact_bps: u16) -> bool {
+        self.max_price_impact_bps
+            .is_none_or(|max| price_impact_bps <= max)
+    }
+
+    /// Whether a pool may be considered at all
+    pub(crate) fn allows_pool(&self, pool: &dyn Pool) -> bool {
+        let dex_name = pool.dex_name();
+
+        let dex_allowed = self
+            .allowed_dexes
+            .as_ref()
+            .is_none_or(|dexes| dexes.is_empty() || dexes.iter().any(|dex| dex == dex_name));
+
+        let dex_excluded = self
+            .excluded_dexes
+            .as_ref()
+            .is_some_and(|dexes| dexes.iter().any(|dex| dex == dex_name));
+
+        let pool_excluded = self
+            .exclude_pools
+            .as_ref()
+            .is_some_and(|excluded| excluded.contains(pool.address()));
+
+        let fresh = self
+            .max_pool_age_ms
+            .is_none_or(|max_age| pool.age_ms().is_some_and(|age| age <= max_age));
+
+        dex_allowed && !dex_excluded && !pool_excluded && fresh
+    }
+}
+
+/// Reject a "swap" from a token to itself
+///
+/// A no-op swap isn't a route: without this check the routers would report a
+/// missing pool, or the multi-hop search would return a cycle.
+pub(crate) fn ensure_distinct_tokens(token_in: &Pubkey, token_out: &Pubkey) -> Result<()> {
+    if token_in == token_out {
+        return Err(RouterError::IdenticalTokens);
+    }
+    Ok(())
+}
+
+/// Order `(pool index, a_to_b)` candidates by `Pool::health_score`, best first
+///
+/// `SplitRouter` uses this to keep the healthiest pools when
+/// `RouterOptions::max_split_pools` drops the rest. Pools that cannot be scored
+/// go last; equal scores keep their original order.
+pub(crate) fn rank_by_health(
+    pools: &[Box<dyn Pool>],
+    candidates: &mut [(usize, bool)],
+    reference_amount: u64,
+) {
+    let mut scored: Vec<(f64, (usize, bool))> = candidates
+        .iter()
+        .map(|&(idx, a_to_b)| {
+            let score = pools[idx].health_score(reference_amount, a_to_b);
+            (score.unwrap_or(f64::NEG_INFINITY), (idx, a_to_b))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    for (slot, (_, candidate)) in candidates.iter_mut().zip(scored) {
+        *slot = candidate;
+    }
+}
+
+/// Drop pools whose address already appeared earlier in `pools`
+///
+/// Passing the same pool twice would let the split router spread an order over
+/// phantom liquidity and overstate the output, so every router dedupes its input
+/// first. The first occurrence of each address is kept; the slice is borrowed
+/// unchanged when it has no duplicates.
+pub fn dedupe_pools(pools: &[Box<dyn Pool>]) -> Cow<'_, [Box<dyn Pool>]> {
+    let mut seen: HashSet<Pubkey> = HashSet::with_capacity(pools.len());
+    if pools.iter().all(|pool| seen.insert(*pool.address())) {
+        return Cow::Borrowed(pools);
+    }
+
+    seen.clear();
+    Cow::Owned(
+        pools
+            .iter()
+            .filter(|pool| seen.insert(*pool.address()))
+            .cloned()
+            .collect(),
+    )
+}
+
+/// Keep only the deepest pool for each `(dex_name, pair_key)`
+///
+/// Depth is `reserve_a + reserve_b`; ties keep the earlier pool. Surviving
+/// pools stay in their original order.
+pub fn select_deepest_per_dex(pools: &[Box<dyn Pool>]) -> Vec<Box<dyn Pool>> {
+    let keep = deepest_per_dex(pools, 0..pools.len());
+    pools
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| keep.contains(idx))
+        .map(|(_, pool)| pool.clone())
+        .collect()
+}
+
+/// Positions of the deepest pool for each `(dex_name, pair_key)` among `positions`
+fn deepest_per_dex(
+    pools: &[Box<dyn Pool>],
+    positions: impl IntoIterator<Item = usize>,
+) -> HashSet<usize> {
+    let depth = |idx: usize| pools[idx].reserve_a() as u128 + pools[idx].reserve_b() as u128;
+
+    let mut deepest: HashMap<(&str, (Pubkey, Pubkey)), usize> = HashMap::new();
+    for idx in positions {
+        let pool = &pools[idx];
+        deepest
+            .entry((pool.dex_name(), pool.pair_key()))
+            .and_modify(|best| {
+                if depth(idx) > depth(*best) {
+                    *best = idx;
+                }
+            })
+            .or_insert(idx);
+    }
+
+    deepest.into_values().collect()
+}
+
+/// Deduped pools, narrowed to the deepest per DEX and pair if `options` asks for it
+pub(crate) fn candidate_pools<'a>(
+    pools: &'a [Box<dyn Pool>],
+    options: &RouterOptions,
+) -> Cow<'a, [Box<dyn Pool>]> {
+    let pools = dedupe_pools(pools);
+    if options.deepest_pool_per_dex {
+        Cow::Owned(select_deepest_per_dex(&pools))
+    } else {
+        pools
+    }
+}
+
+/// The `PoolIndex` positions matching a pair, narrowed as `candidate_pools` would
+///
+/// The index already skips duplicate addresses, so only the deepest-per-DEX
+/// filter is left to apply. Positions keep their index order.
+pub(crate) fn indexed_candidates(
+    pools: &[Box<dyn Pool>],
+    index: &PoolIndex,
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    options: &RouterOptions,
+) -> Vec<usize> {
+    let matching = index.matching(token_in, token_out);
+    if !options.deepest_pool_per_dex {
+        return matching.to_vec();
+    }
+
+    let keep = deepest_per_dex(pools, matching.iter().copied());
+    matching.iter().copied().filter(|idx| keep.contains(idx)).collect()
+}
+
+/// Best single-pool quote on each DEX for a swap, best first
+///
+/// Groups `SinglePoolRouter::find_all_routes` by DEX and keeps each DEX's best
+/// quote, so DEXes can be compared side by side. DEXes with no pool for the
+/// pair (or none with enough liquidity) are left out.
+pub fn compare_by_dex(
+    pools: &[Box<dyn Pool>],
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    amount_in: u64,
+) -> Vec<(String, SwapQuote)> {
+    let mut best: HashMap<String, SwapQuote> = HashMap::new();
+    for quote in SinglePoolRouter::find_all_routes(pools, token_in, token_out, amount_in) {
+        let dex = quote.route.steps[0].dex.clone();
+        match best.get(&dex) {
+            Some(current) if !quote.better_than(current) => {}
+            _ => {
+                best.insert(dex, quote);
+            }
+        }
+    }
+
+    let mut by_dex: Vec<(String, SwapQuote)> = best.into_iter().collect();
+    by_dex.sort_by(|(dex_a, a), (dex_b, b)| b.compare(a).then_with(|| dex_a.cmp(dex_b)));
+    by_dex
+}
+
+/// Routing strategy recommended by `explain_split_decision`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Send the whole swap through the best single pool
+    SinglePool,
+    /// Divide the swap across several pools
+    Split,
+}
+
+impl Strategy {
+    /// The `SwapQuote::strategy` label of quotes produced by this strategy
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::SinglePool => "single_pool",
+            Strategy::Split => "split",
+        }
+    }
+}
+
+/// Single-pool and split outputs for the same swap, side by side
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitDecision {
+    /// Output of the best single pool
+    pub single_output: u64,
+    /// Output of the best split
+    pub split_output: u64,
+    /// How much more the split returns than the single pool, in basis points
+    /// of `single_output` (negative if it returns less)
+    pub improvement_bps: i64,
+    /// Number of distinct pools the split routes through
+    pub pools_used: usize,
+    /// `Split` only if it uses more than one pool and strictly beats the single
+    /// pool; ties go to the simpler single-pool swap
+    pub recommended: Strategy,
+}
+
+/// Compare the best single-pool quote with the best split for a swap
+///
+/// Runs `SinglePoolRouter` and `SplitRouter` with default options and reports
+/// whether splitting pays for itself. Fails if either router finds no route.
+pub fn explain_split_decision(
+    pools: &[Box<dyn Pool>],
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    amount_in: u64,
+) -> Result<SplitDecision> {
+    let single = SinglePoolRouter::find_best_route(pools, token_in, token_out, amount_in)?;
+    let split = SplitRouter::find_best_route(pools, token_in, token_out, amount_in)?;
+
+    let (single_output, split_output) = (single.amount_out, split.amount_out);
+    let improvement_bps = if single_output == 0 {
+        0
+    } else {
+        let difference = split_output as i128 - single_output as i128;
+        (difference * 10000 / single_output as i128) as i64
+    };
+    let pools_used = split
+        .route
+        .steps
+        .iter()
+        .map(|step| step.pool_address)
+        .collect::<HashSet<_>>()
+        .len();
+    let recommended = if pools_used > 1 && split_output > single_output {
+        Strategy::Split
+    } else {
+        Strategy::SinglePool
+    };
+
+    Ok(SplitDecision {
+        single_output,
+        split_output,
+        improvement_bps,
+        pools_used,
+        recommended,
+    })
+}
+
+/// Look for a profitable cycle that starts and ends at `start_token`
+///
+/// Builds the same token graph as `MultiHopRouter` and evaluates every cycle of
+/// up to `max_hops` hops, returning the one with the largest output, or `None`
+/// if no cycle returns more than `amount_in`.
+pub fn find_arbitrage(
+    pools: &[Box<dyn Pool>],
+    start_token: &Pubkey,
+    amount_in: u64,
+    max_hops: usize,
+) -> Option<SwapQuote> {
+    if amount_in == 0 || max_hops < 2 {
+        return None;
+    }
+
+    let graph = MultiHopRouter::build_graph(pools, &RouterOptions::default());
+    let cycles =
+        MultiHopRouter::find_cycles(&graph, start_token, max_hops, multihop::DEFAULT_MAX_PATHS);
+
+    let mut best_quote: Option<SwapQuote> = None;
+
+    for cycle in cycles {
+        let Ok(mut quote) = MultiHopRouter::evaluate_path(&cycle, pools, amount_in) else {
+            continue;
+        };
+
+        if quote.amount_out <= amount_in {
+            continue;
+        }
+
+        quote.strategy = "arbitrage".to_string();
+        best_quote = match best_quote {
+            Some(current) if !quote.better_than(&current) => Some(current),
+            _ => Some(quote),
+        };
+    }
+
+    best_quote
+}
+
+/// Return the first route, trying single pool, then split, then multi-hop, whose
+/// output reaches `min_output`
+///
+/// A cheaper alternative to comparing every strategy: when a single pool already
+/// gives an acceptable quote, the split and multi-hop searches are never run.
+pub fn find_first_viable_route(
+    pools: &[Box<dyn Pool>],
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    amount_in: u64,
+    max_hops: usize,
+    min_output: u64,
+) -> Result<SwapQuote> {
+    first_viable(
+        [
+            &|| SinglePoolRouter::find_best_route(pools, token_in, token_out, amount_in),
+            &|| SplitRouter::find_best_route(pools, token_in, token_out, amount_in),
+            &|| MultiHopRouter::find_best_route(pools, token_in, token_out, amount_in, max_hops),
+        ],
+        min_output,
+    )
+}
+
+/// Run `strategies` in order, returning the first quote that reaches `min_output`
+fn first_viable(
+    strategies: [&dyn Fn() -> Result<SwapQuote>; 3],
+    min_output: u64,
+) -> Result<SwapQuote> {
+    let mut fell_short = false;
+    let mut last_error = None;
+    for strategy in strategies {
+        match strategy() {
+            Ok(quote) if quote.amount_out >= min_output => return Ok(quote),
+            Ok(_) => fell_short = true,
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    // A quote below the threshold is more informative than a later strategy's error
+    match last_error {
+        Some(e) if !fell_short => Err(e),
+        _ => Err(RouterError::NoRouteFound {
+            reason: NoRouteReason::InsufficientLiquidity,
+        }),
+    }
+}
+
+/// Find the top `n` routes across all strategies
+///
+/// Collects every single-pool quote plus the best split and multi-hop quotes,
+/// drops routes whose [`Route::fingerprint`](crate::types::route::Route::fingerprint)
+/// matches a better one, and returns them best first.
+pub fn find_top_routes(
+    pools: &[Box<dyn Pool>],
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    amount_in: u64,
+    max_hops: usize,
+    n: usize,
+) -> Vec<SwapQuote> {
+    let mut candidates = SinglePoolRouter::find_all_routes(pools, token_in, token_out, amount_in);
+
+    if let Ok(quote) = SplitRouter::find_best_route(pools, token_in, token_out, amount_in) {
+        candidates.push(quote);
+    }
+
+    if let Ok(quote) =
+        MultiHopRouter::find_best_route(pools, token_in, token_out, amount_in, max_hops)
+    {
+        candidates.push(quote);
+    }
+
+    // Best first, so deduplication keeps the better of two identical pool sets
+    candidates.sort_by(|a, b| b.compare(a));
+
+    let mut seen: HashSet<u64> = HashSet::new();
+    candidates
+        .into_iter()
+        .filter(|quote| seen.insert(quote.route.fingerprint()))
+        .take(n)
+        .collect()
+}
+
+/// Route one input into a basket of target tokens
+///
+/// `targets` pairs each output mint with its share of `amount_in` in basis
+/// points; the shares must be non-zero and sum to 10000. Each slice is routed
+/// with the best of the single-pool, split, and multi-hop strategies, and one
+/// quote is returned per target, in order. Rounding dust goes to the last slice.
+///
+/// Slices are quoted independently against the current reserves, so two slices
+/// routed through the same pool each see that pool's untouched liquidity.
+pub fn route_basket(
+    pools: &[Box<dyn Pool>],
+    token_in: &Pubkey,
+    targets: &[(Pubkey, u16)],
+    amount_in: u64,
+    max_hops: usize,
+) -> Result<Vec<SwapQuote>> {
+    if targets.is_empty() {
+        return Err(RouterError::ConfigError("Basket has no targets".to_string()));
+    }
+    if let Some((mint, _)) = targets.iter().find(|(mint, bps)| *bps == 0 || mint == token_in) {
+        return Err(RouterError::ConfigError(format!(
+            "Invalid basket target {}: weights must be non-zero and differ from the input token",
+            mint
+        )));
+    }
+
+    let total_bps: u32 = targets.iter().map(|(_, bps)| *bps as u32).sum();
+    if total_bps != 10000 {
+        return Err(RouterError::ConfigError(format!(
+            "Basket weights sum to {} bps, expected 10000",
+            total_bps
+        )));
+    }
+
+    let mut remaining = amount_in;
+    targets
+        .iter()
+        .enumerate()
+        .map(|(i, (token_out, bps))| {
+            let slice = if i + 1 == targets.len() {
+                remaining
+            } else {
+                (amount_in as u128 * *bps as u128 / 10000) as u64
+            };
+            remaining -= slice;
+
+            find_top_routes(pools, token_in, token_out, slice, max_hops, 1)
+                .into_iter()
+                .next()
+                .ok_or(RouterError::NoRouteFound {
+                    reason: NoRouteReason::AllPathsExhausted,
+                })
+        })
+        .collect()
+}
+
+/// Find the best route across the single-pool, split, and multi-hop strategies
+///
+/// Each strategy runs with `options`, and the quote with the largest output net
+/// of `options.hop_penalty_bps` wins, earlier strategies winning ties. Fails with
+/// `NoRouteFound` (`AllPathsExhausted`) when no strategy finds a route.
+pub fn find_best_overall_route(
+    pools: &[Box<dyn Pool>],
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    amount_in: u64,
+    options: &RouterOptions,
+) -> Result<SwapQuote> {
+    ensure_distinct_tokens(token_in, token_out)?;
+
+    let prepared = PreparedPools::new(pools, options);
+    prepared
+        .best_of_strategies(token_in, token_out, amount_in, options)
+        .ok_or(RouterError::NoRouteFound {
+            reason: NoRouteReason::AllPathsExhausted,
+        })
+}
+
+/// `find_best_overall_route` with each strategy evaluated on its own thread
+///
+/// Pools are `Send + Sync`, so the strategies share the prepared pools by
+/// reference. The results are compared in the same order as the sequential
+/// version, so both pick the same quote.
+pub fn find_best_overall_route_parallel(
+    pools: &[Box<dyn Pool>],
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    amount_in: u64,
+    options: &RouterOptions,
+) -> Result<SwapQuote> {
+    ensure_distinct_tokens(token_in, token_out)?;
+
+    let pools = candidate_pools(pools, options);
+    let pools = pools.as_ref();
+    let index = PoolIndex::new(pools);
+
+    let [single, split, multihop] = std::thread::scope(|scope| {
+        let index = &index;
+        let handles = [
+            scope.spawn(move || {
+                SinglePoolRouter::find_best_route_indexed(
+                    pools, index, token_in, token_out, amount_in, options,
+                )
+            }),
+            scope.spawn(move || {
+                SplitRouter::find_best_route_indexed(
+                    pools, index, token_in, token_out, amount_in, options,
+                )
+            }),
+            scope.spawn(move || {
+                let graph = MultiHopRouter::build_graph(pools, options);
+                MultiHopRouter::find_best_route_in_graph(
+                    pools, &graph, token_in, token_out, amount_in, options,
+                )
+            }),
+        ];
+        handles.map(|handle| {
+            handle
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })
+    });
+
+    best_of_strategies([single, split, multihop], options.hop_penalty_bps).ok_or(
+        RouterError::NoRouteFound {
+            reason: NoRouteReason::AllPathsExhausted,
+        },
+    )
+}
+
+/// Route each of `amounts` from `token_in` to `token_out`, so output and price
+/// impact can be compared across trade sizes
+///
+/// Each amount gets the best of the single-pool, split, and multi-hop quotes, or
+/// `None` when nothing routes. The pools are indexed by pair and linked into a
+/// routing graph once up front instead of once per amount.
+pub fn sensitivity_curve(
+    pools: &[Box<dyn Pool>],
+    token_in: &Pubkey,
+    token_out: &Pubkey,
+    amounts: &[u64],
+    max_hops: usize,
+) -> Vec<(u64, Option<SwapQuote>)> {
+    let options = RouterOptions::default().with_max_hops(max_hops);
+    let prepared = PreparedPools::new(pools, &options);
+
+    amounts
+        .iter()
+        .map(|&amount_in| {
+            let best = prepared.best_of_strategies(token_in, token_out, amount_in, &options);
+            (amount_in, best)
+        })
+        .collect()
+}
+
+/// Quote many swaps against the same pools, one result per request
+///
+/// Each `(token_in, token_out, amount_in)` request gets the best of the
+/// single-pool, split, and multi-hop quotes, as `find_best_overall_route` would
+/// pick. The pools are deduplicated, indexed, and linked into a routing graph
+/// once for the whole batch, and a request that cannot be routed gets its own
+/// `Err` without affecting the others.
+pub fn quote_many(
+    pools: &[Box<dyn Pool>],
+    requests: &[(Pubkey, Pubkey, u64)],
+    max_hops: usize,
+) -> Vec<Result<SwapQuote>> {
+    let options = RouterOptions::default().with_max_hops(max_hops);
+    let prepared = PreparedPools::new(pools, &options);
+
+    requests
+        .iter()
+        .map(|(token_in, token_out, amount_in)| {
+            ensure_distinct_tokens(token_in, token_out)?;
+            prepared
+                .best_of_strategies(token_in, token_out, *amount_in, &options)
+                .ok_or(RouterError::NoRouteFound {
+                    reason: NoRouteReason::AllPathsExhausted,
+                })
+        })
+        .collect()
+}
+
+/// Candidate pools with their pair index and routing graph, built once and
+/// shared by every request routed with the same options
+struct PreparedPools<'a> {
+    pools: Cow<'a, [Box<dyn Pool>]>,
+    index: PoolIndex,
+    graph: BTreeMap<Pubkey, Vec<RouteEdge>>,
+}
+
+impl<'a> PreparedPools<'a> {
+    /// Narrow `pools` by `options`, then index and link the survivors
+    fn new(pools: &'a [Box<dyn Pool>], options: &RouterOptions) -> Self {
+        let pools = candidate_pools(pools, options);
+        let index = PoolIndex::new(&pools);
+        let graph = MultiHopRouter::build_graph(&pools, options);
+        Self { pools, index, graph }
+    }
+
+    /// Best of the single-pool, split, and multi-hop quotes, or `None` when no
+    /// strategy finds a route
+    ///
+    /// `options` must be the options the pools were prepared with.
+    fn best_of_strategies(
+        &self,
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        options: &RouterOptions,
+    ) -> Option<SwapQuote> {
+        let (pools, index) = (self.pools.as_ref(), &self.index);
+        let candidates = [
+            SinglePoolRouter::find_best_route_indexed(
+                pools, index, token_in, token_out, amount_in, options,
+            ),
+            SplitRouter::find_best_route_indexed(
+                pools, index, token_in, token_out, amount_in, options,
+            ),
+            MultiHopRouter::find_best_route_in_graph(
+                pools, &self.graph, token_in, token_out, amount_in, options,
+            ),
+        ];
+
+        best_of_strategies(candidates, options.hop_penalty_bps)
+    }
+}
+
+/// Best of the single-pool, split, and multi-hop results, in that order, by
+/// output net of `hop_penalty_bps`; earlier strategies win ties and failed
+/// strategies are skipped
+fn best_of_strategies(
+    [single, split, multihop]: [Result<SwapQuote>; 3],
+    hop_penalty_bps: u16,
+) -> Option<SwapQuote> {
+    [("Single pool", single), ("Split routing", split), ("Multi-hop", multihop)]
+        .into_iter()
+        .filter_map(|(strategy, result)| {
+            let quote = result.ok()?;
+            debug!("   {}: {} output", strategy, quote.amount_out);
+            Some(quote)
+        })
+        .reduce(|best, quote| {
+            if quote.better_than_with_hop_penalty(&best, hop_penalty_bps) {
+                quote
+            } else {
+                best
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::{MeteoraPool, OrcaPool, RaydiumPool};
+    use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+    fn create_test_pools(token_a: Pubkey, token_b: Pubkey) -> Vec<Box<dyn Pool>> {
+        vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+            )),
+            Box::new(
+                OrcaPool::new_whirlpool(
+                    Pubkey::new_unique(),
+                    token_a,
+                    token_b,
+                    2_000_000_000,
+                    100_000_000_000,
+                    10,
+                )
+                .unwrap(),
+            ),
+            Box::new(MeteoraPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_500_000_000,
+                75_000_000_000,
+                20,
+            )),
+        ]
+    }
+
+    fn create_raydium_and_orca_pools(token_a: Pubkey, token_b: Pubkey) -> Vec<Box<dyn Pool>> {
+        vec![
+            // Raydium is the deeper pool and wins when allowed
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                2_000_000_000,
+                100_000_000_000,
+            )),
+            Box::new(OrcaPool::new_constant_product(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+            )),
+        ]
+    }
+
+    #[test]
+    fn test_excluded_dexes_in_all_routers() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pools = create_raydium_and_orca_pools(token_a, token_b);
+
+        let quote = SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, 1_000_000)
+            .unwrap();
+        assert_eq!(quote.route.steps[0].dex, "Raydium");
+
+        let options = RouterOptions::default().with_excluded_dexes(vec!["Raydium".to_string()]);
+
+        let quote = SinglePoolRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, 1_000_000, &options,
+        )
+        .unwrap();
+        assert_eq!(quote.route.steps[0].dex, "Orca");
+
+        let quote = SplitRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, 100_000_000, &options,
+        )
+        .unwrap();
+        assert!(quote.route.steps.iter().all(|step| step.dex == "Orca"));
+
+        let quote = MultiHopRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, 1_000_000, &options,
+        )
+        .unwrap();
+        assert_eq!(quote.route.steps[0].dex, "Orca");
+    }
+
+    #[test]
+    fn test_empty_allowlist_allows_all_dexes() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pools = create_raydium_and_orca_pools(token_a, token_b);
+
+        let options = RouterOptions::default().with_allowed_dexes(Vec::new());
+        let quote = SinglePoolRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, 1_000_000, &options,
+        )
+        .unwrap();
+        assert_eq!(quote.route.steps[0].dex, "Raydium");
+
+        // The denylist wins over the allowlist
+        let options = RouterOptions::default()
+            .with_allowed_dexes(vec!["Raydium".to_string(), "Orca".to_string()])
+            .with_excluded_dexes(vec!["Raydium".to_string()]);
+        assert!(!options.allows_pool(pools[0].as_ref()));
+        assert!(options.allows_pool(pools[1].as_ref()));
+    }
+
+    #[test]
+    fn test_max_pool_age_skips_stale_pools() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let mut pools = create_raydium_and_orca_pools(token_a, token_b);
+
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        // Refresh only the Orca pool; Raydium's data is now stale
+        pools[1].update_reserves(1_000_000_000, 50_000_000_000).unwrap();
+
+        let options = RouterOptions::default().with_max_pool_age(25);
+        assert!(!options.allows_pool(pools[0].as_ref()));
+        assert!(options.allows_pool(pools[1].as_ref()));
+
+        let quote = SinglePoolRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, 1_000_000, &options,
+        )
+        .unwrap();
+        assert_eq!(quote.route.steps[0].dex, "Orca");
+    }
+
+    #[test]
+    fn test_find_arbitrage_triangle() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let token_c = Pubkey::new_unique();
+
+        // 1 A = 2 B, 1 B = 2 C, but 1 C = 1 A: going A -> B -> C -> A quadruples A
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                2_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_b,
+                token_c,
+                1_000_000_000,
+                2_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_c,
+                token_a,
+                1_000_000_000,
+                1_000_000_000,
+            )),
+        ];
+
+        let quote = find_arbitrage(&pools, &token_a, 1_000_000, 3).unwrap();
+
+        assert_eq!(quote.strategy, "arbitrage");
+        assert_eq!(quote.token_in, token_a);
+        assert_eq!(quote.token_out, token_a);
+        assert!(quote.amount_out > quote.amount_in);
+        assert_eq!(quote.route.hop_count(), 3);
+        assert_eq!(quote.route.steps[0].token_out, token_b);
+        assert_eq!(quote.route.steps[1].token_out, token_c);
+    }
+
+    #[test]
+    fn test_find_arbitrage_none_when_fairly_priced() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let token_c = Pubkey::new_unique();
+
+        // Consistent prices: fees make every cycle unprofitable
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                2_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_b,
+                token_c,
+                1_000_000_000,
+                2_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_c,
+                token_a,
+                4_000_000_000,
+                1_000_000_000,
+            )),
+        ];
+
+        assert!(find_arbitrage(&pools, &token_a, 1_000_000, 3).is_none());
+        assert!(find_arbitrage(&pools, &token_a, 1_000_000, 1).is_none());
+    }
+
+    #[test]
+    fn test_explain_split_decision() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pools: Vec<Box<dyn Pool>> = (0..2)
+            .map(|_| {
+                Box::new(RaydiumPool::new(
+                    Pubkey::new_unique(),
+                    token_a,
+                    token_b,
+                    1_000_000_000_000,
+                    50_000_000_000_000,
+                )) as Box<dyn Pool>
+            })
+            .collect();
+
+        // 10% of one pool's depth: splitting halves the impact on each
+        let decision = explain_split_decision(&pools, &token_a, &token_b, 100_000_000_000).unwrap();
+        assert!(decision.split_output >= decision.single_output);
+        assert!(decision.improvement_bps >= 0);
+        assert_eq!(decision.pools_used, 2);
+        assert_eq!(decision.recommended, Strategy::Split);
+        assert_eq!(decision.recommended.name(), "split");
+
+        // With one pool there is nothing to split across
+        let decision =
+            explain_split_decision(&pools[..1], &token_a, &token_b, 100_000_000_000).unwrap();
+        assert_eq!(decision.split_output, decision.single_output);
+        assert_eq!(decision.improvement_bps, 0);
+        assert_eq!(decision.recommended, Strategy::SinglePool);
+
+        assert!(explain_split_decision(&pools, &token_a, &token_a, 1_000).is_err());
+    }
+
+    #[test]
+    fn test_quote_many_isolates_failures() {
+        let sol = Pubkey::new_unique();
+        let usdc = Pubkey::new_unique();
+        let ray = Pubkey::new_unique();
+        let orphan = Pubkey::new_unique();
+
+        let mut pools = create_test_pools(sol, usdc);
+        pools.push(Box::new(RaydiumPool::new(
+            Pubkey::new_unique(),
+            usdc,
+            ray,
+            50_000_000_000,
+            10_000_000_000,
+        )));
+
+        let requests = [
+            (sol, usdc, 1_000_000),
+            (sol, orphan, 1_000_000),
+            (sol, ray, 1_000_000),
+        ];
+        let results = quote_many(&pools, &requests, 2);
+        assert_eq!(results.len(), 3);
+
+        let direct = results[0].as_ref().unwrap();
+        let expected =
+            sensitivity_curve(&pools, &sol, &usdc, &[1_000_000], 2)[0].1.clone().unwrap();
+        assert_eq!(direct.amount_out, expected.amount_out);
+
+        assert!(matches!(
+            results[1],
+            Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::AllPathsExhausted
+            })
+        ));
+
+        let two_hop = results[2].as_ref().unwrap();
+        assert_eq!((two_hop.token_in, two_hop.token_out), (sol, ray));
+        assert_eq!(two_hop.route.hop_count(), 2);
+
+        // Identical tokens fail on their own too
+        let results = quote_many(&pools, &[(sol, sol, 1_000_000), (sol, usdc, 1_000_000)], 2);
+        assert!(matches!(results[0], Err(RouterError::IdenticalTokens)));
+        assert!(results[1].is_ok());
+    }
+
+    #[test]
+    fn test_overall_route_matches_each_strategy() {
+        let sol = Pubkey::new_unique();
+        let usdc = Pubkey::new_unique();
+        let ray = Pubkey::new_unique();
+        let mut pools = create_test_pools(sol, usdc);
+        pools.push(Box::new(RaydiumPool::new(
+            Pubkey::new_unique(),
+            sol,
+            ray,
+            1_000_000_000,
+            200_000_000_000,
+        )));
+        pools.push(Box::new(RaydiumPool::new(
+            Pubkey::new_unique(),
+            ray,
+            usdc,
+            200_000_000_000,
+            60_000_000_000,
+        )));
+
+        let options = RouterOptions {
+            excluded_dexes: Some(vec!["Meteora".to_string()]),
+            deepest_pool_per_dex: true,
+            hop_penalty_bps: 5,
+            ..RouterOptions::default()
+        };
+        for amount_in in [1_000, 1_000_000, 1_000_000_000] {
+            let strategies = [
+                SinglePoolRouter::find_best_route_with_options(
+                    &pools, &sol, &usdc, amount_in, &options,
+                ),
+                SplitRouter::find_best_route_with_options(
+                    &pools, &sol, &usdc, amount_in, &options,
+                ),
+                MultiHopRouter::find_best_route_with_options(
+                    &pools, &sol, &usdc, amount_in, &options,
+                ),
+            ];
+            let expected = best_of_strategies(strategies, options.hop_penalty_bps).unwrap();
+
+            let sequential =
+                find_best_overall_route(&pools, &sol, &usdc, amount_in, &options).unwrap();
+            let parallel =
+                find_best_overall_route_parallel(&pools, &sol, &usdc, amount_in, &options)
+                    .unwrap();
+            for quote in [&sequential, &parallel] {
+                assert_eq!(quote.amount_out, expected.amount_out);
+                assert_eq!(quote.strategy, expected.strategy);
+                assert_eq!(quote.route.fingerprint(), expected.route.fingerprint());
+            }
+            assert!(sequential.route.steps.iter().all(|step| step.dex != "Meteora"));
+        }
+
+        let orphan = Pubkey::new_unique();
+        for result in [
+            find_best_overall_route(&pools, &sol, &orphan, 1_000, &options),
+            find_best_overall_route_parallel(&pools, &sol, &orphan, 1_000, &options),
+        ] {
+            assert!(matches!(
+                result,
+                Err(RouterError::NoRouteFound {
+                    reason: NoRouteReason::AllPathsExhausted
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_overall_route_rejects_identical_tokens() {
+        let token = Pubkey::new_unique();
+        let pools = create_test_pools(token, Pubkey::new_unique());
+        let options = RouterOptions::default();
+
+        let result = find_best_overall_route(&pools, &token, &token, 1_000, &options);
+        assert!(matches!(result, Err(RouterError::IdenticalTokens)));
+        let result = find_best_overall_route_parallel(&pools, &token, &token, 1_000, &options);
+        assert!(matches!(result, Err(RouterError::IdenticalTokens)));
+    }
+
+    #[test]
+    fn test_first_viable_route_stops_at_single_pool() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pools = create_test_pools(token_a, token_b);
+        let amount_in = 1_000_000;
+
+        // The strategies `find_first_viable_route` runs, each counting its runs
+        let single = || SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, amount_in);
+        let split = || SplitRouter::find_best_route(&pools, &token_a, &token_b, amount_in);
+        let multihop =
+            || MultiHopRouter::find_best_route(&pools, &token_a, &token_b, amount_in, 2);
+        let runs = [AtomicUsize::new(0), AtomicUsize::new(0), AtomicUsize::new(0)];
+        let counted = |strategy: usize, route: &dyn Fn() -> Result<SwapQuote>| {
+            runs[strategy].fetch_add(1, AtomicOrdering::SeqCst);
+            route()
+        };
+
+        let quote = first_viable(
+            [&|| counted(0, &single), &|| counted(1, &split), &|| counted(2, &multihop)],
+            1,
+        )
+        .unwrap();
+        assert_eq!(quote.strategy, "single_pool");
+
+        // Split and multi-hop never ran
+        let runs = runs.map(|count| count.load(AtomicOrdering::SeqCst));
+        assert_eq!(runs, [1, 0, 0]);
+
+        let public = find_first_viable_route(&pools, &token_a, &token_b, amount_in, 2, 1).unwrap();
+        assert_eq!(public.amount_out, quote.amount_out);
+    }
+
+    #[test]
+    fn test_first_viable_route_falls_through_strategies() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pools = create_test_pools(token_a, token_b);
+        let amount_in = 500_000_000;
+
+        let single = SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, amount_in)
+            .unwrap();
+        let split = SplitRouter::find_best_route(&pools, &token_a, &token_b, amount_in).unwrap();
+        assert!(split.amount_out > single.amount_out);
+
+        // Only the split route clears a threshold above the best single pool
+        let quote = find_first_viable_route(
+            &pools,
+            &token_a,
+            &token_b,
+            amount_in,
+            2,
+            single.amount_out + 1,
+        )
+        .unwrap();
+        assert_eq!(quote.strategy, split.strategy);
+        assert_eq!(quote.amount_out, split.amount_out);
+
+        let result =
+            find_first_viable_route(&pools, &token_a, &token_b, amount_in, 2, u64::MAX);
+        assert!(matches!(
+            result,
+            Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::InsufficientLiquidity
+            })
+        ));
+    }
+
+    #[test]
+    fn test_route_basket_slices_by_weight() {
+        let sol = Pubkey::new_unique();
+        let usdc = Pubkey::new_unique();
+        let ray = Pubkey::new_unique();
+        let mut pools = create_test_pools(sol, usdc);
+        pools.push(Box::new(RaydiumPool::new(
+            Pubkey::new_unique(),
+            sol,
+            ray,
+            1_000_000_000,
+            200_000_000_000,
+        )));
+
+        let amount_in = 100_000_001;
+        let targets = [(usdc, 3000), (ray, 7000)];
+        let quotes = route_basket(&pools, &sol, &targets, amount_in, 2).unwrap();
+
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(quotes[0].token_out, usdc);
+        assert_eq!(quotes[0].amount_in, 30_000_000);
+        assert_eq!(quotes[1].token_out, ray);
+        assert_eq!(quotes[1].amount_in, 70_000_001);
+        assert!(quotes.iter().all(|quote| quote.amount_out > 0));
+    }
+
+    #[test]
+    fn test_route_basket_rejects_bad_weights() {
+        let sol = Pubkey::new_unique();
+        let usdc = Pubkey::new_unique();
+        let ray = Pubkey::new_unique();
+        let pools = create_test_pools(sol, usdc);
+
+        for targets in [
+            vec![(usdc, 3000), (ray, 6000)],
+            vec![(usdc, 10000), (ray, 0)],
+            vec![(sol, 10000)],
+            vec![],
+        ] {
+            let result = route_basket(&pools, &sol, &targets, 1_000_000, 2);
+            assert!(matches!(result, Err(RouterError::ConfigError(_))));
+        }
+    }
+
+    #[test]
+    fn test_sensitivity_curve_scales_with_size() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pools = create_test_pools(token_a, token_b);
+
+        let amounts: Vec<u64> = (0..7).map(|exp| 10u64.pow(exp + 3)).collect();
+        let curve = sensitivity_curve(&pools, &token_a, &token_b, &amounts, 2);
+
+        assert_eq!(curve.len(), amounts.len());
+        let quotes: Vec<&SwapQuote> = curve
+            .iter()
+            .zip(&amounts)
+            .map(|((amount, quote), expected)| {
+                assert_eq!(amount, expected);
+                quote.as_ref().unwrap()
+            })
+            .collect();
+        for pair in quotes.windows(2) {
+            assert!(pair[1].amount_out > pair[0].amount_out);
+            assert!(pair[1].price_impact_bps >= pair[0].price_impact_bps);
+        }
+
+        // Unroutable amounts are reported rather than dropped
+        let curve = sensitivity_curve(&pools, &token_a, &Pubkey::new_unique(), &amounts, 2);
+        assert!(curve.iter().all(|(_, quote)| quote.is_none()));
+    }
+
+    #[test]
+    fn test_find_top_routes_sorted_and_limited() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pools = create_test_pools(token_a, token_b);
+
+        let routes = find_top_routes(&pools, &token_a, &token_b, 50_000_000, 2, 3);
+
+        assert!(!routes.is_empty());
+        assert!(routes.len() <= 3);
+        for pair in routes.windows(2) {
+            assert!(pair[0].amount_out >= pair[1].amount_out);
+        }
+
+        let routes = find_top_routes(&pools, &token_a, &token_b, 50_000_000, 2, 1);
+        assert_eq!(routes.len(), 1);
+    }
+
+    #[test]
+    fn test_compare_by_dex() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        let best_raydium = Pubkey::new_unique();
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                best_raydium,
+                token_a,
+                token_b,
+                10_000_000_000,
+                500_000_000_000,
+            )),
+            Box::new(
+                OrcaPool::new_whirlpool(
+                    Pubkey::new_unique(),
+                    token_a,
+                    token_b,
+                    2_000_000_000,
+                    100_000_000_000,
+                    10,
+                )
+                .unwrap(),
+            ),
+            Box::new(MeteoraPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                500_000_000,
+                25_000_000_000,
+                20,
+            )),
+        ];
+
+        let by_dex = compare_by_dex(&pools, &token_a, &token_b, 10_000_000);
+
+        let mut dexes: Vec<&str> = by_dex.iter().map(|(dex, _)| dex.as_str()).collect();
+        dexes.sort();
+        assert_eq!(dexes, vec!["Meteora", "Orca", "Raydium"]);
+        assert!(by_dex.windows(2).all(|pair| pair[0].1.amount_out >= pair[1].1.amount_out));
+
+        let (_, raydium) = by_dex.iter().find(|(dex, _)| dex == "Raydium").unwrap();
+        assert_eq!(raydium.route.steps[0].pool_address, best_raydium);
+    }
+
+    #[test]
+    fn test_select_deepest_per_dex() {
+        let sol = Pubkey::new_unique();
+        let usdc = Pubkey::new_unique();
+
+        let shallow = Pubkey::new_unique();
+        let deep = Pubkey::new_unique();
+        let orca = Pubkey::new_unique();
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(shallow, sol, usdc, 1_000_000_000, 50_000_000_000)),
+            // Same pair listed the other way round
+            Box::new(RaydiumPool::new(deep, usdc, sol, 500_000_000_000, 10_000_000_000)),
+            Box::new(OrcaPool::new_whirlpool(orca, sol, usdc, 100, 5_000, 30).unwrap()),
+        ];
+
+        let selected = select_deepest_per_dex(&pools);
+        let addresses: Vec<Pubkey> = selected.iter().map(|pool| *pool.address()).collect();
+        assert_eq!(addresses, vec![deep, orca]);
+
+        // The routing option narrows the candidates every router sees
+        let options = RouterOptions::default().with_deepest_pool_per_dex();
+        assert_eq!(candidate_pools(&pools, &options).len(), 2);
+        assert_eq!(candidate_pools(&pools, &RouterOptions::default()).len(), 3);
+    }
+
+    #[test]
+    fn test_duplicate_pool_treated_as_one() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pool: Box<dyn Pool> = Box::new(RaydiumPool::new(
+            Pubkey::new_unique(),
+            token_a,
+            token_b,
+            1_000_000_000,
+            50_000_000_000,
+        ));
+        let pools = vec![pool.clone(), pool.clone()];
+
+        let deduped = dedupe_pools(&pools);
+        assert!(matches!(deduped, Cow::Owned(_)));
+        assert_eq!(deduped.len(), 1);
+        assert!(matches!(dedupe_pools(&pools[..1]), Cow::Borrowed(_)));
+
+        // Splitting across the "two" pools must not beat the single pool
+        let amount_in = 100_000_000;
+        let single = SinglePoolRouter::find_best_route(&[pool], &token_a, &token_b, amount_in)
+            .unwrap();
+        let split = SplitRouter::find_best_route(&pools, &token_a, &token_b, amount_in).unwrap();
+        assert_eq!(split.amount_out, single.amount_out);
+        assert_eq!(split.route.steps.len(), 1);
+
+        let all = SinglePoolRouter::find_all_routes(&pools, &token_a, &token_b, amount_in);
+        assert_eq!(all.len(), 1);
+    }
+
+    #[test]
+    fn test_find_top_routes_deduplicates_pool_sets() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pools = create_test_pools(token_a, token_b);
+
+        // The direct multi-hop route duplicates the best single-pool route; the
+        // trade is large enough that the split route spreads across pools
+        let routes = find_top_routes(&pools, &token_a, &token_b, 500_000_000, 2, 10);
+
+        let mut pool_sets: Vec<Vec<Pubkey>> = routes
+            .iter()
+            .map(|quote| {
+                let mut set: Vec<Pubkey> =
+                    quote.route.steps.iter().map(|s| s.pool_address).collect();
+                set.sort();
+                set
+            })
+            .collect();
+        let total = pool_sets.len();
+        pool_sets.sort();
+        pool_sets.dedup();
+        assert_eq!(pool_sets.len(), total);
+
+        // Three single pools plus the split route
+        assert_eq!(routes.len(), 4);
+    }
+}
@@ -1,23 +1,40 @@
 //! Multi-hop router - finds optimal routes through intermediate tokens
 
-use crate::error::{Result, RouterError};
+use crate::calculator::calculate_fee_amount;
+use crate::error::{NoRouteReason, Result, RouterError};
+use crate::router::{RouterOptions, candidate_pools, ensure_distinct_tokens};
+use crate::router::split::SplitRouter;
 use crate::types::pool::Pool;
 use crate::types::route::{Route, RouteStep, SwapQuote};
 use solana_sdk::pubkey::Pubkey;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// Default cap on the number of candidate paths explored per search
+pub const DEFAULT_MAX_PATHS: usize = 10_000;
+
+/// Number of edge reweighting passes run by `find_best_route_dijkstra`
+pub const SHORTEST_PATH_REFINEMENT_PASSES: usize = 3;
 
 /// Router for multi-hop routing through intermediate tokens
 pub struct MultiHopRouter;
 
 /// Represents an edge in the routing graph
 #[derive(Debug, Clone)]
-struct RouteEdge {
+pub(crate) struct RouteEdge {
     pool_index: usize,
     from_token: Pubkey,
     to_token: Pubkey,
     a_to_b: bool,
 }
 
+/// Cheapest known path to a token during the shortest-path search
+#[derive(Debug, Clone)]
+struct PathLabel {
+    /// Sum of `-ln(amount_out / amount_in)` over the path's edges
+    cost: f64,
+    path: Vec<RouteEdge>,
+}
+
 impl MultiHopRouter {
     /// Find the best multi-hop route (up to max_hops)
     ///
@@ -29,31 +46,578 @@ impl MultiHopRouter {
         amount_in: u64,
         max_hops: usize,
     ) -> Result<SwapQuote> {
+        Self::find_best_route_with_options(
+            pools,
+            token_in,
+            token_out,
+            amount_in,
+            &RouterOptions::default().with_max_hops(max_hops),
+        )
+    }
+
+    /// Find the best multi-hop route (up to `options.max_hops`), subject to `options`
+    ///
+    /// Pools rejected by the DEX/pool filters are left out of the routing graph, and
+    /// paths whose total price impact exceeds `options.max_price_impact_bps` are skipped.
+    pub fn find_best_route_with_options(
+        pools: &[Box<dyn Pool>],
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        options: &RouterOptions,
+    ) -> Result<SwapQuote> {
+        Self::search(
+            pools,
+            token_in,
+            token_out,
+            amount_in,
+            DEFAULT_MAX_PATHS,
+            options,
+        )
+    }
+
+    /// Find the best multi-hop route, exploring at most `max_paths` candidate paths
+    ///
+    /// On densely connected pool sets the search stops once the cap is reached and
+    /// the best route among the paths explored so far is returned.
+    pub fn find_best_route_capped(
+        pools: &[Box<dyn Pool>],
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        max_hops: usize,
+        max_paths: usize,
+    ) -> Result<SwapQuote> {
+        Self::search(
+            pools,
+            token_in,
+            token_out,
+            amount_in,
+            max_paths,
+            &RouterOptions::default().with_max_hops(max_hops),
+        )
+    }
+
+    /// Find the best multi-hop route with a shortest-path search rather than by
+    /// enumerating every path
+    ///
+    /// Edges are weighted by `-ln(effective_price)`, where the effective price is
+    /// `amount_out / amount_in` at the amount expected to reach the edge's source
+    /// token, so the cheapest path is the one with the largest output. AMM prices
+    /// depend on the amount, so the hop-limited Bellman-Ford search is rerun for
+    /// up to `SHORTEST_PATH_REFINEMENT_PASSES` passes, each reweighting the edges
+    /// with the amounts the previous pass expected at every token. The best path
+    /// found by any pass is evaluated exactly and returned.
+    pub fn find_best_route_dijkstra(
+        pools: &[Box<dyn Pool>],
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        max_hops: usize,
+    ) -> Result<SwapQuote> {
+        Self::find_best_route_dijkstra_with_options(
+            pools,
+            token_in,
+            token_out,
+            amount_in,
+            &RouterOptions::default().with_max_hops(max_hops),
+        )
+    }
+
+    /// `find_best_route_dijkstra` up to `options.max_hops`, leaving pools rejected
+    /// by the DEX/pool filters out of the graph
+    ///
+    /// Each hop is also charged `options.hop_penalty_bps`, hops whose own price
+    /// impact exceeds `options.max_price_impact_bps` are never taken, and a route
+    /// whose total impact exceeds it is rejected, as in `find_best_route_with_options`.
+    /// The search keeps only the cheapest path to each token, so it cannot force
+    /// a route through `options.required_intermediate`; setting it fails with
+    /// `RouterError::ConfigError` (use `find_best_route_with_options` instead).
+    pub fn find_best_route_dijkstra_with_options(
+        pools: &[Box<dyn Pool>],
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        options: &RouterOptions,
+    ) -> Result<SwapQuote> {
+        let max_hops = options.max_hops;
+        if max_hops == 0 || max_hops > 3 {
+            return Err(RouterError::ConfigError(
+                "max_hops must be between 1 and 3".to_string(),
+            ));
+        }
+
+        ensure_distinct_tokens(token_in, token_out)?;
+
+        if options.required_intermediate.is_some() {
+            return Err(RouterError::ConfigError(
+                "required_intermediate is not supported by the shortest-path search".to_string(),
+            ));
+        }
+
+        if amount_in == 0 {
+            return Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::ZeroAmount,
+            });
+        }
+
+        let pools = candidate_pools(pools, options);
+        let pools = pools.as_ref();
+        let graph = Self::build_graph(pools, options);
+
+        let mut estimates = HashMap::from([(*token_in, amount_in)]);
+        let mut best_quote: Option<SwapQuote> = None;
+        let mut reached = false;
+        let mut filtered_by_impact = false;
+
+        for _ in 0..SHORTEST_PATH_REFINEMENT_PASSES {
+            let labels =
+                Self::shortest_paths(&graph, pools, token_in, amount_in, &estimates, options);
+            let Some(label) = labels.get(token_out) else {
+                break;
+            };
+            reached = true;
+
+            if let Ok(quote) = Self::evaluate_path(&label.path, pools, amount_in) {
+                if !options.allows_price_impact(quote.price_impact_bps) {
+                    filtered_by_impact = true;
+                } else if best_quote.as_ref().is_none_or(|best| {
+                    quote.better_than_with_hop_penalty(best, options.hop_penalty_bps)
+                }) {
+                    best_quote = Some(quote);
+                }
+            }
+
+            let refined: HashMap<Pubkey, u64> = labels
+                .iter()
+                .map(|(token, label)| (*token, implied_amount(amount_in, label.cost)))
+                .collect();
+            if refined == estimates {
+                break;
+            }
+            estimates = refined;
+        }
+
+        best_quote.ok_or(RouterError::NoRouteFound {
+            reason: if filtered_by_impact {
+                NoRouteReason::InsufficientLiquidity
+            } else if reached {
+                NoRouteReason::AllPathsExhausted
+            } else {
+                NoRouteReason::NoMatchingPool
+            },
+        })
+    }
+
+    /// Hop-limited Bellman-Ford over `-ln(effective_price)` edge weights
+    ///
+    /// Edges out of a token are priced at its amount in `estimates`, or at the
+    /// amount implied by its current label if it has none. Labels are compared
+    /// with `-ln(1 - hop_penalty)` added per hop, and edges whose price impact
+    /// exceeds `options.max_price_impact_bps` are skipped. Labels never revisit a
+    /// token, so every returned path is simple.
+    fn shortest_paths(
+        graph: &BTreeMap<Pubkey, Vec<RouteEdge>>,
+        pools: &[Box<dyn Pool>],
+        token_in: &Pubkey,
+        amount_in: u64,
+        estimates: &HashMap<Pubkey, u64>,
+        options: &RouterOptions,
+    ) -> BTreeMap<Pubkey, PathLabel> {
+        // Kept out of `cost` so the implied amounts stay the swap amounts
+        let hop_cost = -(1.0 - options.hop_penalty_bps.min(9999) as f64 / 10000.0).ln();
+        let penalized = |label: &PathLabel| label.cost + hop_cost * label.path.len() as f64;
+
+        let mut labels = BTreeMap::from([(
+            *token_in,
+            PathLabel {
+                cost: 0.0,
+                path: Vec::new(),
+            },
+        )]);
+
+        for _ in 0..options.max_hops {
+            let mut next = labels.clone();
+
+            for (token, label) in &labels {
+                let Some(edges) = graph.get(token) else {
+                    continue;
+                };
+                let amount = estimates
+                    .get(token)
+                    .copied()
+                    .unwrap_or_else(|| implied_amount(amount_in, label.cost));
+                if amount == 0 {
+                    continue;
+                }
+
+                for edge in edges {
+                    let visited = edge.to_token == *token_in
+                        || label.path.iter().any(|e| e.to_token == edge.to_token);
+                    if visited {
+                        continue;
+                    }
+
+                    let pool = &pools[edge.pool_index];
+                    let Ok((amount_out, impact)) = pool.calculate_output(amount, edge.a_to_b)
+                    else {
+                        continue;
+                    };
+                    if amount_out == 0 || !options.allows_price_impact(impact) {
+                        continue;
+                    }
+
+                    let cost = label.cost - (amount_out as f64 / amount as f64).ln();
+                    let penalized_cost = cost + hop_cost * (label.path.len() + 1) as f64;
+                    if next
+                        .get(&edge.to_token)
+                        .is_none_or(|current| penalized_cost < penalized(current))
+                    {
+                        let mut path = label.path.clone();
+                        path.push(edge.clone());
+                        next.insert(edge.to_token, PathLabel { cost, path });
+                    }
+                }
+            }
+
+            labels = next;
+        }
+
+        labels
+    }
+
+    /// `find_best_route_with_options` over a routing graph built up front
+    ///
+    /// `pools` must already be narrowed by `candidate_pools`, and `graph` built
+    /// from them by `build_graph` with the same `options`, so callers routing a
+    /// batch of requests build the graph once instead of once per request.
+    pub(crate) fn find_best_route_in_graph(
+        pools: &[Box<dyn Pool>],
+        graph: &BTreeMap<Pubkey, Vec<RouteEdge>>,
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        options: &RouterOptions,
+    ) -> Result<SwapQuote> {
+        Self::search_graph(
+            pools,
+            graph,
+            token_in,
+            token_out,
+            amount_in,
+            DEFAULT_MAX_PATHS,
+            options,
+        )
+    }
+
+    /// Evaluate every path (up to `max_paths`) and keep the best acceptable quote
+    fn search(
+        pools: &[Box<dyn Pool>],
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        max_paths: usize,
+        options: &RouterOptions,
+    ) -> Result<SwapQuote> {
+        let pools = candidate_pools(pools, options);
+        let pools = pools.as_ref();
+        let graph = Self::build_graph(pools, options);
+
+        Self::search_graph(pools, &graph, token_in, token_out, amount_in, max_paths, options)
+    }
+
+    /// `search` over candidate pools already linked into `graph`
+    fn search_graph(
+        pools: &[Box<dyn Pool>],
+        graph: &BTreeMap<Pubkey, Vec<RouteEdge>>,
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        max_paths: usize,
+        options: &RouterOptions,
+    ) -> Result<SwapQuote> {
+        let max_hops = options.max_hops;
         if max_hops == 0 || max_hops > 3 {
             return Err(RouterError::ConfigError(
                 "max_hops must be between 1 and 3".to_string(),
             ));
         }
 
-        // Build routing graph
-        let graph = Self::build_graph(pools);
+        ensure_distinct_tokens(token_in, token_out)?;
+
+        if amount_in == 0 {
+            return Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::ZeroAmount,
+            });
+        }
 
         // Find all possible paths
-        let paths = Self::find_all_paths(&graph, token_in, token_out, max_hops);
+        let mut paths = Self::find_all_paths(graph, token_in, token_out, max_hops, max_paths);
+
+        if let Some(required) = options.required_intermediate {
+            paths.retain(|path| Self::passes_through(path, &required));
+        }
 
         if paths.is_empty() {
-            return Err(RouterError::NoRouteFound);
+            return Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::NoMatchingPool,
+            });
         }
 
         // Evaluate each path and find the best
         let mut best_quote: Option<SwapQuote> = None;
+        let mut filtered_by_impact = false;
 
         for path in paths {
             if let Ok(quote) = Self::evaluate_path(&path, pools, amount_in) {
+                if !options.allows_price_impact(quote.price_impact_bps) {
+                    filtered_by_impact = true;
+                    continue;
+                }
+
+                best_quote = match best_quote {
+                    None => Some(quote),
+                    Some(current_best) => {
+                        if quote.better_than_with_hop_penalty(
+                            &current_best,
+                            options.hop_penalty_bps,
+                        ) {
+                            Some(quote)
+                        } else {
+                            Some(current_best)
+                        }
+                    }
+                };
+            }
+        }
+
+        best_quote.ok_or(RouterError::NoRouteFound {
+            reason: if filtered_by_impact {
+                NoRouteReason::InsufficientLiquidity
+            } else {
+                NoRouteReason::AllPathsExhausted
+            },
+        })
+    }
+
+    /// Find the path that requires the least input to produce exactly `amount_out`
+    ///
+    /// Each candidate path is evaluated backward, last hop first, inverting each
+    /// pool's curve with `Pool::required_input` to find how much each hop needs,
+    /// then quoted forward to confirm it fills the target. Paths where some hop
+    /// cannot supply the required amount are skipped.
+    pub fn find_best_route_exact_out(
+        pools: &[Box<dyn Pool>],
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_out: u64,
+        max_hops: usize,
+    ) -> Result<SwapQuote> {
+        Self::find_best_route_exact_out_with_options(
+            pools,
+            token_in,
+            token_out,
+            amount_out,
+            &RouterOptions::default().with_max_hops(max_hops),
+        )
+    }
+
+    /// `find_best_route_exact_out` up to `options.max_hops`, subject to `options`
+    ///
+    /// Pools rejected by the DEX/pool filters are left out of the routing graph,
+    /// and only paths through `options.required_intermediate` (if set) are
+    /// considered.
+    pub fn find_best_route_exact_out_with_options(
+        pools: &[Box<dyn Pool>],
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_out: u64,
+        options: &RouterOptions,
+    ) -> Result<SwapQuote> {
+        let max_hops = options.max_hops;
+        if max_hops == 0 || max_hops > 3 {
+            return Err(RouterError::ConfigError(
+                "max_hops must be between 1 and 3".to_string(),
+            ));
+        }
+
+        ensure_distinct_tokens(token_in, token_out)?;
+
+        if amount_out == 0 {
+            return Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::ZeroAmount,
+            });
+        }
+
+        let pools = candidate_pools(pools, options);
+        let pools = pools.as_ref();
+
+        let graph = Self::build_graph(pools, options);
+        let mut paths =
+            Self::find_all_paths(&graph, token_in, token_out, max_hops, DEFAULT_MAX_PATHS);
+
+        if let Some(required) = options.required_intermediate {
+            paths.retain(|path| Self::passes_through(path, &required));
+        }
+
+        if paths.is_empty() {
+            return Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::NoMatchingPool,
+            });
+        }
+
+        let mut best_quote: Option<SwapQuote> = None;
+
+        for path in paths {
+            let Ok(quote) = Self::evaluate_path_exact_out(&path, pools, amount_out) else {
+                continue;
+            };
+
+            best_quote = match best_quote {
+                Some(current_best) if current_best.amount_in <= quote.amount_in => {
+                    Some(current_best)
+                }
+                _ => Some(quote),
+            };
+        }
+
+        best_quote.ok_or(RouterError::NoRouteFound {
+            reason: NoRouteReason::InsufficientLiquidity,
+        })
+    }
+
+    /// Find the best multi-hop route, splitting the first hop across pools
+    ///
+    /// The first hop is optimized with the split router across every pool for that
+    /// token pair; the aggregate output is then routed through the best single pools
+    /// for the remaining hops.
+    pub fn find_best_route_with_split(
+        pools: &[Box<dyn Pool>],
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        max_hops: usize,
+    ) -> Result<SwapQuote> {
+        Self::find_best_route_with_split_with_options(
+            pools,
+            token_in,
+            token_out,
+            amount_in,
+            &RouterOptions::default().with_max_hops(max_hops),
+        )
+    }
+
+    /// `find_best_route_with_split` up to `options.max_hops`, subject to `options`
+    ///
+    /// Both the split first hop and the remaining hops only use pools passing the
+    /// DEX/pool filters, and routes are ranked and filtered on price impact, hop
+    /// penalty and `options.required_intermediate` as in `find_best_route_with_options`.
+    pub fn find_best_route_with_split_with_options(
+        pools: &[Box<dyn Pool>],
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        options: &RouterOptions,
+    ) -> Result<SwapQuote> {
+        let max_hops = options.max_hops;
+        if max_hops == 0 || max_hops > 3 {
+            return Err(RouterError::ConfigError(
+                "max_hops must be between 1 and 3".to_string(),
+            ));
+        }
+
+        ensure_distinct_tokens(token_in, token_out)?;
+
+        if amount_in == 0 {
+            return Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::ZeroAmount,
+            });
+        }
+
+        let pools = candidate_pools(pools, options);
+        let pools = pools.as_ref();
+        let graph = Self::build_graph(pools, options);
+
+        // Candidate intermediate tokens reachable from token_in in one hop
+        let mut intermediates: Vec<Pubkey> = Vec::new();
+        if let Some(edges) = graph.get(token_in) {
+            for edge in edges {
+                if edge.to_token != *token_out && !intermediates.contains(&edge.to_token) {
+                    intermediates.push(edge.to_token);
+                }
+            }
+        }
+
+        let mut best_quote: Option<SwapQuote> = None;
+        let mut found_path = false;
+        let mut filtered_by_impact = false;
+
+        for intermediate in intermediates {
+            let first_hop = match SplitRouter::find_best_route_with_options(
+                pools,
+                token_in,
+                &intermediate,
+                amount_in,
+                options,
+            ) {
+                Ok(quote) => quote,
+                Err(_) => continue,
+            };
+
+            // Remaining hops must not loop back through token_in
+            let paths = Self::find_all_paths(
+                &graph,
+                &intermediate,
+                token_out,
+                max_hops - 1,
+                DEFAULT_MAX_PATHS,
+            );
+
+            for path in paths {
+                if path.iter().any(|edge| edge.to_token == *token_in) {
+                    continue;
+                }
+                if let Some(required) = options.required_intermediate
+                    && intermediate != required
+                    && !Self::passes_through(&path, &required)
+                {
+                    continue;
+                }
+                found_path = true;
+
+                let rest = match Self::evaluate_path(&path, pools, first_hop.amount_out) {
+                    Ok(quote) => quote,
+                    Err(_) => continue,
+                };
+
+                let mut steps = first_hop.route.steps.clone();
+                steps.extend(rest.route.steps);
+
+                let mut route = Route::multi_step(steps);
+                route.total_input = amount_in;
+                route.total_output = rest.amount_out;
+
+                let quote = SwapQuote::new(
+                    *token_in,
+                    *token_out,
+                    amount_in,
+                    rest.amount_out,
+                    route,
+                    "multi_hop_split".to_string(),
+                );
+
+                if !options.allows_price_impact(quote.price_impact_bps) {
+                    filtered_by_impact = true;
+                    continue;
+                }
+
                 best_quote = match best_quote {
                     None => Some(quote),
                     Some(current_best) => {
-                        if quote.better_than(&current_best) {
+                        if quote.better_than_with_hop_penalty(
+                            &current_best,
+                            options.hop_penalty_bps,
+                        ) {
                             Some(quote)
                         } else {
                             Some(current_best)
@@ -63,14 +627,32 @@ impl MultiHopRouter {
             }
         }
 
-        best_quote.ok_or(RouterError::NoRouteFound)
+        best_quote.ok_or(RouterError::NoRouteFound {
+            reason: if filtered_by_impact {
+                NoRouteReason::InsufficientLiquidity
+            } else if found_path {
+                NoRouteReason::AllPathsExhausted
+            } else {
+                NoRouteReason::NoMatchingPool
+            },
+        })
     }
 
-    /// Build a graph of all possible token swaps
-    fn build_graph(pools: &[Box<dyn Pool>]) -> HashMap<Pubkey, Vec<RouteEdge>> {
-        let mut graph: HashMap<Pubkey, Vec<RouteEdge>> = HashMap::new();
+    /// Build a graph of all possible token swaps through the pools allowed by `options`
+    ///
+    /// Tokens are kept in a `BTreeMap` and each token's edges in pool order, so
+    /// path discovery (and therefore tie-breaking) is the same on every run.
+    pub(crate) fn build_graph(
+        pools: &[Box<dyn Pool>],
+        options: &RouterOptions,
+    ) -> BTreeMap<Pubkey, Vec<RouteEdge>> {
+        let mut graph: BTreeMap<Pubkey, Vec<RouteEdge>> = BTreeMap::new();
 
         for (idx, pool) in pools.iter().enumerate() {
+            if !options.allows_pool(pool.as_ref()) {
+                continue;
+            }
+
             let token_a = *pool.token_a();
             let token_b = *pool.token_b();
 
@@ -94,23 +676,37 @@ impl MultiHopRouter {
         graph
     }
 
+    /// Whether `path` visits `token` between its first and last edge
+    fn passes_through(path: &[RouteEdge], token: &Pubkey) -> bool {
+        path.split_last()
+            .is_some_and(|(_, init)| init.iter().any(|edge| edge.to_token == *token))
+    }
+
     /// Find all paths from token_in to token_out within max_hops
+    ///
+    /// Stops once `max_paths` paths have been found, and never holds more than
+    /// `max_paths` partial paths in the queue.
     fn find_all_paths(
-        graph: &HashMap<Pubkey, Vec<RouteEdge>>,
+        graph: &BTreeMap<Pubkey, Vec<RouteEdge>>,
         token_in: &Pubkey,
         token_out: &Pubkey,
         max_hops: usize,
+        max_paths: usize,
     ) -> Vec<Vec<RouteEdge>> {
         let mut all_paths = Vec::new();
         let mut queue = VecDeque::new();
 
-        // Initialize: (current_token, path, visited_tokens)
-        queue.push_back((*token_in, Vec::new(), HashSet::new()));
+        // Initialize: (current_token, path)
+        queue.push_back((*token_in, Vec::new()));
+
+        while let Some((current_token, path)) = queue.pop_front() {
+            if all_paths.len() >= max_paths {
+                break;
+            }
 
-        while let Some((current_token, path, mut visited)) = queue.pop_front() {
             // Check if we've reached the destination
             if current_token == *token_out && !path.is_empty() {
-                all_paths.push(path.clone());
+                all_paths.push(path);
                 continue;
             }
 
@@ -119,21 +715,24 @@ impl MultiHopRouter {
                 continue;
             }
 
-            // Mark current token as visited
-            visited.insert(current_token);
-
             // Explore neighbors
             if let Some(edges) = graph.get(&current_token) {
                 for edge in edges {
-                    // Avoid cycles
-                    if visited.contains(&edge.to_token) {
+                    // Avoid cycles: the path itself is the visited set (at most max_hops long)
+                    let visited = edge.to_token == *token_in
+                        || path.iter().any(|e: &RouteEdge| e.to_token == edge.to_token);
+                    if visited {
                         continue;
                     }
 
+                    if all_paths.len() + queue.len() >= max_paths {
+                        break;
+                    }
+
                     let mut new_path = path.clone();
                     new_path.push(edge.clone());
 
-                    queue.push_back((edge.to_token, new_path, visited.clone()));
+                    queue.push_back((edge.to_token, new_path));
                 }
             }
         }
@@ -141,8 +740,61 @@ impl MultiHopRouter {
         all_paths
     }
 
+    /// Find cycles that leave `start_token` and return to it within max_hops
+    ///
+    /// Intermediate tokens and pools are never revisited; only the start token may
+    /// repeat, as the final hop. Stops once `max_paths` cycles have been found.
+    pub(crate) fn find_cycles(
+        graph: &BTreeMap<Pubkey, Vec<RouteEdge>>,
+        start_token: &Pubkey,
+        max_hops: usize,
+        max_paths: usize,
+    ) -> Vec<Vec<RouteEdge>> {
+        let mut cycles = Vec::new();
+        let mut queue = VecDeque::new();
+
+        queue.push_back((*start_token, Vec::new()));
+
+        while let Some((current_token, path)) = queue.pop_front() {
+            let Some(edges) = graph.get(&current_token) else {
+                continue;
+            };
+
+            for edge in edges {
+                if cycles.len() >= max_paths {
+                    return cycles;
+                }
+
+                let pool_used = path.iter().any(|e: &RouteEdge| e.pool_index == edge.pool_index);
+                if pool_used {
+                    continue;
+                }
+
+                let mut new_path = path.clone();
+                new_path.push(edge.clone());
+
+                if edge.to_token == *start_token {
+                    // A single pool can't form a cycle on its own
+                    if new_path.len() >= 2 {
+                        cycles.push(new_path);
+                    }
+                    continue;
+                }
+
+                let visited = path.iter().any(|e: &RouteEdge| e.to_token == edge.to_token);
+                if visited || new_path.len() >= max_hops || queue.len() >= max_paths {
+                    continue;
+                }
+
+                queue.push_back((edge.to_token, new_path));
+            }
+        }
+
+        cycles
+    }
+
     /// Evaluate a path and create a swap quote
-    fn evaluate_path(
+    pub(crate) fn evaluate_path(
         path: &[RouteEdge],
         pools: &[Box<dyn Pool>],
         initial_amount: u64,
@@ -164,13 +816,16 @@ impl MultiHopRouter {
                 amount_out,
                 price_impact_bps: price_impact,
                 fee_bps: pool.fee_bps(),
+                fee_amount: calculate_fee_amount(current_amount, pool.fee_bps()),
             });
 
             current_amount = amount_out;
         }
 
         if steps.is_empty() {
-            return Err(RouterError::NoRouteFound);
+            return Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::AllPathsExhausted,
+            });
         }
 
         let token_in = steps.first().unwrap().token_in;
@@ -186,20 +841,298 @@ impl MultiHopRouter {
             format!("multi_hop_{}", path.len()),
         ))
     }
+
+    /// Evaluate a path backward from a desired final output
+    ///
+    /// Each hop reports the amount the next hop needs as its output, and the
+    /// price impact of its forward quote.
+    fn evaluate_path_exact_out(
+        path: &[RouteEdge],
+        pools: &[Box<dyn Pool>],
+        amount_out: u64,
+    ) -> Result<SwapQuote> {
+        // (input, output) each hop needs, last hop first
+        let mut required = Vec::with_capacity(path.len());
+        let mut required_out = amount_out;
+
+        for edge in path.iter().rev() {
+            let amount_in = pools[edge.pool_index].required_input(required_out, edge.a_to_b)?;
+            required.push((amount_in, required_out));
+            required_out = amount_in;
+        }
+        required.reverse();
+
+        // Quote the path forward from the required input to confirm it fills the target
+        let forward = Self::evaluate_path(path, pools, required_out)?;
+        if forward.amount_out < amount_out {
+            return Err(RouterError::InsufficientLiquidity);
+        }
+
+        let steps: Vec<RouteStep> = forward
+            .route
+            .steps
+            .into_iter()
+            .zip(required)
+            .map(|(step, (amount_in, amount_out))| RouteStep {
+                amount_in,
+                amount_out,
+                fee_amount: calculate_fee_amount(amount_in, step.fee_bps),
+                ..step
+            })
+            .collect();
+
+        let route = Route::multi_step(steps);
+        Ok(SwapQuote::new(
+            forward.token_in,
+            forward.token_out,
+            required_out,
+            amount_out,
+            route,
+            "multi_hop_exact_out".to_string(),
+        ))
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::dex::RaydiumPool;
+/// Amount expected at the end of a path of the given cost
+fn implied_amount(amount_in: u64, cost: f64) -> u64 {
+    (amount_in as f64 * (-cost).exp()) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::{MeteoraPool, RaydiumPool};
+    use crate::types::test_support::MockPool;
+
+    #[test]
+    fn test_two_hop_route() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let token_c = Pubkey::new_unique();
+
+        // Create pools: A-B and B-C
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_b,
+                token_c,
+                50_000_000_000,
+                2_000_000_000,
+            )),
+        ];
+
+        // Route from A to C through B
+        let quote = MultiHopRouter::find_best_route(&pools, &token_a, &token_c, 1_000_000, 2)
+            .unwrap();
+
+        assert_eq!(quote.route.hop_count(), 2);
+        assert_eq!(quote.route.steps[0].token_in, token_a);
+        assert_eq!(quote.route.steps[0].token_out, token_b);
+        assert_eq!(quote.route.steps[1].token_in, token_b);
+        assert_eq!(quote.route.steps[1].token_out, token_c);
+        assert!(quote.strategy.starts_with("multi_hop"));
+    }
+
+    #[test]
+    fn test_two_hop_exact_out() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let token_c = Pubkey::new_unique();
+
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_b,
+                token_c,
+                50_000_000_000,
+                2_000_000_000,
+            )),
+            // Direct pool too shallow to fill the target: must be skipped
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_c,
+                1_000_000,
+                1_000_000,
+            )),
+        ];
+        let target = 10_000_000;
+
+        let quote =
+            MultiHopRouter::find_best_route_exact_out(&pools, &token_a, &token_c, target, 2)
+                .unwrap();
+
+        assert_eq!(quote.route.hop_count(), 2);
+        assert_eq!(quote.amount_out, target);
+        assert_eq!(quote.route.steps[0].amount_out, quote.route.steps[1].amount_in);
+        assert_eq!(quote.strategy, "multi_hop_exact_out");
+
+        // Running the computed input forward yields at least the target
+        let forward_path = [
+            RouteEdge {
+                pool_index: 0,
+                from_token: token_a,
+                to_token: token_b,
+                a_to_b: true,
+            },
+            RouteEdge {
+                pool_index: 1,
+                from_token: token_b,
+                to_token: token_c,
+                a_to_b: true,
+            },
+        ];
+        let forward = MultiHopRouter::evaluate_path(&forward_path, &pools, quote.amount_in).unwrap();
+        assert!(forward.amount_out >= target);
+    }
+
+    #[test]
+    fn test_exact_out_inverts_stable_hop() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let token_c = Pubkey::new_unique();
+
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+            )),
+            // B and C are pegged: the stable curve barely moves the price
+            Box::new(MeteoraPool::new_stable(
+                Pubkey::new_unique(),
+                token_b,
+                token_c,
+                20_000_000_000,
+                20_000_000_000,
+                4,
+                100,
+            )),
+        ];
+        let target = 5_000_000_000;
+
+        let quote =
+            MultiHopRouter::find_best_route_exact_out(&pools, &token_a, &token_c, target, 2)
+                .unwrap();
+        assert_eq!(quote.amount_out, target);
+
+        // The stable hop needs far less than constant-product math would ask for
+        let stable_in = quote.route.steps[1].amount_in;
+        let constant_product = crate::calculator::calculate_amount_in(
+            target,
+            20_000_000_000,
+            20_000_000_000,
+            4,
+        )
+        .unwrap();
+        assert!(stable_in < constant_product);
+        let (stable_out, _) = pools[1].calculate_output(stable_in, true).unwrap();
+        assert!(stable_out >= target);
+
+        // Running the computed input forward yields at least the target
+        let forward_path = [
+            RouteEdge {
+                pool_index: 0,
+                from_token: token_a,
+                to_token: token_b,
+                a_to_b: true,
+            },
+            RouteEdge {
+                pool_index: 1,
+                from_token: token_b,
+                to_token: token_c,
+                a_to_b: true,
+            },
+        ];
+        let forward = MultiHopRouter::evaluate_path(&forward_path, &pools, quote.amount_in).unwrap();
+        assert!(forward.amount_out >= target);
+    }
+
+    #[test]
+    fn test_exact_out_insufficient_liquidity() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        let pools: Vec<Box<dyn Pool>> = vec![Box::new(RaydiumPool::new(
+            Pubkey::new_unique(),
+            token_a,
+            token_b,
+            1_000_000,
+            1_000_000,
+        ))];
+
+        let result =
+            MultiHopRouter::find_best_route_exact_out(&pools, &token_a, &token_b, 2_000_000, 2);
+        assert!(matches!(
+            result,
+            Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::InsufficientLiquidity
+            })
+        ));
+    }
+
+    #[test]
+    fn test_two_hop_fee_amounts() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let token_c = Pubkey::new_unique();
+
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_b,
+                token_c,
+                50_000_000_000,
+                2_000_000_000,
+            )),
+        ];
+
+        let quote = MultiHopRouter::find_best_route(&pools, &token_a, &token_c, 1_000_000, 2)
+            .unwrap();
+        let steps = &quote.route.steps;
+
+        // 0.25% of each hop's input, charged in that hop's input token
+        assert_eq!(steps[0].fee_amount, 2_500);
+        assert_eq!(steps[1].fee_amount, steps[1].amount_in * 25 / 10000);
+        assert!(steps[1].fee_amount > 0);
+
+        // Only the first hop is denominated in the input token
+        assert_eq!(quote.route.total_fees_in_input_token(), 2_500);
+        assert_eq!(
+            quote.route.fees_per_step(),
+            vec![(token_a, 2_500), (token_b, steps[1].fee_amount)]
+        );
+    }
 
     #[test]
-    fn test_two_hop_route() {
+    fn test_max_price_impact_filters_paths() {
         let token_a = Pubkey::new_unique();
         let token_b = Pubkey::new_unique();
         let token_c = Pubkey::new_unique();
 
-        // Create pools: A-B and B-C
         let pools: Vec<Box<dyn Pool>> = vec![
             Box::new(RaydiumPool::new(
                 Pubkey::new_unique(),
@@ -217,16 +1150,26 @@ mod tests {
             )),
         ];
 
-        // Route from A to C through B
-        let quote = MultiHopRouter::find_best_route(&pools, &token_a, &token_c, 1_000_000, 2)
+        let quote = MultiHopRouter::find_best_route(&pools, &token_a, &token_c, 10_000_000, 2)
             .unwrap();
+        assert!(quote.price_impact_bps > 0);
 
-        assert_eq!(quote.route.hop_count(), 2);
-        assert_eq!(quote.route.steps[0].token_in, token_a);
-        assert_eq!(quote.route.steps[0].token_out, token_b);
-        assert_eq!(quote.route.steps[1].token_in, token_b);
-        assert_eq!(quote.route.steps[1].token_out, token_c);
-        assert!(quote.strategy.starts_with("multi_hop"));
+        let options = RouterOptions::default().with_max_price_impact(quote.price_impact_bps);
+        let allowed = MultiHopRouter::find_best_route_with_options(
+            &pools, &token_a, &token_c, 10_000_000, &options,
+        );
+        assert!(allowed.is_ok());
+
+        let options = RouterOptions::default().with_max_price_impact(quote.price_impact_bps - 1);
+        let result = MultiHopRouter::find_best_route_with_options(
+            &pools, &token_a, &token_c, 10_000_000, &options,
+        );
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::InsufficientLiquidity
+            }
+        ));
     }
 
     #[test]
@@ -268,6 +1211,244 @@ mod tests {
         assert!(quote.amount_out > 0);
     }
 
+    #[test]
+    fn test_hop_penalty_prefers_direct_route() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let token_c = Pubkey::new_unique();
+
+        // A-C-B returns 5 bps more than the direct A-B pool
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(MockPool::fixed(token_a, token_b, 1_000_000, 0)),
+            Box::new(MockPool::fixed(token_a, token_c, 2_000_000, 0)),
+            Box::new(MockPool::fixed(token_c, token_b, 1_000_500, 0)),
+        ];
+
+        let quote = MultiHopRouter::find_best_route(&pools, &token_a, &token_b, 1_000, 2).unwrap();
+        assert_eq!(quote.route.hop_count(), 2);
+        assert_eq!(quote.amount_out, 1_000_500);
+
+        // A 10 bps penalty on the extra hop outweighs the 5 bps advantage
+        let options = RouterOptions::default().with_hop_penalty(10);
+        let quote = MultiHopRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, 1_000, &options,
+        )
+        .unwrap();
+        assert_eq!(quote.route.hop_count(), 1);
+        assert_eq!(quote.amount_out, 1_000_000);
+
+        // A 4 bps penalty doesn't
+        let options = RouterOptions::default().with_hop_penalty(4);
+        let quote = MultiHopRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, 1_000, &options,
+        )
+        .unwrap();
+        assert_eq!(quote.route.hop_count(), 2);
+    }
+
+    #[test]
+    fn test_dijkstra_honours_max_price_impact() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let token_c = Pubkey::new_unique();
+
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_b,
+                token_c,
+                50_000_000_000,
+                2_000_000_000,
+            )),
+        ];
+
+        let quote =
+            MultiHopRouter::find_best_route_dijkstra(&pools, &token_a, &token_c, 10_000_000, 2)
+                .unwrap();
+        assert!(quote.price_impact_bps > 0);
+
+        let options = RouterOptions::default().with_max_price_impact(quote.price_impact_bps);
+        let allowed = MultiHopRouter::find_best_route_dijkstra_with_options(
+            &pools, &token_a, &token_c, 10_000_000, &options,
+        );
+        assert!(allowed.is_ok());
+
+        let options = RouterOptions::default().with_max_price_impact(quote.price_impact_bps - 1);
+        let result = MultiHopRouter::find_best_route_dijkstra_with_options(
+            &pools, &token_a, &token_c, 10_000_000, &options,
+        );
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::InsufficientLiquidity
+            }
+        ));
+    }
+
+    #[test]
+    fn test_dijkstra_honours_hop_penalty() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let token_c = Pubkey::new_unique();
+
+        // A-C-B returns 5 bps more than the direct A-B pool
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(MockPool::fixed(token_a, token_b, 1_000_000, 0)),
+            Box::new(MockPool::fixed(token_a, token_c, 2_000_000, 0)),
+            Box::new(MockPool::fixed(token_c, token_b, 1_000_500, 0)),
+        ];
+
+        let quote =
+            MultiHopRouter::find_best_route_dijkstra(&pools, &token_a, &token_b, 1_000, 2).unwrap();
+        assert_eq!(quote.route.hop_count(), 2);
+
+        let options = RouterOptions::default().with_hop_penalty(10);
+        let quote = MultiHopRouter::find_best_route_dijkstra_with_options(
+            &pools, &token_a, &token_b, 1_000, &options,
+        )
+        .unwrap();
+        assert_eq!(quote.route.hop_count(), 1);
+        assert_eq!(quote.amount_out, 1_000_000);
+
+        let options = RouterOptions::default().with_hop_penalty(4);
+        let quote = MultiHopRouter::find_best_route_dijkstra_with_options(
+            &pools, &token_a, &token_b, 1_000, &options,
+        )
+        .unwrap();
+        assert_eq!(quote.route.hop_count(), 2);
+    }
+
+    #[test]
+    fn test_required_intermediate_forces_route() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let usdc = Pubkey::new_unique();
+
+        // Deep direct A-B pool beats the A-USDC-B path on output
+        let direct = Pubkey::new_unique();
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                direct,
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                usdc,
+                1_000_000_000,
+                1_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                usdc,
+                token_b,
+                1_000_000_000,
+                40_000_000_000,
+            )),
+        ];
+
+        let best = MultiHopRouter::find_best_route(&pools, &token_a, &token_b, 1_000_000, 2)
+            .unwrap();
+        assert_eq!(best.route.steps.len(), 1);
+        assert_eq!(best.route.steps[0].pool_address, direct);
+
+        let options = RouterOptions::default().with_required_intermediate(usdc);
+        let forced = MultiHopRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, 1_000_000, &options,
+        )
+        .unwrap();
+        assert_eq!(forced.route.steps.len(), 2);
+        assert_eq!(forced.route.steps[0].token_out, usdc);
+        assert!(forced.amount_out < best.amount_out);
+
+        // A token no path visits leaves nothing to route through
+        let options = RouterOptions::default().with_required_intermediate(Pubkey::new_unique());
+        let result = MultiHopRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, 1_000_000, &options,
+        );
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::NoMatchingPool
+            }
+        ));
+
+        // Exact-output routing honours it too
+        let options = RouterOptions::default().with_required_intermediate(usdc);
+        let exact_out = MultiHopRouter::find_best_route_exact_out_with_options(
+            &pools, &token_a, &token_b, 1_000_000, &options,
+        )
+        .unwrap();
+        assert_eq!(exact_out.route.steps.len(), 2);
+        assert_eq!(exact_out.route.steps[0].token_out, usdc);
+
+        // The shortest-path search can't force an intermediate, so it refuses to
+        let result = MultiHopRouter::find_best_route_dijkstra_with_options(
+            &pools, &token_a, &token_b, 1_000_000, &options,
+        );
+        assert!(matches!(result, Err(RouterError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_tied_routes_resolve_deterministically() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        // Three identical A-X-B paths through different intermediates
+        let mut pools: Vec<Box<dyn Pool>> = Vec::new();
+        for _ in 0..3 {
+            let mid = Pubkey::new_unique();
+            pools.push(Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                mid,
+                1_000_000_000,
+                1_000_000_000,
+            )));
+            pools.push(Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                mid,
+                token_b,
+                1_000_000_000,
+                1_000_000_000,
+            )));
+        }
+
+        let pool_path = |quote: SwapQuote| -> Vec<Pubkey> {
+            quote.route.steps.iter().map(|step| step.pool_address).collect()
+        };
+
+        let first = pool_path(
+            MultiHopRouter::find_best_route(&pools, &token_a, &token_b, 1_000_000, 2).unwrap(),
+        );
+        let first_dijkstra = pool_path(
+            MultiHopRouter::find_best_route_dijkstra(&pools, &token_a, &token_b, 1_000_000, 2)
+                .unwrap(),
+        );
+
+        for _ in 0..20 {
+            let quote =
+                MultiHopRouter::find_best_route(&pools, &token_a, &token_b, 1_000_000, 2).unwrap();
+            assert_eq!(pool_path(quote), first);
+
+            let quote = MultiHopRouter::find_best_route_dijkstra(
+                &pools, &token_a, &token_b, 1_000_000, 2,
+            )
+            .unwrap();
+            assert_eq!(pool_path(quote), first_dijkstra);
+        }
+    }
+
     #[test]
     fn test_no_route_found() {
         let token_a = Pubkey::new_unique();
@@ -285,7 +1466,34 @@ mod tests {
 
         let result = MultiHopRouter::find_best_route(&pools, &token_a, &token_c, 1_000_000, 2);
 
-        assert!(result.is_err());
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::NoMatchingPool
+            }
+        ));
+    }
+
+    #[test]
+    fn test_identical_tokens_rejected() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        // A -> B -> A would otherwise come back as a two-hop "route"
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(Pubkey::new_unique(), token_a, token_b, 1_000, 1_000)),
+            Box::new(RaydiumPool::new(Pubkey::new_unique(), token_b, token_a, 1_000, 1_000)),
+        ];
+
+        let results = [
+            MultiHopRouter::find_best_route(&pools, &token_a, &token_a, 100, 2),
+            MultiHopRouter::find_best_route_dijkstra(&pools, &token_a, &token_a, 100, 2),
+            MultiHopRouter::find_best_route_exact_out(&pools, &token_a, &token_a, 100, 2),
+            MultiHopRouter::find_best_route_with_split(&pools, &token_a, &token_a, 100, 2),
+        ];
+        for result in results {
+            assert!(matches!(result, Err(RouterError::IdenticalTokens)));
+        }
     }
 
     #[test]
@@ -319,7 +1527,7 @@ mod tests {
         // Route from A to A should not be found (would be a cycle)
         let result = MultiHopRouter::find_best_route(&pools, &token_a, &token_a, 1_000_000, 2);
 
-        assert!(result.is_err());
+        assert!(matches!(result.unwrap_err(), RouterError::IdenticalTokens));
     }
 
     #[test]
@@ -360,4 +1568,282 @@ mod tests {
         assert_eq!(quote.route.hop_count(), 3);
         assert_eq!(quote.strategy, "multi_hop_3");
     }
+
+    #[test]
+    fn test_all_paths_exhausted() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        // Path exists, but the amount overflows the pool math
+        let pools: Vec<Box<dyn Pool>> = vec![Box::new(RaydiumPool::new(
+            Pubkey::new_unique(),
+            token_a,
+            token_b,
+            u64::MAX,
+            u64::MAX,
+        ))];
+
+        let result = MultiHopRouter::find_best_route(&pools, &token_a, &token_b, u64::MAX, 2);
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::AllPathsExhausted
+            }
+        ));
+
+        let result = MultiHopRouter::find_best_route(&pools, &token_a, &token_b, 0, 2);
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::ZeroAmount
+            }
+        ));
+    }
+
+    #[test]
+    fn test_multi_hop_with_split_first_hop() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let token_c = Pubkey::new_unique();
+
+        // Two A-B pools and one deep B-C pool
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_b,
+                token_c,
+                500_000_000_000,
+                20_000_000_000,
+            )),
+        ];
+
+        let amount_in = 100_000_000;
+        let quote =
+            MultiHopRouter::find_best_route_with_split(&pools, &token_a, &token_c, amount_in, 2)
+                .unwrap();
+
+        assert_eq!(quote.strategy, "multi_hop_split");
+        assert_eq!(quote.amount_in, amount_in);
+        assert_eq!(quote.route.total_input, amount_in);
+
+        let (first_hop, rest): (Vec<_>, Vec<_>) = quote
+            .route
+            .steps
+            .iter()
+            .partition(|step| step.token_in == token_a);
+
+        assert_eq!(first_hop.len(), 2);
+        assert_eq!(rest.len(), 1);
+        assert_eq!(first_hop.iter().map(|s| s.amount_in).sum::<u64>(), amount_in);
+        assert_eq!(first_hop.iter().map(|s| s.amount_out).sum::<u64>(), rest[0].amount_in);
+        assert_eq!(rest[0].amount_out, quote.amount_out);
+
+        // Splitting the first hop should beat routing it through a single pool
+        let unsplit =
+            MultiHopRouter::find_best_route(&pools, &token_a, &token_c, amount_in, 2).unwrap();
+        assert!(quote.amount_out > unsplit.amount_out);
+    }
+
+    #[test]
+    fn test_multi_hop_with_split_respects_options() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let token_c = Pubkey::new_unique();
+
+        // Two Raydium A-B pools, the second twice as deep, and one B-C pool
+        let shallow = Pubkey::new_unique();
+        let deep = Pubkey::new_unique();
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                shallow,
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                deep,
+                token_a,
+                token_b,
+                2_000_000_000,
+                100_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_b,
+                token_c,
+                500_000_000_000,
+                20_000_000_000,
+            )),
+        ];
+        let amount_in = 100_000_000;
+        let route_with = |options: &RouterOptions| {
+            MultiHopRouter::find_best_route_with_split_with_options(
+                &pools, &token_a, &token_c, amount_in, options,
+            )
+        };
+        let first_hop_pools = |quote: &SwapQuote| -> Vec<Pubkey> {
+            quote
+                .route
+                .steps
+                .iter()
+                .filter(|step| step.token_in == token_a)
+                .map(|step| step.pool_address)
+                .collect()
+        };
+
+        let quote = route_with(&RouterOptions::default()).unwrap();
+        assert_eq!(first_hop_pools(&quote).len(), 2);
+
+        // Excluded pools stay out of the split first hop
+        let options = RouterOptions::default().with_excluded_pools(vec![deep]);
+        let quote = route_with(&options).unwrap();
+        assert_eq!(first_hop_pools(&quote), vec![shallow]);
+
+        // Only the deepest pool per DEX and pair is split across
+        let options = RouterOptions::default().with_deepest_pool_per_dex();
+        let quote = route_with(&options).unwrap();
+        assert_eq!(first_hop_pools(&quote), vec![deep]);
+
+        // The whole route is held to the price impact limit
+        let unlimited = route_with(&RouterOptions::default()).unwrap();
+        let options =
+            RouterOptions::default().with_max_price_impact(unlimited.price_impact_bps - 1);
+        assert!(matches!(
+            route_with(&options).unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::InsufficientLiquidity
+            }
+        ));
+    }
+
+    fn create_dense_pools(tokens: &[Pubkey]) -> Vec<Box<dyn Pool>> {
+        // Two pools between every pair of tokens
+        let mut pools: Vec<Box<dyn Pool>> = Vec::new();
+        for (i, token_x) in tokens.iter().enumerate() {
+            for token_y in &tokens[i + 1..] {
+                for _ in 0..2 {
+                    pools.push(Box::new(RaydiumPool::new(
+                        Pubkey::new_unique(),
+                        *token_x,
+                        *token_y,
+                        1_000_000_000,
+                        1_000_000_000,
+                    )));
+                }
+            }
+        }
+        pools
+    }
+
+    #[test]
+    fn test_find_cycles_returns_to_start() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let token_c = Pubkey::new_unique();
+
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(Pubkey::new_unique(), token_a, token_b, 1_000, 1_000)),
+            Box::new(RaydiumPool::new(Pubkey::new_unique(), token_b, token_c, 1_000, 1_000)),
+            Box::new(RaydiumPool::new(Pubkey::new_unique(), token_c, token_a, 1_000, 1_000)),
+        ];
+        let graph = MultiHopRouter::build_graph(&pools, &RouterOptions::default());
+
+        // A -> B -> C -> A in both directions
+        let cycles = MultiHopRouter::find_cycles(&graph, &token_a, 3, DEFAULT_MAX_PATHS);
+        assert_eq!(cycles.len(), 2);
+        for cycle in &cycles {
+            assert_eq!(cycle.len(), 3);
+            assert_eq!(cycle[0].from_token, token_a);
+            assert_eq!(cycle[2].to_token, token_a);
+        }
+
+        // Too few hops to close the triangle
+        assert!(MultiHopRouter::find_cycles(&graph, &token_a, 2, DEFAULT_MAX_PATHS).is_empty());
+    }
+
+    #[test]
+    fn test_dense_graph_respects_path_cap() {
+        let tokens: Vec<Pubkey> = (0..8).map(|_| Pubkey::new_unique()).collect();
+        let pools = create_dense_pools(&tokens);
+        let (token_a, token_d) = (tokens[0], tokens[3]);
+
+        let graph = MultiHopRouter::build_graph(&pools, &RouterOptions::default());
+        let capped = MultiHopRouter::find_all_paths(&graph, &token_a, &token_d, 3, 100);
+        assert!(!capped.is_empty());
+        assert!(capped.len() <= 100);
+
+        let uncapped =
+            MultiHopRouter::find_all_paths(&graph, &token_a, &token_d, 3, DEFAULT_MAX_PATHS);
+        assert!(uncapped.len() <= DEFAULT_MAX_PATHS);
+
+        let quote = MultiHopRouter::find_best_route(&pools, &token_a, &token_d, 1_000_000, 3)
+            .unwrap();
+        assert_eq!(quote.route.steps.first().unwrap().token_in, token_a);
+        assert_eq!(quote.route.steps.last().unwrap().token_out, token_d);
+
+        // A tight cap still yields a valid route from the paths explored
+        let quote =
+            MultiHopRouter::find_best_route_capped(&pools, &token_a, &token_d, 1_000_000, 3, 5)
+                .unwrap();
+        assert_eq!(quote.route.steps.first().unwrap().token_in, token_a);
+        assert_eq!(quote.route.steps.last().unwrap().token_out, token_d);
+    }
+
+    #[test]
+    fn test_shortest_path_matches_or_beats_enumeration() {
+        let tokens: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let (token_a, token_b, token_c, token_d) = (tokens[0], tokens[1], tokens[2], tokens[3]);
+        let pool = |x: Pubkey, y: Pubkey, reserve_x: u64, reserve_y: u64| -> Box<dyn Pool> {
+            Box::new(RaydiumPool::new(Pubkey::new_unique(), x, y, reserve_x, reserve_y))
+        };
+
+        // A shallow direct pool, deep two-hop paths, and a three-hop detour
+        let pools: Vec<Box<dyn Pool>> = vec![
+            pool(token_a, token_d, 10_000_000, 10_000_000),
+            pool(token_a, token_b, 1_000_000_000, 2_000_000_000),
+            pool(token_b, token_d, 2_000_000_000, 1_000_000_000),
+            pool(token_a, token_c, 500_000_000, 500_000_000),
+            pool(token_c, token_d, 500_000_000, 520_000_000),
+            pool(token_b, token_c, 2_000_000_000, 1_050_000_000),
+        ];
+
+        for amount_in in [1_000, 1_000_000, 50_000_000] {
+            let enumerated =
+                MultiHopRouter::find_best_route(&pools, &token_a, &token_d, amount_in, 3).unwrap();
+            let shortest = MultiHopRouter::find_best_route_dijkstra(
+                &pools, &token_a, &token_d, amount_in, 3,
+            )
+            .unwrap();
+
+            assert!(shortest.amount_out >= enumerated.amount_out);
+            assert_eq!(shortest.amount_in, amount_in);
+            assert_eq!(shortest.route.steps.first().unwrap().token_in, token_a);
+            assert_eq!(shortest.route.steps.last().unwrap().token_out, token_d);
+        }
+
+        let unreachable = Pubkey::new_unique();
+        let result =
+            MultiHopRouter::find_best_route_dijkstra(&pools, &token_a, &unreachable, 1_000, 3);
+        assert!(matches!(
+            result,
+            Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::NoMatchingPool
+            })
+        ));
+    }
 }
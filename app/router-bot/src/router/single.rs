@@ -1,6 +1,11 @@
 //! Single pool router - finds the best single pool for a swap
 
-use crate::error::{Result, RouterError};
+use crate::calculator::calculate_fee_amount;
+use crate::error::{NoRouteReason, Result, RouterError};
+use crate::router::{
+    PoolIndex, RouterOptions, candidate_pools, dedupe_pools, ensure_distinct_tokens,
+    indexed_candidates,
+};
 use crate::types::pool::Pool;
 use crate::types::route::{Route, RouteStep, SwapQuote};
 use solana_sdk::pubkey::Pubkey;
@@ -24,10 +29,76 @@ impl SinglePoolRouter {
         token_in: &Pubkey,
         token_out: &Pubkey,
         amount_in: u64,
+    ) -> Result<SwapQuote> {
+        Self::find_best_route_with_options(
+            pools,
+            token_in,
+            token_out,
+            amount_in,
+            &RouterOptions::default(),
+        )
+    }
+
+    /// Find the best pool for a swap, subject to `options`
+    ///
+    /// Pools rejected by the DEX/pool filters are ignored, and pools whose quote
+    /// exceeds `options.max_price_impact_bps` are skipped.
+    pub fn find_best_route_with_options(
+        pools: &[Box<dyn Pool>],
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        options: &RouterOptions,
+    ) -> Result<SwapQuote> {
+        ensure_distinct_tokens(token_in, token_out)?;
+
+        if amount_in == 0 {
+            return Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::ZeroAmount,
+            });
+        }
+
+        let pools = candidate_pools(pools, options);
+        Self::best_quote(&pools, 0..pools.len(), token_in, token_out, amount_in, options)
+    }
+
+    /// Find the best pool for a swap using a pre-built `PoolIndex` over `pools`
+    ///
+    /// Equivalent to `find_best_route_with_options`, but only the pools indexed
+    /// under the token pair are considered instead of scanning every pool.
+    pub fn find_best_route_indexed(
+        pools: &[Box<dyn Pool>],
+        index: &PoolIndex,
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        options: &RouterOptions,
+    ) -> Result<SwapQuote> {
+        ensure_distinct_tokens(token_in, token_out)?;
+
+        if amount_in == 0 {
+            return Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::ZeroAmount,
+            });
+        }
+
+        let candidates = indexed_candidates(pools, index, token_in, token_out, options);
+        Self::best_quote(pools, candidates, token_in, token_out, amount_in, options)
+    }
+
+    /// Best single-pool quote among the pools at positions `candidates`
+    fn best_quote(
+        pools: &[Box<dyn Pool>],
+        candidates: impl IntoIterator<Item = usize>,
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        options: &RouterOptions,
     ) -> Result<SwapQuote> {
         let mut best_quote: Option<SwapQuote> = None;
+        let mut found_matching_pool = false;
 
-        for pool in pools {
+        for pool in candidates.into_iter().map(|idx| &pools[idx]) {
             // Check if pool matches token pair
             let (matches, a_to_b) = if pool.token_a() == token_in && pool.token_b() == token_out {
                 (true, true)
@@ -37,9 +108,10 @@ impl SinglePoolRouter {
                 (false, false)
             };
 
-            if !matches {
+            if !matches || !options.allows_pool(pool.as_ref()) {
                 continue;
             }
+            found_matching_pool = true;
 
             // Check liquidity
             if !pool.has_sufficient_liquidity(amount_in, a_to_b) {
@@ -48,6 +120,7 @@ impl SinglePoolRouter {
 
             // Calculate output
             match pool.calculate_output(amount_in, a_to_b) {
+                Ok((_, price_impact)) if !options.allows_price_impact(price_impact) => continue,
                 Ok((amount_out, price_impact)) => {
                     let step = RouteStep {
                         pool_address: *pool.address(),
@@ -58,6 +131,7 @@ impl SinglePoolRouter {
                         amount_out,
                         price_impact_bps: price_impact,
                         fee_bps: pool.fee_bps(),
+                        fee_amount: calculate_fee_amount(amount_in, pool.fee_bps()),
                     };
 
                     let route = Route::single_step(step, amount_in, amount_out);
@@ -74,7 +148,7 @@ impl SinglePoolRouter {
                     best_quote = match best_quote {
                         None => Some(quote),
                         Some(current_best) => {
-                            if quote.better_than(&current_best) {
+                            if Self::prefer(&quote, &current_best, options) {
                                 Some(quote)
                             } else {
                                 Some(current_best)
@@ -86,7 +160,161 @@ impl SinglePoolRouter {
             }
         }
 
-        best_quote.ok_or(RouterError::NoRouteFound)
+        best_quote.ok_or(RouterError::NoRouteFound {
+            reason: Self::no_route_reason(found_matching_pool),
+        })
+    }
+
+    /// Whether `candidate` should replace `current` as the best quote
+    ///
+    /// Outputs within `options.tie_tolerance_bps` of each other are a tie, won by
+    /// the pool with the lower fee: a cheaper fee tier holds up better if the
+    /// trade size changes before execution. Equal fees fall back to `better_than`.
+    fn prefer(candidate: &SwapQuote, current: &SwapQuote, options: &RouterOptions) -> bool {
+        let fee = |quote: &SwapQuote| quote.route.steps.first().map_or(0, |step| step.fee_bps);
+
+        if let Some(tolerance_bps) = options.tie_tolerance_bps {
+            let larger = candidate.amount_out.max(current.amount_out) as u128;
+            let difference = candidate.amount_out.abs_diff(current.amount_out) as u128;
+            let tied = difference * 10000 <= larger * tolerance_bps as u128;
+
+            if tied && fee(candidate) != fee(current) {
+                return fee(candidate) < fee(current);
+            }
+        }
+
+        candidate.better_than(current)
+    }
+
+    /// Find the pool that requires the least input to produce a desired output
+    ///
+    /// # Arguments
+    /// * `pools` - List of available pools
+    /// * `token_in` - Input token mint
+    /// * `token_out` - Output token mint
+    /// * `amount_out` - Desired output amount
+    ///
+    /// # Returns
+    /// A swap quote whose `amount_in` is the required input, or error if no pool can fill it
+    pub fn find_best_route_exact_out(
+        pools: &[Box<dyn Pool>],
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_out: u64,
+    ) -> Result<SwapQuote> {
+        Self::find_best_route_exact_out_with_options(
+            pools,
+            token_in,
+            token_out,
+            amount_out,
+            &RouterOptions::default(),
+        )
+    }
+
+    /// Find the pool that requires the least input for `amount_out`, subject to `options`
+    ///
+    /// Each pool's own curve is inverted with `Pool::required_input`, and the
+    /// input is quoted forward to confirm it fills the target. Pools are filtered
+    /// as in `find_best_route_with_options`.
+    pub fn find_best_route_exact_out_with_options(
+        pools: &[Box<dyn Pool>],
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_out: u64,
+        options: &RouterOptions,
+    ) -> Result<SwapQuote> {
+        ensure_distinct_tokens(token_in, token_out)?;
+
+        if amount_out == 0 {
+            return Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::ZeroAmount,
+            });
+        }
+
+        let pools = candidate_pools(pools, options);
+
+        let mut best_quote: Option<SwapQuote> = None;
+        let mut found_matching_pool = false;
+
+        for pool in pools.iter().filter(|pool| options.allows_pool(pool.as_ref())) {
+            let (matches, a_to_b) = if pool.token_a() == token_in && pool.token_b() == token_out {
+                (true, true)
+            } else if pool.token_b() == token_in && pool.token_a() == token_out {
+                (true, false)
+            } else {
+                (false, false)
+            };
+
+            if !matches {
+                continue;
+            }
+            found_matching_pool = true;
+
+            // Pools that cannot fill the target (InsufficientLiquidity) are skipped
+            let Ok(amount_in) = pool.required_input(amount_out, a_to_b) else {
+                continue;
+            };
+            if !pool.has_sufficient_liquidity(amount_in, a_to_b) {
+                continue;
+            }
+
+            // Quote the input forward: it must fill the target within the impact limit
+            let price_impact = match pool.calculate_output(amount_in, a_to_b) {
+                Ok((filled, price_impact))
+                    if filled >= amount_out && options.allows_price_impact(price_impact) =>
+                {
+                    price_impact
+                }
+                _ => continue,
+            };
+
+            let step = RouteStep {
+                pool_address: *pool.address(),
+                dex: pool.dex_name().to_string(),
+                token_in: *token_in,
+                token_out: *token_out,
+                amount_in,
+                amount_out,
+                price_impact_bps: price_impact,
+                fee_bps: pool.fee_bps(),
+                fee_amount: calculate_fee_amount(amount_in, pool.fee_bps()),
+            };
+
+            let route = Route::single_step(step, amount_in, amount_out);
+            let quote = SwapQuote::new(
+                *token_in,
+                *token_out,
+                amount_in,
+                amount_out,
+                route,
+                "single_pool_exact_out".to_string(),
+            );
+
+            // Keep if this requires less input than current best
+            best_quote = match best_quote {
+                None => Some(quote),
+                Some(current_best) => {
+                    if quote.amount_in < current_best.amount_in {
+                        Some(quote)
+                    } else {
+                        Some(current_best)
+                    }
+                }
+            };
+        }
+
+        best_quote.ok_or(RouterError::NoRouteFound {
+            reason: Self::no_route_reason(found_matching_pool),
+        })
+    }
+
+    /// Reason for a failed search, depending on whether any pool matched the pair
+    fn no_route_reason(found_matching_pool: bool) -> NoRouteReason {
+        if found_matching_pool {
+            NoRouteReason::InsufficientLiquidity
+        } else {
+            NoRouteReason::NoMatchingPool
+        }
     }
 
     /// Find all viable pools for a token pair (for analysis/debugging)
@@ -96,6 +324,9 @@ impl SinglePoolRouter {
         token_out: &Pubkey,
         amount_in: u64,
     ) -> Vec<SwapQuote> {
+        let pools = dedupe_pools(pools);
+        let pools = pools.as_ref();
+
         let mut quotes = Vec::new();
 
         for pool in pools {
@@ -121,6 +352,7 @@ impl SinglePoolRouter {
                     amount_out,
                     price_impact_bps: price_impact,
                     fee_bps: pool.fee_bps(),
+                    fee_amount: calculate_fee_amount(amount_in, pool.fee_bps()),
                 };
 
                 let route = Route::single_step(step, amount_in, amount_out);
@@ -146,6 +378,7 @@ impl SinglePoolRouter {
 mod tests {
     use super::*;
     use crate::dex::{OrcaPool, RaydiumPool};
+    use crate::types::test_support::MockPool;
 
     fn create_test_pools() -> Vec<Box<dyn Pool>> {
         let token_a = Pubkey::new_unique();
@@ -194,7 +427,215 @@ mod tests {
         let result = SinglePoolRouter::find_best_route(&pools, &wrong_token, &token_b, 1_000_000);
 
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), RouterError::NoRouteFound));
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::NoMatchingPool
+            }
+        ));
+    }
+
+    #[test]
+    fn test_tie_tolerance_prefers_lower_fee() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        // The 0.30% pool quotes 5 bps more than the 0.25% pool
+        let (low_fee, high_fee) = (25, 30);
+        let best_output = 2_001_000;
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(MockPool::fixed(token_a, token_b, 2_000_000, 0).with_fee_bps(low_fee)),
+            Box::new(MockPool::fixed(token_a, token_b, best_output, 0).with_fee_bps(high_fee)),
+        ];
+        let amount_in = 50_000_000;
+
+        let quote = SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, amount_in)
+            .unwrap();
+        assert_eq!(quote.route.steps[0].fee_bps, high_fee);
+        assert_eq!(quote.amount_out, best_output);
+
+        let options = RouterOptions::default().with_tie_tolerance(10);
+        let quote = SinglePoolRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, amount_in, &options,
+        )
+        .unwrap();
+        assert_eq!(quote.route.steps[0].fee_bps, low_fee);
+        assert!(quote.amount_out < best_output);
+
+        // Outside the tolerance, output wins again
+        let options = RouterOptions::default().with_tie_tolerance(1);
+        let quote = SinglePoolRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, amount_in, &options,
+        )
+        .unwrap();
+        assert_eq!(quote.amount_out, best_output);
+    }
+
+    #[test]
+    fn test_identical_tokens_rejected() {
+        let pools = create_test_pools();
+        let token_a = *pools[0].token_a();
+        let index = PoolIndex::new(&pools);
+        let options = RouterOptions::default();
+
+        let results = [
+            SinglePoolRouter::find_best_route(&pools, &token_a, &token_a, 1_000_000),
+            SinglePoolRouter::find_best_route_indexed(
+                &pools, &index, &token_a, &token_a, 1_000_000, &options,
+            ),
+            SinglePoolRouter::find_best_route_exact_out(&pools, &token_a, &token_a, 1_000_000),
+        ];
+        for result in results {
+            assert!(matches!(result, Err(RouterError::IdenticalTokens)));
+        }
+    }
+
+    #[test]
+    fn test_no_route_reasons() {
+        let pools = create_test_pools();
+        let token_a = *pools[0].token_a();
+        let token_b = *pools[0].token_b();
+
+        let result = SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, 0);
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::ZeroAmount
+            }
+        ));
+
+        let result = SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, u64::MAX);
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::InsufficientLiquidity
+            }
+        ));
+    }
+
+    fn create_shallow_and_deep_pools() -> Vec<Box<dyn Pool>> {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        vec![
+            // Better price, but thin enough that a modest trade moves it
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                10_000_000,
+                600_000_000,
+            )),
+            Box::new(OrcaPool::new_constant_product(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+            )),
+        ]
+    }
+
+    #[test]
+    fn test_max_price_impact_prefers_deeper_pool() {
+        let pools = create_shallow_and_deep_pools();
+        let token_a = *pools[0].token_a();
+        let token_b = *pools[0].token_b();
+
+        let unfiltered =
+            SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, 500_000).unwrap();
+        assert_eq!(unfiltered.route.steps[0].dex, "Raydium");
+        assert!(unfiltered.price_impact_bps > 100);
+
+        let options = RouterOptions::default().with_max_price_impact(100);
+        let filtered = SinglePoolRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, 500_000, &options,
+        )
+        .unwrap();
+        assert_eq!(filtered.route.steps[0].dex, "Orca");
+        assert!(filtered.price_impact_bps <= 100);
+    }
+
+    #[test]
+    fn test_max_price_impact_filters_every_pool() {
+        let pools = create_shallow_and_deep_pools();
+        let token_a = *pools[0].token_a();
+        let token_b = *pools[0].token_b();
+
+        let options = RouterOptions::default().with_max_price_impact(1);
+        let result = SinglePoolRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, 500_000, &options,
+        );
+
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::InsufficientLiquidity
+            }
+        ));
+    }
+
+    #[test]
+    fn test_allowed_dexes_only_considers_orca() {
+        let pools = create_test_pools();
+        let token_a = *pools[0].token_a();
+        let token_b = *pools[0].token_b();
+
+        let options = RouterOptions::default().with_allowed_dexes(vec!["Orca".to_string()]);
+        let quote = SinglePoolRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, 1_000_000, &options,
+        )
+        .unwrap();
+        assert_eq!(quote.route.steps[0].dex, "Orca");
+
+        let options = RouterOptions::default().with_allowed_dexes(vec!["Phoenix".to_string()]);
+        let result = SinglePoolRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, 1_000_000, &options,
+        );
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::NoMatchingPool
+            }
+        ));
+    }
+
+    #[test]
+    fn test_exclude_pools() {
+        let pools = create_test_pools();
+        let token_a = *pools[0].token_a();
+        let token_b = *pools[0].token_b();
+
+        let best = SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, 1_000_000).unwrap();
+        let best_pool = best.route.steps[0].pool_address;
+
+        let options = RouterOptions::default().with_excluded_pools(vec![best_pool]);
+        let quote = SinglePoolRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, 1_000_000, &options,
+        )
+        .unwrap();
+        assert_ne!(quote.route.steps[0].pool_address, best_pool);
+    }
+
+    #[test]
+    fn test_single_pool_fee_amount() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pools: Vec<Box<dyn Pool>> = vec![Box::new(RaydiumPool::new(
+            Pubkey::new_unique(),
+            token_a,
+            token_b,
+            1_000_000_000,
+            50_000_000_000,
+        ))];
+
+        let quote = SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, 1_000_000)
+            .unwrap();
+
+        // 0.25% of 1_000_000
+        assert_eq!(quote.route.steps[0].fee_amount, 2_500);
+        assert_eq!(quote.route.total_fees_in_input_token(), 2_500);
+        assert_eq!(quote.route.fees_per_step(), vec![(token_a, 2_500)]);
     }
 
     #[test]
@@ -230,28 +671,146 @@ mod tests {
         let token_a = Pubkey::new_unique();
         let token_b = Pubkey::new_unique();
 
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(MockPool::fixed(token_a, token_b, 49_800_000, 0).with_dex("Raydium")),
+            Box::new(MockPool::fixed(token_a, token_b, 49_900_000, 0).with_dex("Orca")),
+            Box::new(MockPool::failing(token_a, token_b).with_dex("Meteora")),
+        ];
+
+        let quote = SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, 1_000_000)
+            .unwrap();
+
+        // Should choose Orca for its better output, skipping the failing pool
+        assert_eq!(quote.route.steps[0].dex, "Orca");
+        assert_eq!(quote.amount_out, 49_900_000);
+    }
+
+    #[test]
+    fn test_exact_out_prefers_deeper_pool() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        let shallow = Pubkey::new_unique();
+        let deep = Pubkey::new_unique();
+
         let pools: Vec<Box<dyn Pool>> = vec![
             Box::new(RaydiumPool::new(
-                Pubkey::new_unique(),
+                shallow,
                 token_a,
                 token_b,
                 1_000_000_000,
                 50_000_000_000,
-            )), // 0.25% fee
-            Box::new(OrcaPool::new_whirlpool(
-                Pubkey::new_unique(),
+            )),
+            Box::new(RaydiumPool::new(
+                deep,
                 token_a,
                 token_b,
-                1_000_000_000,
-                50_000_000_000,
-                10, // 0.1% fee - should give better output
+                10_000_000_000,
+                500_000_000_000,
             )),
         ];
 
-        let quote = SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, 1_000_000)
-            .unwrap();
+        let target = 5_000_000_000;
+        let quote =
+            SinglePoolRouter::find_best_route_exact_out(&pools, &token_a, &token_b, target)
+                .unwrap();
 
-        // Should choose Orca due to lower fee
-        assert_eq!(quote.route.steps[0].dex, "Orca");
+        // Deeper pool needs less input for the same output
+        assert_eq!(quote.route.steps[0].pool_address, deep);
+        assert_eq!(quote.amount_out, target);
+
+        let (shallow_out, _) = pools[0].calculate_output(quote.amount_in, true).unwrap();
+        assert!(shallow_out < target);
+        let (deep_out, _) = pools[1].calculate_output(quote.amount_in, true).unwrap();
+        assert!(deep_out >= target);
+    }
+
+    #[test]
+    fn test_exact_out_insufficient_liquidity() {
+        let pools = create_test_pools();
+        let token_a = *pools[0].token_a();
+        let token_b = *pools[0].token_b();
+
+        // More than either pool holds
+        let result =
+            SinglePoolRouter::find_best_route_exact_out(&pools, &token_a, &token_b, 200_000_000_000);
+
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::InsufficientLiquidity
+            }
+        ));
+    }
+
+    #[test]
+    fn test_exact_out_inverts_concentrated_pool() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        // Price of 50 B per A, with 10x the liquidity of a full-range position
+        let pool = OrcaPool::new_whirlpool_with_liquidity(
+            Pubkey::new_unique(),
+            token_a,
+            token_b,
+            1_000_000_000,
+            50_000_000_000,
+            10,
+            (50f64.sqrt() * (1u128 << 64) as f64) as u128,
+            70_000_000_000,
+        )
+        .unwrap();
+        let pools: Vec<Box<dyn Pool>> = vec![Box::new(pool)];
+
+        let target = 5_000_000_000;
+        let quote =
+            SinglePoolRouter::find_best_route_exact_out(&pools, &token_a, &token_b, target)
+                .unwrap();
+        assert_eq!(quote.amount_out, target);
+
+        // The input fills the target, and one unit less would not
+        let (output, _) = pools[0].calculate_output(quote.amount_in, true).unwrap();
+        assert!(output >= target);
+        let (output, _) = pools[0].calculate_output(quote.amount_in - 1, true).unwrap();
+        assert!(output < target);
+
+        // Constant-product math on the same reserves would overcharge
+        let constant_product =
+            crate::calculator::calculate_amount_in(target, 1_000_000_000, 50_000_000_000, 10)
+                .unwrap();
+        assert!(quote.amount_in < constant_product);
+    }
+
+    #[test]
+    fn test_exact_out_respects_options() {
+        let pools = create_shallow_and_deep_pools();
+        let token_a = *pools[0].token_a();
+        let token_b = *pools[0].token_b();
+        let target = 30_000_000;
+
+        let unfiltered =
+            SinglePoolRouter::find_best_route_exact_out(&pools, &token_a, &token_b, target)
+                .unwrap();
+        assert_eq!(unfiltered.route.steps[0].dex, "Raydium");
+        assert!(unfiltered.price_impact_bps > 100);
+
+        let options = RouterOptions::default().with_max_price_impact(100);
+        let filtered = SinglePoolRouter::find_best_route_exact_out_with_options(
+            &pools, &token_a, &token_b, target, &options,
+        )
+        .unwrap();
+        assert_eq!(filtered.route.steps[0].dex, "Orca");
+        assert!(filtered.price_impact_bps <= 100);
+
+        let options = RouterOptions::default().with_allowed_dexes(vec!["Phoenix".to_string()]);
+        let result = SinglePoolRouter::find_best_route_exact_out_with_options(
+            &pools, &token_a, &token_b, target, &options,
+        );
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::NoMatchingPool
+            }
+        ));
     }
 }
@@ -1,10 +1,18 @@
 //! Split router - optimizes by splitting amount across multiple pools
 
-use crate::error::{Result, RouterError};
+use crate::calculator::calculate_fee_amount;
+use crate::error::{NoRouteReason, Result, RouterError};
+use crate::router::{
+    PoolIndex, RouterOptions, candidate_pools, ensure_distinct_tokens, indexed_candidates,
+    rank_by_health,
+};
 use crate::types::pool::Pool;
 use crate::types::route::{Route, RouteStep, SwapQuote};
 use solana_sdk::pubkey::Pubkey;
 
+/// Default cap on the number of pools a split is spread across
+pub const DEFAULT_MAX_SPLIT_POOLS: usize = 3;
+
 /// Router for split routing across multiple pools
 pub struct SplitRouter;
 
@@ -27,10 +35,69 @@ impl SplitRouter {
         token_out: &Pubkey,
         amount_in: u64,
     ) -> Result<SwapQuote> {
+        Self::find_best_route_with_options(
+            pools,
+            token_in,
+            token_out,
+            amount_in,
+            &RouterOptions::default(),
+        )
+    }
+
+    /// Find optimal split routing, subject to `options`
+    ///
+    /// Only pools passing the DEX/pool filters are split across, and a split whose
+    /// combined price impact exceeds `options.max_price_impact_bps` is rejected.
+    pub fn find_best_route_with_options(
+        pools: &[Box<dyn Pool>],
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        options: &RouterOptions,
+    ) -> Result<SwapQuote> {
+        let pools = candidate_pools(pools, options);
+        Self::find_split_route(&pools, 0..pools.len(), token_in, token_out, amount_in, options)
+    }
+
+    /// Find optimal split routing using a pre-built `PoolIndex` over `pools`
+    ///
+    /// Equivalent to `find_best_route_with_options`, but only the pools indexed
+    /// under the token pair are considered instead of scanning every pool.
+    pub fn find_best_route_indexed(
+        pools: &[Box<dyn Pool>],
+        index: &PoolIndex,
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        options: &RouterOptions,
+    ) -> Result<SwapQuote> {
+        let candidates = indexed_candidates(pools, index, token_in, token_out, options);
+        Self::find_split_route(pools, candidates, token_in, token_out, amount_in, options)
+    }
+
+    /// Find the output-maximizing split across the pools at positions `candidates`
+    /// that are allowed by `options`, rejecting it if it exceeds the impact limit
+    fn find_split_route(
+        pools: &[Box<dyn Pool>],
+        candidates: impl IntoIterator<Item = usize>,
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+        options: &RouterOptions,
+    ) -> Result<SwapQuote> {
+        ensure_distinct_tokens(token_in, token_out)?;
+
+        if amount_in == 0 {
+            return Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::ZeroAmount,
+            });
+        }
+
         // First, filter pools that match the token pair
-        let matching_pools: Vec<(usize, bool)> = pools
-            .iter()
-            .enumerate()
+        let mut matching_pools: Vec<(usize, bool)> = candidates
+            .into_iter()
+            .map(|idx| (idx, &pools[idx]))
+            .filter(|(_, pool)| options.allows_pool(pool.as_ref()))
             .filter_map(|(idx, pool)| {
                 if pool.token_a() == token_in && pool.token_b() == token_out {
                     Some((idx, true))
@@ -43,132 +110,279 @@ impl SplitRouter {
             .collect();
 
         if matching_pools.is_empty() {
-            return Err(RouterError::NoRouteFound);
+            return Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::NoMatchingPool,
+            });
+        }
+        // Ranking costs a quote per pool, so only rank when the cap drops some
+        if let Some(max_pools) = options.max_split_pools.map(|max_pools| max_pools.max(1))
+            && matching_pools.len() > max_pools
+        {
+            rank_by_health(pools, &mut matching_pools, amount_in);
+            matching_pools.truncate(max_pools);
         }
 
         // If only one pool, no splitting needed
-        if matching_pools.len() == 1 {
+        let quote = if matching_pools.len() == 1 {
             let (idx, a_to_b) = matching_pools[0];
             let pool = &pools[idx];
-            return Self::create_single_pool_quote(pool, token_in, token_out, amount_in, a_to_b);
+            Self::create_single_pool_quote(pool, token_in, token_out, amount_in, a_to_b)?
+        } else {
+            let max_per_pool =
+                Self::max_amount_per_pool(amount_in, matching_pools.len(), options);
+
+            // Try different split strategies for 2 pools
+            let best_split = if matching_pools.len() == 2 {
+                Self::optimize_two_pool_split(pools, &matching_pools, amount_in, max_per_pool)?
+            } else {
+                // For 3+ pools, use a greedy approach
+                Self::optimize_multi_pool_split(pools, &matching_pools, amount_in, max_per_pool)?
+            };
+
+            // Build route from best split
+            let split_quote = Self::build_split_route(
+                &best_split,
+                pools,
+                &matching_pools,
+                token_in,
+                token_out,
+                amount_in,
+            )?;
+
+            // Without a per-pool cap, never return a split worse than its best pool alone
+            let best_single = (max_per_pool >= amount_in)
+                .then(|| {
+                    Self::best_single_pool_quote(
+                        pools,
+                        &matching_pools,
+                        token_in,
+                        token_out,
+                        amount_in,
+                    )
+                })
+                .flatten();
+            match best_single {
+                Some(single) if single.amount_out > split_quote.amount_out => single,
+                _ => split_quote,
+            }
+        };
+
+        if !options.allows_price_impact(quote.price_impact_bps) {
+            return Err(RouterError::NoRouteFound {
+                reason: NoRouteReason::InsufficientLiquidity,
+            });
         }
 
-        // Try different split strategies for 2 pools
-        let best_split = if matching_pools.len() == 2 {
-            Self::optimize_two_pool_split(pools, &matching_pools, token_in, token_out, amount_in)?
-        } else {
-            // For 3+ pools, use a greedy approach
-            Self::optimize_multi_pool_split(pools, &matching_pools, token_in, token_out, amount_in)?
+        Ok(quote)
+    }
+
+    /// Largest amount any one pool may receive under `options.max_pool_fraction_bps`
+    ///
+    /// If the cap cannot cover `amount_in` across `pool_count` pools, it is raised
+    /// to an even split, the closest feasible allocation.
+    fn max_amount_per_pool(amount_in: u64, pool_count: usize, options: &RouterOptions) -> u64 {
+        let Some(fraction_bps) = options.max_pool_fraction_bps else {
+            return amount_in;
         };
 
-        // Build route from best split
-        Self::build_split_route(&best_split, pools, &matching_pools, token_in, token_out, amount_in)
+        let cap = (amount_in as u128 * fraction_bps.min(10000) as u128 / 10000) as u64;
+        cap.max(amount_in.div_ceil(pool_count as u64))
     }
 
-    /// Optimize split between exactly 2 pools
+    /// Optimize split between exactly 2 pools, giving neither more than `max_per_pool`
     fn optimize_two_pool_split(
         pools: &[Box<dyn Pool>],
         matching_pools: &[(usize, bool)],
-        _token_in: &Pubkey,
-        _token_out: &Pubkey,
         amount_in: u64,
+        max_per_pool: u64,
     ) -> Result<Vec<SplitAllocation>> {
         let (idx1, a_to_b1) = matching_pools[0];
         let (idx2, a_to_b2) = matching_pools[1];
 
-        let mut best_total_output = 0u64;
-        let mut best_split = Vec::new();
-
-        // Try different split percentages: 0%, 10%, 20%, ..., 100%
-        for percentage1 in (0..=100).step_by(10) {
-            let percentage2 = 100 - percentage1;
-
-            let amount1 = (amount_in as u128 * percentage1 / 100) as u64;
+        // Output of each pool for a given allocation to pool 1 (None if either side fails)
+        let evaluate = |amount1: u64| -> Option<(u64, u64)> {
             let amount2 = amount_in - amount1;
 
-            // Calculate outputs for each pool
             let output1 = if amount1 > 0 {
-                match pools[idx1].calculate_output(amount1, a_to_b1) {
-                    Ok((out, _)) => out,
-                    Err(_) => continue,
-                }
+                pools[idx1].calculate_output(amount1, a_to_b1).ok()?.0
             } else {
                 0
             };
 
             let output2 = if amount2 > 0 {
-                match pools[idx2].calculate_output(amount2, a_to_b2) {
-                    Ok((out, _)) => out,
-                    Err(_) => continue,
-                }
+                pools[idx2].calculate_output(amount2, a_to_b2).ok()?.0
             } else {
                 0
             };
 
-            let total_output = output1 + output2;
-
-            if total_output > best_total_output {
-                best_total_output = total_output;
-                best_split = vec![
-                    SplitAllocation {
-                        pool_index: idx1,
-                        percentage: percentage1 as u8,
-                        amount_in: amount1,
-                        amount_out: output1,
-                    },
-                    SplitAllocation {
-                        pool_index: idx2,
-                        percentage: percentage2 as u8,
-                        amount_in: amount2,
-                        amount_out: output2,
-                    },
-                ];
+            Some((output1, output2))
+        };
+
+        let total = |amount1: u64| -> u64 {
+            evaluate(amount1)
+                .map(|(output1, output2)| output1 + output2)
+                .unwrap_or(0)
+        };
+
+        // Combined output is concave in the allocation for constant-product pools,
+        // so ternary search over raw token units converges on the optimum
+        let min_amount1 = amount_in - max_per_pool;
+        let max_amount1 = max_per_pool;
+        let mut lo = min_amount1;
+        let mut hi = max_amount1;
+
+        while hi - lo > 2 {
+            let third = (hi - lo) / 3;
+            let m1 = lo + third;
+            let m2 = hi - third;
+
+            if total(m1) < total(m2) {
+                lo = m1 + 1;
+            } else {
+                hi = m2 - 1;
             }
         }
 
-        if best_split.is_empty() {
-            return Err(RouterError::NoRouteFound);
+        // Check the remaining window plus both extreme allocations
+        let mut best: Option<(u64, u64, u64)> = None;
+
+        for amount1 in (lo..=hi).chain([min_amount1, max_amount1]) {
+            if let Some((output1, output2)) = evaluate(amount1) {
+                let is_better = match best {
+                    None => true,
+                    Some((_, best1, best2)) => output1 + output2 > best1 + best2,
+                };
+                if is_better {
+                    best = Some((amount1, output1, output2));
+                }
+            }
         }
 
-        Ok(best_split)
+        let (amount1, output1, output2) = best.ok_or(RouterError::NoRouteFound {
+            reason: NoRouteReason::InsufficientLiquidity,
+        })?;
+        let amount2 = amount_in - amount1;
+
+        let percentage1 = if amount_in > 0 {
+            (amount1 as u128 * 100 / amount_in as u128) as u8
+        } else {
+            0
+        };
+
+        Ok(vec![
+            SplitAllocation {
+                pool_index: idx1,
+                percentage: percentage1,
+                amount_in: amount1,
+                amount_out: output1,
+            },
+            SplitAllocation {
+                pool_index: idx2,
+                percentage: 100 - percentage1,
+                amount_in: amount2,
+                amount_out: output2,
+            },
+        ])
     }
 
-    /// Optimize split across 3+ pools (greedy approach)
+    /// Optimize split across 3+ pools (marginal-output greedy)
+    ///
+    /// Allocates the input in 1% chunks, giving each chunk to the pool whose output
+    /// grows the most per unit given what it has already been allocated. For concave
+    /// pools this converges on the water-filling optimum. Pools that reach
+    /// `max_per_pool` only take what fits, and the rest goes to the other pools.
+    ///
+    /// A pool whose allocation ends up contributing no output (e.g. a nearly
+    /// drained pool handed the rounding remainder) is dropped and the input is
+    /// reallocated across the rest, unless they cannot absorb it under the cap.
     fn optimize_multi_pool_split(
         pools: &[Box<dyn Pool>],
         matching_pools: &[(usize, bool)],
-        _token_in: &Pubkey,
-        _token_out: &Pubkey,
         amount_in: u64,
+        max_per_pool: u64,
     ) -> Result<Vec<SplitAllocation>> {
-        // Simple greedy approach: split equally and adjust
-        let pool_count = matching_pools.len();
-        let base_amount = amount_in / pool_count as u64;
+        let allocations = Self::greedy_split(pools, matching_pools, amount_in, max_per_pool)?;
 
-        let mut allocations = Vec::new();
+        let contributing: Vec<(usize, bool)> = matching_pools
+            .iter()
+            .filter(|(idx, _)| {
+                allocations
+                    .iter()
+                    .all(|alloc| alloc.pool_index != *idx || alloc.amount_out > 0)
+            })
+            .copied()
+            .collect();
+        if contributing.len() == matching_pools.len() || contributing.is_empty() {
+            return Ok(allocations);
+        }
 
-        for (pool_idx, (idx, a_to_b)) in matching_pools.iter().enumerate() {
-            let amount = if pool_idx == pool_count - 1 {
-                // Last pool gets remainder
-                amount_in - (base_amount * (pool_count - 1) as u64)
-            } else {
-                base_amount
-            };
+        Ok(Self::greedy_split(pools, &contributing, amount_in, max_per_pool)
+            .unwrap_or(allocations))
+    }
 
-            if let Ok((output, _)) = pools[*idx].calculate_output(amount, *a_to_b) {
-                allocations.push(SplitAllocation {
-                    pool_index: *idx,
-                    percentage: (amount * 100 / amount_in) as u8,
-                    amount_in: amount,
-                    amount_out: output,
-                });
+    /// Marginal-output greedy allocation used by `optimize_multi_pool_split`
+    fn greedy_split(
+        pools: &[Box<dyn Pool>],
+        matching_pools: &[(usize, bool)],
+        amount_in: u64,
+        max_per_pool: u64,
+    ) -> Result<Vec<SplitAllocation>> {
+        const CHUNKS: u64 = 100;
+
+        let chunk = (amount_in / CHUNKS).max(1);
+        let mut allocated = vec![0u64; matching_pools.len()];
+        let mut outputs = vec![0u64; matching_pools.len()];
+        let mut remaining = amount_in;
+
+        while remaining > 0 {
+            // The final chunk absorbs any rounding remainder
+            let next = if remaining < 2 * chunk { remaining } else { chunk };
+
+            // (slot, amount taken, output after taking it, marginal output)
+            let mut best: Option<(usize, u64, u64, u64)> = None;
+            for (slot, (idx, a_to_b)) in matching_pools.iter().enumerate() {
+                let take = next.min(max_per_pool - allocated[slot]);
+                if take == 0 {
+                    continue;
+                }
+                let Ok((output, _)) = pools[*idx].calculate_output(allocated[slot] + take, *a_to_b)
+                else {
+                    continue;
+                };
+
+                // Compare marginal output per unit of input, as capped pools take less
+                let marginal = output.saturating_sub(outputs[slot]);
+                let is_better = match best {
+                    None => true,
+                    Some((_, best_take, _, best_marginal)) => {
+                        marginal as u128 * best_take as u128 > best_marginal as u128 * take as u128
+                    }
+                };
+                if is_better {
+                    best = Some((slot, take, output, marginal));
+                }
             }
-        }
 
-        if allocations.is_empty() {
-            return Err(RouterError::NoRouteFound);
+            let (slot, take, output, _) = best.ok_or(RouterError::NoRouteFound {
+                reason: NoRouteReason::InsufficientLiquidity,
+            })?;
+            allocated[slot] += take;
+            outputs[slot] = output;
+            remaining -= take;
         }
 
+        let allocations = matching_pools
+            .iter()
+            .enumerate()
+            .filter(|(slot, _)| allocated[*slot] > 0)
+            .map(|(slot, (idx, _))| SplitAllocation {
+                pool_index: *idx,
+                percentage: (allocated[slot] as u128 * 100 / amount_in as u128) as u8,
+                amount_in: allocated[slot],
+                amount_out: outputs[slot],
+            })
+            .collect();
+
         Ok(allocations)
     }
 
@@ -206,12 +420,13 @@ impl SplitRouter {
                 amount_out: output,
                 price_impact_bps: price_impact,
                 fee_bps: pool.fee_bps(),
+                fee_amount: calculate_fee_amount(alloc.amount_in, pool.fee_bps()),
             });
 
             total_output += output;
         }
 
-        let route = Route::multi_step(steps);
+        let route = Route::split_steps(steps);
         Ok(SwapQuote::new(
             *token_in,
             *token_out,
@@ -222,6 +437,23 @@ impl SplitRouter {
         ))
     }
 
+    /// Quote for the matching pool giving the most output on its own
+    fn best_single_pool_quote(
+        pools: &[Box<dyn Pool>],
+        matching_pools: &[(usize, bool)],
+        token_in: &Pubkey,
+        token_out: &Pubkey,
+        amount_in: u64,
+    ) -> Option<SwapQuote> {
+        matching_pools
+            .iter()
+            .filter_map(|&(idx, a_to_b)| {
+                let pool = &pools[idx];
+                Self::create_single_pool_quote(pool, token_in, token_out, amount_in, a_to_b).ok()
+            })
+            .max_by_key(|quote| quote.amount_out)
+    }
+
     /// Helper to create single pool quote
     fn create_single_pool_quote(
         pool: &Box<dyn Pool>,
@@ -241,6 +473,7 @@ impl SplitRouter {
             amount_out,
             price_impact_bps: price_impact,
             fee_bps: pool.fee_bps(),
+            fee_amount: calculate_fee_amount(amount_in, pool.fee_bps()),
         };
 
         let route = Route::single_step(step, amount_in, amount_out);
@@ -290,6 +523,46 @@ mod tests {
         assert!(!quote.route.steps.is_empty());
     }
 
+    #[test]
+    fn test_split_max_price_impact() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+            )),
+            Box::new(OrcaPool::new_constant_product(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                2_000_000_000,
+                100_000_000_000,
+            )),
+        ];
+
+        let quote = SplitRouter::find_best_route(&pools, &token_a, &token_b, 10_000_000).unwrap();
+
+        let options = RouterOptions::default().with_max_price_impact(quote.price_impact_bps);
+        let allowed =
+            SplitRouter::find_best_route_with_options(&pools, &token_a, &token_b, 10_000_000, &options);
+        assert_eq!(allowed.unwrap().amount_out, quote.amount_out);
+
+        let options = RouterOptions::default().with_max_price_impact(quote.price_impact_bps - 1);
+        let result =
+            SplitRouter::find_best_route_with_options(&pools, &token_a, &token_b, 10_000_000, &options);
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::InsufficientLiquidity
+            }
+        ));
+    }
+
     #[test]
     fn test_split_single_pool_fallback() {
         let token_a = Pubkey::new_unique();
@@ -310,26 +583,53 @@ mod tests {
     }
 
     #[test]
-    fn test_split_three_pools() {
+    fn test_split_identical_tokens_rejected() {
         let token_a = Pubkey::new_unique();
-        let token_b = Pubkey::new_unique();
-
         let pools: Vec<Box<dyn Pool>> = vec![
             Box::new(RaydiumPool::new(
                 Pubkey::new_unique(),
                 token_a,
-                token_b,
+                Pubkey::new_unique(),
                 1_000_000_000,
                 50_000_000_000,
             )),
-            Box::new(OrcaPool::new_whirlpool(
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                Pubkey::new_unique(),
+                1_000_000_000,
+                50_000_000_000,
+            )),
+        ];
+
+        let result = SplitRouter::find_best_route(&pools, &token_a, &token_a, 1_000_000);
+        assert!(matches!(result, Err(RouterError::IdenticalTokens)));
+    }
+
+    #[test]
+    fn test_split_three_pools() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
                 Pubkey::new_unique(),
                 token_a,
                 token_b,
-                2_000_000_000,
-                100_000_000_000,
-                10,
+                1_000_000_000,
+                50_000_000_000,
             )),
+            Box::new(
+                OrcaPool::new_whirlpool(
+                    Pubkey::new_unique(),
+                    token_a,
+                    token_b,
+                    2_000_000_000,
+                    100_000_000_000,
+                    10,
+                )
+                .unwrap(),
+            ),
             Box::new(MeteoraPool::new(
                 Pubkey::new_unique(),
                 token_a,
@@ -347,6 +647,135 @@ mod tests {
         assert!(quote.amount_out > 0);
     }
 
+    #[test]
+    fn test_split_max_split_pools() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        let pools: Vec<Box<dyn Pool>> = (1..=5u64)
+            .map(|depth| -> Box<dyn Pool> {
+                Box::new(RaydiumPool::new(
+                    Pubkey::new_unique(),
+                    token_a,
+                    token_b,
+                    depth * 1_000_000_000,
+                    depth * 50_000_000_000,
+                ))
+            })
+            .collect();
+        let amount_in = 1_000_000_000;
+
+        let uncapped_options = RouterOptions {
+            max_split_pools: None,
+            ..RouterOptions::default()
+        };
+        let uncapped = SplitRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, amount_in, &uncapped_options,
+        )
+        .unwrap();
+        assert!(uncapped.route.steps.len() > DEFAULT_MAX_SPLIT_POOLS);
+
+        // The default cap applies without any options
+        let default =
+            SplitRouter::find_best_route(&pools, &token_a, &token_b, amount_in).unwrap();
+        assert!(default.route.steps.len() <= DEFAULT_MAX_SPLIT_POOLS);
+
+        let options = RouterOptions::default().with_max_split_pools(2);
+        let capped = SplitRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, amount_in, &options,
+        )
+        .unwrap();
+
+        assert!(capped.route.steps.len() <= 2);
+        // Only the two deepest pools are split across
+        for step in &capped.route.steps {
+            let pool = pools.iter().find(|p| *p.address() == step.pool_address).unwrap();
+            assert!(pool.reserve_a() >= 4_000_000_000);
+        }
+    }
+
+    #[test]
+    fn test_split_drops_drained_pools() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        let deep = Pubkey::new_unique();
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                deep,
+                token_a,
+                token_b,
+                10_000_000_000,
+                500_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(Pubkey::new_unique(), token_a, token_b, 1_000, 1)),
+            Box::new(RaydiumPool::new(Pubkey::new_unique(), token_a, token_b, 1_000, 2)),
+        ];
+        let amount_in = 1_000_000_000;
+
+        let quote = SplitRouter::find_best_route(&pools, &token_a, &token_b, amount_in).unwrap();
+
+        assert_eq!(quote.route.steps.len(), 1);
+        assert_eq!(quote.route.steps[0].pool_address, deep);
+        assert_eq!(quote.route.steps[0].amount_in, amount_in);
+
+        let (deep_alone, _) = pools[0].calculate_output(amount_in, true).unwrap();
+        assert!(quote.amount_out >= deep_alone);
+    }
+
+    #[test]
+    fn test_marginal_split_beats_equal_split() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        // Same price, very different depths
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                100_000_000_000,
+                5_000_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000_000,
+                50_000_000_000_000,
+            )),
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                5_000_000_000_000,
+                250_000_000_000_000,
+            )),
+        ];
+
+        let amount_in = 600_000_000_000; // 600 SOL
+
+        let equal_split: u64 = pools
+            .iter()
+            .map(|pool| pool.calculate_output(amount_in / 3, true).unwrap().0)
+            .sum();
+
+        let quote = SplitRouter::find_best_route(&pools, &token_a, &token_b, amount_in).unwrap();
+
+        assert!(quote.amount_out > equal_split);
+        let allocated: u64 = quote.route.steps.iter().map(|s| s.amount_in).sum();
+        assert_eq!(allocated, amount_in);
+
+        // The deepest pool takes the largest share
+        let deepest = quote
+            .route
+            .steps
+            .iter()
+            .max_by_key(|s| s.amount_in)
+            .unwrap();
+        assert_eq!(deepest.pool_address, *pools[2].address());
+    }
+
     #[test]
     fn test_split_vs_single_pool() {
         let token_a = Pubkey::new_unique();
@@ -377,4 +806,181 @@ mod tests {
         // For large swaps, split routing should be beneficial
         assert!(split_quote.amount_out > 0);
     }
+
+    #[test]
+    fn test_split_no_route_reasons() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        let pools: Vec<Box<dyn Pool>> = vec![Box::new(RaydiumPool::new(
+            Pubkey::new_unique(),
+            token_a,
+            token_b,
+            1_000_000_000,
+            50_000_000_000,
+        ))];
+
+        let result = SplitRouter::find_best_route(&pools, &token_a, &Pubkey::new_unique(), 1_000);
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::NoMatchingPool
+            }
+        ));
+
+        let result = SplitRouter::find_best_route(&pools, &token_a, &token_b, 0);
+        assert!(matches!(
+            result.unwrap_err(),
+            RouterError::NoRouteFound {
+                reason: NoRouteReason::ZeroAmount
+            }
+        ));
+    }
+
+    #[test]
+    fn test_ternary_split_beats_grid() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000_000,
+                50_000_000_000_000,
+            )),
+            Box::new(OrcaPool::new_constant_product(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                3_000_000_000_000,
+                150_000_000_000_000,
+            )),
+        ];
+
+        let amount_in = 50_000_000_000; // 50 SOL
+
+        // Best result from the old 10%-step grid
+        let grid_best = (0..=100u64)
+            .step_by(10)
+            .map(|percentage| {
+                let amount1 = amount_in * percentage / 100;
+                let amount2 = amount_in - amount1;
+                let output1 = if amount1 > 0 {
+                    pools[0].calculate_output(amount1, true).unwrap().0
+                } else {
+                    0
+                };
+                let output2 = if amount2 > 0 {
+                    pools[1].calculate_output(amount2, true).unwrap().0
+                } else {
+                    0
+                };
+                output1 + output2
+            })
+            .max()
+            .unwrap();
+
+        let quote = SplitRouter::find_best_route(&pools, &token_a, &token_b, amount_in).unwrap();
+
+        assert!(quote.amount_out >= grid_best);
+        let allocated: u64 = quote.route.steps.iter().map(|s| s.amount_in).sum();
+        assert_eq!(allocated, amount_in);
+    }
+
+    fn deep_and_shallow_pools(token_a: Pubkey, token_b: Pubkey) -> Vec<Box<dyn Pool>> {
+        vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000_000,
+                50_000_000_000_000,
+            )),
+            Box::new(OrcaPool::new_constant_product(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                4_000_000_000_000,
+                200_000_000_000_000,
+            )),
+        ]
+    }
+
+    #[test]
+    fn test_split_max_pool_fraction() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pools = deep_and_shallow_pools(token_a, token_b);
+        let amount_in = 100_000_000_000;
+
+        // Unconstrained, the deeper pool takes roughly 80% of the input
+        let unconstrained =
+            SplitRouter::find_best_route(&pools, &token_a, &token_b, amount_in).unwrap();
+        assert!(unconstrained.route.steps.iter().any(|s| s.amount_in > amount_in * 6 / 10));
+
+        let options = RouterOptions::default().with_max_pool_fraction(6000);
+        let capped = SplitRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, amount_in, &options,
+        )
+        .unwrap();
+
+        assert_eq!(capped.route.steps.len(), 2);
+        for step in &capped.route.steps {
+            assert!(step.amount_in <= amount_in * 6 / 10);
+        }
+        let allocated: u64 = capped.route.steps.iter().map(|s| s.amount_in).sum();
+        assert_eq!(allocated, amount_in);
+        assert!(capped.amount_out <= unconstrained.amount_out);
+    }
+
+    #[test]
+    fn test_split_max_pool_fraction_multi_pool() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let mut pools = deep_and_shallow_pools(token_a, token_b);
+        pools.push(Box::new(MeteoraPool::new(
+            Pubkey::new_unique(),
+            token_a,
+            token_b,
+            500_000_000_000,
+            25_000_000_000_000,
+            20,
+        )));
+        let amount_in = 100_000_000_000;
+
+        let options = RouterOptions::default().with_max_pool_fraction(5000);
+        let quote = SplitRouter::find_best_route_with_options(
+            &pools, &token_a, &token_b, amount_in, &options,
+        )
+        .unwrap();
+
+        for step in &quote.route.steps {
+            assert!(step.amount_in <= amount_in / 2);
+        }
+        let allocated: u64 = quote.route.steps.iter().map(|s| s.amount_in).sum();
+        assert_eq!(allocated, amount_in);
+    }
+
+    #[test]
+    fn test_split_max_pool_fraction_single_pool_fallback() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let mut pools = deep_and_shallow_pools(token_a, token_b);
+        pools.truncate(1);
+
+        let options = RouterOptions::default().with_max_pool_fraction(6000);
+        let quote = SplitRouter::find_best_route_with_options(
+            &pools,
+            &token_a,
+            &token_b,
+            10_000_000,
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(quote.route.steps.len(), 1);
+        assert_eq!(quote.route.steps[0].amount_in, 10_000_000);
+    }
 }
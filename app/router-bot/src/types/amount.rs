@@ -0,0 +1,149 @@
+//! Conversion between raw token units and human-readable decimal amounts
+
+use crate::error::{Result, RouterError};
+
+/// Format a raw token amount as a decimal string with thousands separators
+///
+/// Trailing zeros in the fractional part are dropped, so `1_500_000` with
+/// 6 decimals formats as `"1.5"` and `2_000_000_000` with 6 decimals as `"2,000"`.
+pub fn format_token_amount(raw: u64, decimals: u8) -> String {
+    // Any u64 is below 10^39, so a scale too large for u128 leaves it all fraction
+    let (whole, fraction) = match 10u128.checked_pow(decimals as u32) {
+        Some(scale) => (raw as u128 / scale, raw as u128 % scale),
+        None => (0, raw as u128),
+    };
+
+    let whole_digits = whole.to_string();
+    let mut formatted = String::with_capacity(whole_digits.len() + whole_digits.len() / 3);
+    for (idx, digit) in whole_digits.chars().enumerate() {
+        if idx > 0 && (whole_digits.len() - idx).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+
+    if fraction > 0 {
+        let fraction_digits = format!("{:0width$}", fraction, width = decimals as usize);
+        formatted.push('.');
+        formatted.push_str(fraction_digits.trim_end_matches('0'));
+    }
+
+    formatted
+}
+
+/// Parse a decimal token amount (e.g. `"1.5"`) into raw token units
+///
+/// Thousands separators are accepted. Digits beyond `decimals` places are
+/// truncated (rounded down), so the result never exceeds the requested amount.
+pub fn parse_token_amount(s: &str, decimals: u8) -> Result<u64> {
+    let cleaned: String = s.trim().chars().filter(|c| *c != ',').collect();
+
+    let (whole, fraction) = match cleaned.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (cleaned.as_str(), ""),
+    };
+
+    let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+        return Err(RouterError::ConfigError(format!("Invalid token amount: {}", s)));
+    }
+
+    let scale = 10u128
+        .checked_pow(decimals as u32)
+        .ok_or(RouterError::MathOverflow)?;
+
+    let whole_units = if whole.is_empty() {
+        0
+    } else {
+        whole.parse::<u128>().map_err(|_| RouterError::MathOverflow)?
+    };
+
+    // Pad or truncate the fractional digits to exactly `decimals` places
+    let mut fraction_digits: String = fraction.chars().take(decimals as usize).collect();
+    while fraction_digits.len() < decimals as usize {
+        fraction_digits.push('0');
+    }
+    let fraction_units = if fraction_digits.is_empty() {
+        0
+    } else {
+        fraction_digits.parse::<u128>().map_err(|_| RouterError::MathOverflow)?
+    };
+
+    let raw = whole_units
+        .checked_mul(scale)
+        .and_then(|units| units.checked_add(fraction_units))
+        .ok_or(RouterError::MathOverflow)?;
+
+    u64::try_from(raw).map_err(|_| RouterError::MathOverflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_token_amount() {
+        assert_eq!(format_token_amount(1_500_000_000, 9), "1.5");
+        assert_eq!(format_token_amount(1_234_567_890_123, 6), "1,234,567.890123");
+        assert_eq!(format_token_amount(2_000_000_000, 6), "2,000");
+        assert_eq!(format_token_amount(1, 9), "0.000000001");
+        assert_eq!(format_token_amount(0, 6), "0");
+        assert_eq!(format_token_amount(123_456, 0), "123,456");
+    }
+
+    #[test]
+    fn test_format_beyond_u128_scale() {
+        assert_eq!(format_token_amount(1, 40), format!("0.{}1", "0".repeat(39)));
+        assert_eq!(format_token_amount(0, 255), "0");
+
+        let formatted = format_token_amount(u64::MAX, 255);
+        assert!(formatted.starts_with("0.000"));
+        assert!(formatted.ends_with(&u64::MAX.to_string()));
+        assert_eq!(formatted.len(), "0.".len() + 255);
+    }
+
+    #[test]
+    fn test_parse_token_amount() {
+        assert_eq!(parse_token_amount("1.5", 9).unwrap(), 1_500_000_000);
+        assert_eq!(parse_token_amount("1,000", 6).unwrap(), 1_000_000_000);
+        assert_eq!(parse_token_amount("0.000001", 6).unwrap(), 1);
+        assert_eq!(parse_token_amount(".5", 2).unwrap(), 50);
+        assert_eq!(parse_token_amount("42", 0).unwrap(), 42);
+    }
+
+    #[test]
+    fn test_parse_trailing_zeros_and_rounding() {
+        assert_eq!(parse_token_amount("1.500000", 6).unwrap(), 1_500_000);
+        assert_eq!(parse_token_amount("1.50", 6).unwrap(), 1_500_000);
+
+        // Extra precision is truncated rather than rounded up
+        assert_eq!(parse_token_amount("1.9999999", 6).unwrap(), 1_999_999);
+        assert_eq!(parse_token_amount("0.0000009", 6).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_parse_invalid_and_overflow() {
+        assert!(matches!(parse_token_amount("abc", 6), Err(RouterError::ConfigError(_))));
+        assert!(matches!(parse_token_amount("1.2.3", 6), Err(RouterError::ConfigError(_))));
+        assert!(matches!(parse_token_amount("-1", 6), Err(RouterError::ConfigError(_))));
+        assert!(matches!(parse_token_amount("", 6), Err(RouterError::ConfigError(_))));
+
+        assert_eq!(parse_token_amount("18446744073709551615", 0).unwrap(), u64::MAX);
+        assert!(matches!(
+            parse_token_amount("18446744073709551616", 0),
+            Err(RouterError::MathOverflow)
+        ));
+        assert!(matches!(
+            parse_token_amount("18446744073.709551616", 9),
+            Err(RouterError::MathOverflow)
+        ));
+    }
+
+    #[test]
+    fn test_format_parse_round_trip() {
+        for (raw, decimals) in [(1_500_000_000u64, 9u8), (123_456_789, 6), (u64::MAX, 9)] {
+            let formatted = format_token_amount(raw, decimals);
+            assert_eq!(parse_token_amount(&formatted, decimals).unwrap(), raw);
+        }
+    }
+}
@@ -1,5 +1,11 @@
+pub mod amount;
 pub mod pool;
+pub mod pubkey;
 pub mod route;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_support;
 
-pub use pool::Pool;
-pub use route::{Route, RouteStep, SwapQuote};
+pub use amount::{format_token_amount, parse_token_amount};
+pub use pool::{Pool, SwapPreview};
+pub use pubkey::parse_pubkey;
+pub use route::{Route, RouteKind, RouteStep, SwapQuote};
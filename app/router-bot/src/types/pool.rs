@@ -1,7 +1,15 @@
 //! Pool trait and common pool types
 
-use crate::error::Result;
+use crate::error::{Result, RouterError};
 use solana_sdk::pubkey::Pubkey;
+use std::any::Any;
+use std::time::Instant;
+
+/// Fee at or above which a pool's health score gets no credit for its fee (1%)
+const HEALTH_FEE_CEILING_BPS: f64 = 100.0;
+
+/// Price impact at or above which a pool's health score gets no credit for it (10%)
+const HEALTH_IMPACT_CEILING_BPS: f64 = 1000.0;
 
 /// Represents a liquidity pool on any DEX
 pub trait Pool: Send + Sync {
@@ -35,6 +43,237 @@ pub trait Pool: Send + Sync {
 
     /// Check if pool has sufficient liquidity for the swap
     fn has_sufficient_liquidity(&self, input_amount: u64, a_to_b: bool) -> bool;
+
+    /// Replace the pool's reserves in place (e.g. after an account update)
+    fn update_reserves(&mut self, reserve_a: u64, reserve_b: u64) -> Result<()>;
+
+    /// When the pool's reserves were last set, if known
+    fn last_updated(&self) -> Option<Instant>;
+
+    /// Clone the pool behind a trait object
+    fn clone_box(&self) -> Box<dyn Pool>;
+
+    /// The concrete pool as `Any`, for downcasting to a DEX-specific type
+    /// (e.g. `pool.as_any().downcast_ref::<PhoenixPool>()`)
+    fn as_any(&self) -> &dyn Any;
+
+    /// ID of the on-chain program that executes swaps against this pool, or
+    /// `None` for pools modelled for routing only, which cannot be executed
+    fn program_id(&self) -> Option<Pubkey>;
+
+    /// Both reserves as `(reserve_a, reserve_b)`
+    fn reserves(&self) -> (u64, u64) {
+        (self.reserve_a(), self.reserve_b())
+    }
+
+    /// The constant-product invariant `reserve_a * reserve_b`
+    ///
+    /// Only meaningful for pools priced off their reserves. Orderbook pools
+    /// return 0: their reserves are resting order depth, which no swap
+    /// preserves a product of.
+    fn constant_product_k(&self) -> u128 {
+        self.reserve_a() as u128 * self.reserve_b() as u128
+    }
+
+    /// The pool's two mints in sorted order, so a pair keys the same way no
+    /// matter which mint the pool lists first
+    fn pair_key(&self) -> (Pubkey, Pubkey) {
+        normalize_pair(self.token_a(), self.token_b())
+    }
+
+    /// Whether `mint` is one of the pool's two tokens
+    fn contains_token(&self, mint: &Pubkey) -> bool {
+        self.token_a() == mint || self.token_b() == mint
+    }
+
+    /// Milliseconds since the pool's reserves were last set, if known
+    fn age_ms(&self) -> Option<u64> {
+        self.last_updated()
+            .map(|updated| updated.elapsed().as_millis().min(u64::MAX as u128) as u64)
+    }
+
+    /// Current spot price in raw output units per raw input unit, without simulating a swap
+    ///
+    /// Defaults to the reserve ratio, which is the spot price of a constant-product pool.
+    fn mid_price(&self, a_to_b: bool) -> f64 {
+        let (reserve_in, reserve_out) = if a_to_b {
+            (self.reserve_a(), self.reserve_b())
+        } else {
+            (self.reserve_b(), self.reserve_a())
+        };
+
+        if reserve_in == 0 {
+            return 0.0;
+        }
+
+        reserve_out as f64 / reserve_in as f64
+    }
+
+    /// Total value of both reserves, given USD prices per raw unit of each token
+    ///
+    /// A quick depth signal for ranking pools; prices must already account for
+    /// token decimals.
+    fn liquidity_usd_equivalent(&self, price_a_usd: f64, price_b_usd: f64) -> f64 {
+        self.reserve_a() as f64 * price_a_usd + self.reserve_b() as f64 * price_b_usd
+    }
+
+    /// Largest input whose price impact stays at or below `target_impact_bps`
+    ///
+    /// Binary searches `calculate_output` between zero and the input-side reserve,
+    /// assuming price impact grows with trade size.
+    fn depth_at_impact(&self, target_impact_bps: u16, a_to_b: bool) -> Result<u64> {
+        let reserve_in = if a_to_b { self.reserve_a() } else { self.reserve_b() };
+        if reserve_in == 0 {
+            return Err(RouterError::InvalidReserves);
+        }
+
+        let within_target = |amount: u64| {
+            self.calculate_output(amount, a_to_b)
+                .is_ok_and(|(_, impact)| impact <= target_impact_bps)
+        };
+
+        if within_target(reserve_in) {
+            return Ok(reserve_in);
+        }
+
+        // Invariant: `lo` is within the target (or zero), `hi` is not
+        let mut lo = 0u64;
+        let mut hi = reserve_in;
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if within_target(mid) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        Ok(lo)
+    }
+
+    /// Smallest input whose `calculate_output` reaches `output_amount`
+    ///
+    /// Binary searches `calculate_output` over the whole `u64` range, so it
+    /// inverts any pool's curve, assuming output grows with input until inputs
+    /// get too large to quote. Fails with `RouterError::InsufficientLiquidity`
+    /// if no input reaches the target.
+    fn required_input(&self, output_amount: u64, a_to_b: bool) -> Result<u64> {
+        if output_amount == 0 {
+            return Ok(0);
+        }
+
+        // Past the answer, an input either reaches the target or cannot be quoted
+        let overshoots = |amount: u64| {
+            self.calculate_output(amount, a_to_b)
+                .map_or(true, |(amount_out, _)| amount_out >= output_amount)
+        };
+
+        if !overshoots(u64::MAX) {
+            return Err(RouterError::InsufficientLiquidity);
+        }
+
+        // Invariant: `lo` falls short of the target (or is zero), `hi` overshoots
+        let mut lo = 0u64;
+        let mut hi = u64::MAX;
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if overshoots(mid) {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+
+        // `hi` may be an input the pool refuses to quote rather than one that fills
+        let (amount_out, _) = self.calculate_output(hi, a_to_b)?;
+        if amount_out < output_amount {
+            return Err(RouterError::InsufficientLiquidity);
+        }
+        Ok(hi)
+    }
+
+    /// Score in `[0, 1]` for ranking pools before routing; higher is better
+    ///
+    /// Averages three signals for a swap of `reference_amount`: depth
+    /// (`reserve_in / (reserve_in + reference_amount)`), fee (linear down to
+    /// zero at `HEALTH_FEE_CEILING_BPS`), and price impact (linear down to zero
+    /// at `HEALTH_IMPACT_CEILING_BPS`).
+    fn health_score(&self, reference_amount: u64, a_to_b: bool) -> Result<f64> {
+        let reserve_in = if a_to_b { self.reserve_a() } else { self.reserve_b() };
+        if reserve_in == 0 {
+            return Err(RouterError::InvalidReserves);
+        }
+
+        let (_, impact_bps) = self.calculate_output(reference_amount, a_to_b)?;
+
+        let depth = reserve_in as f64 / (reserve_in as f64 + reference_amount as f64);
+        let fee = 1.0 - (self.fee_bps() as f64 / HEALTH_FEE_CEILING_BPS).min(1.0);
+        let impact = 1.0 - (impact_bps as f64 / HEALTH_IMPACT_CEILING_BPS).min(1.0);
+
+        Ok((depth + fee + impact) / 3.0)
+    }
+
+    /// Gross and net output, fee, price impact, and post-swap reserves in one call
+    ///
+    /// The fee is reported in output-token units: `gross_out` is `net_out`
+    /// grossed up by `fee_bps`, and `fee_paid` is the difference. The whole
+    /// input is assumed to stay in the pool, fee included.
+    fn swap_preview(&self, input_amount: u64, a_to_b: bool) -> Result<SwapPreview> {
+        let (net_out, price_impact_bps) = self.calculate_output(input_amount, a_to_b)?;
+        let (reserve_in, reserve_out) = if a_to_b {
+            (self.reserve_a(), self.reserve_b())
+        } else {
+            (self.reserve_b(), self.reserve_a())
+        };
+
+        let fee_bps = self.fee_bps().min(10000) as u128;
+        let gross_out = if fee_bps == 10000 {
+            net_out
+        } else {
+            u64::try_from(net_out as u128 * 10000 / (10000 - fee_bps))
+                .map_err(|_| RouterError::MathOverflow)?
+        };
+
+        Ok(SwapPreview {
+            gross_out,
+            fee_paid: gross_out - net_out,
+            net_out,
+            price_impact_bps,
+            new_reserve_in: reserve_in
+                .checked_add(input_amount)
+                .ok_or(RouterError::MathOverflow)?,
+            new_reserve_out: reserve_out
+                .checked_sub(net_out)
+                .ok_or(RouterError::InsufficientLiquidity)?,
+        })
+    }
+}
+
+/// Breakdown of a single swap through one pool, from `Pool::swap_preview`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapPreview {
+    /// Output before the fee, in output-token units
+    pub gross_out: u64,
+    /// Fee charged, in output-token units
+    pub fee_paid: u64,
+    /// Output actually received (`gross_out - fee_paid`)
+    pub net_out: u64,
+    pub price_impact_bps: u16,
+    /// Input-side reserve after the swap
+    pub new_reserve_in: u64,
+    /// Output-side reserve after the swap
+    pub new_reserve_out: u64,
+}
+
+/// Order two mints so `(a, b)` and `(b, a)` give the same key
+pub fn normalize_pair(a: &Pubkey, b: &Pubkey) -> (Pubkey, Pubkey) {
+    if a <= b { (*a, *b) } else { (*b, *a) }
+}
+
+impl Clone for Box<dyn Pool> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
 }
 
 /// Common pool information shared across DEXes
@@ -47,6 +286,8 @@ pub struct PoolInfo {
     pub reserve_a: u64,
     pub reserve_b: u64,
     pub fee_bps: u16,
+    /// When the reserves were last set (construction or `set_reserves`)
+    pub last_updated: Option<Instant>,
 }
 
 impl PoolInfo {
@@ -67,9 +308,21 @@ impl PoolInfo {
             reserve_a,
             reserve_b,
             fee_bps,
+            last_updated: Some(Instant::now()),
         }
     }
 
+    /// Replace both reserves, rejecting empty pools
+    pub fn set_reserves(&mut self, reserve_a: u64, reserve_b: u64) -> Result<()> {
+        if reserve_a == 0 || reserve_b == 0 {
+            return Err(RouterError::InvalidReserves);
+        }
+        self.reserve_a = reserve_a;
+        self.reserve_b = reserve_b;
+        self.last_updated = Some(Instant::now());
+        Ok(())
+    }
+
     /// Get reserves for a given direction
     pub fn get_reserves(&self, a_to_b: bool) -> (u64, u64) {
         if a_to_b {
@@ -83,6 +336,7 @@ impl PoolInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dex::{MeteoraPool, OrcaPool, PhoenixPool, RaydiumPool};
 
     #[test]
     fn test_pool_info_creation() {
@@ -127,4 +381,293 @@ mod tests {
         assert_eq!(reserve_in, 50_000_000);
         assert_eq!(reserve_out, 1_000_000);
     }
+
+    #[test]
+    fn test_set_reserves() {
+        let mut pool = PoolInfo::new(
+            Pubkey::new_unique(),
+            "TestDex".to_string(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000,
+            50_000_000,
+            25,
+        );
+
+        pool.set_reserves(2_000_000, 40_000_000).unwrap();
+        assert_eq!(pool.get_reserves(true), (2_000_000, 40_000_000));
+
+        assert!(pool.set_reserves(0, 40_000_000).is_err());
+        assert_eq!(pool.reserve_a, 2_000_000);
+    }
+
+    #[test]
+    fn test_mid_price_from_reserves() {
+        // 1000 SOL / 50000 USDC, both expressed with 9 decimals
+        let pool: Box<dyn Pool> = Box::new(RaydiumPool::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000_000,
+            50_000_000_000_000,
+        ));
+
+        assert!((pool.mid_price(true) - 50.0).abs() < 1e-9);
+        assert!((pool.mid_price(false) - 0.02).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_pair_key_ignores_token_order() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let forward = RaydiumPool::new(Pubkey::new_unique(), token_a, token_b, 1_000, 50_000);
+        let reverse = OrcaPool::new_constant_product(
+            Pubkey::new_unique(),
+            token_b,
+            token_a,
+            50_000,
+            1_000,
+        );
+
+        assert_eq!(forward.pair_key(), reverse.pair_key());
+        assert_eq!(forward.pair_key(), normalize_pair(&token_b, &token_a));
+        assert!(forward.pair_key().0 <= forward.pair_key().1);
+    }
+
+    #[test]
+    fn test_contains_token() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pool = RaydiumPool::new(Pubkey::new_unique(), token_a, token_b, 1_000, 50_000);
+
+        assert!(pool.contains_token(&token_a));
+        assert!(pool.contains_token(&token_b));
+        assert!(!pool.contains_token(&Pubkey::new_unique()));
+    }
+
+    #[test]
+    fn test_clone_pool_vec() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+            )),
+            Box::new(OrcaPool::new_constant_product(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                2_000_000_000,
+                100_000_000_000,
+            )),
+            Box::new(MeteoraPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_500_000_000,
+                75_000_000_000,
+                20,
+            )),
+            Box::new(PhoenixPool::new(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+                50_000_000,
+                50_000_000,
+            )),
+        ];
+
+        let mut scenario = pools.clone();
+        for pool in scenario.iter_mut() {
+            pool.update_reserves(1, 1).unwrap();
+        }
+
+        for (original, copy) in pools.iter().zip(&scenario) {
+            assert_eq!(original.address(), copy.address());
+            assert_eq!(original.dex_name(), copy.dex_name());
+            assert_eq!(copy.reserve_a(), 1);
+            assert!(original.reserve_a() > 1);
+            assert!(original.reserve_b() > 1);
+        }
+    }
+
+    #[test]
+    fn test_liquidity_usd_equivalent() {
+        let pool = RaydiumPool::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,  // 1 SOL (9 decimals)
+            50_000_000_000, // 50,000 USDC (6 decimals)
+        );
+
+        // $150 per SOL and $1 per USDC, expressed per raw unit
+        let value = pool.liquidity_usd_equivalent(150.0 / 1e9, 1.0 / 1e6);
+        assert!((value - 50_150.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_depth_at_impact() {
+        let shallow = RaydiumPool::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+        );
+        let deep = RaydiumPool::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            10_000_000_000,
+            500_000_000_000,
+        );
+
+        let shallow_depth = shallow.depth_at_impact(100, true).unwrap();
+        let deep_depth = deep.depth_at_impact(100, true).unwrap();
+
+        assert!(shallow_depth > 0);
+        assert!(deep_depth > shallow_depth);
+
+        // The returned depth is the boundary: one more unit crosses the target
+        let (_, impact) = shallow.calculate_output(shallow_depth, true).unwrap();
+        assert!(impact <= 100);
+        let (_, impact) = shallow.calculate_output(shallow_depth + 1, true).unwrap();
+        assert!(impact > 100);
+    }
+
+    #[test]
+    fn test_required_input_inverts_each_curve() {
+        let pools: Vec<Box<dyn Pool>> = vec![
+            Box::new(RaydiumPool::new(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                1_000_000_000,
+                50_000_000_000,
+            )),
+            Box::new(
+                OrcaPool::new_whirlpool_with_liquidity(
+                    Pubkey::new_unique(),
+                    Pubkey::new_unique(),
+                    Pubkey::new_unique(),
+                    1_000_000_000,
+                    50_000_000_000,
+                    10,
+                    (50f64.sqrt() * (1u128 << 64) as f64) as u128,
+                    70_000_000_000,
+                )
+                .unwrap(),
+            ),
+            Box::new(MeteoraPool::new_stable(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                50_000_000_000,
+                50_000_000_000,
+                4,
+                100,
+            )),
+        ];
+
+        for pool in &pools {
+            let target = 1_000_000_000;
+            let input = pool.required_input(target, true).unwrap();
+
+            // The smallest input that fills the target
+            let (output, _) = pool.calculate_output(input, true).unwrap();
+            assert!(output >= target);
+            let (output, _) = pool.calculate_output(input - 1, true).unwrap();
+            assert!(output < target);
+        }
+
+        // Nothing fills more than the pool holds
+        assert!(matches!(
+            pools[0].required_input(60_000_000_000, true),
+            Err(RouterError::InsufficientLiquidity)
+        ));
+    }
+
+    #[test]
+    fn test_health_score_prefers_deep_cheap_pools() {
+        let deep = RaydiumPool::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            100_000_000_000,
+            5_000_000_000_000,
+        );
+        let thin = RaydiumPool::new_with_fee(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+            100,
+        )
+        .unwrap();
+
+        let deep_score = deep.health_score(100_000_000, true).unwrap();
+        let thin_score = thin.health_score(100_000_000, true).unwrap();
+
+        assert!(deep_score > thin_score);
+        assert!((0.0..=1.0).contains(&deep_score));
+        assert!((0.0..=1.0).contains(&thin_score));
+    }
+
+    #[test]
+    fn test_age_ms_increases_and_resets_on_update() {
+        let mut pool = RaydiumPool::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+        );
+
+        let initial_age = pool.age_ms().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let aged = pool.age_ms().unwrap();
+        assert!(aged >= initial_age + 20);
+
+        pool.update_reserves(2_000_000_000, 100_000_000_000).unwrap();
+        assert!(pool.age_ms().unwrap() < aged);
+    }
+
+    #[test]
+    fn test_swap_preview_raydium() {
+        let pool = RaydiumPool::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000_000,
+            50_000_000_000,
+        );
+
+        for (input, a_to_b) in [(1_000_000, true), (50_000_000, true), (2_000_000_000, false)] {
+            let preview = pool.swap_preview(input, a_to_b).unwrap();
+            let (net_out, impact) = pool.calculate_output(input, a_to_b).unwrap();
+            let (reserve_in, reserve_out) = if a_to_b {
+                (pool.reserve_a(), pool.reserve_b())
+            } else {
+                (pool.reserve_b(), pool.reserve_a())
+            };
+
+            assert_eq!(preview.net_out, net_out);
+            assert_eq!(preview.price_impact_bps, impact);
+            assert_eq!(preview.gross_out - preview.fee_paid, preview.net_out);
+            // 0.25% of the gross output, give or take rounding
+            let expected_fee = preview.gross_out * pool.fee_bps() as u64 / 10000;
+            assert!(preview.fee_paid.abs_diff(expected_fee) <= 1);
+            assert_eq!(preview.new_reserve_in - reserve_in, input);
+            assert_eq!(reserve_out - preview.new_reserve_out, net_out);
+        }
+    }
 }
@@ -0,0 +1,55 @@
+//! Parsing of user-supplied pubkeys
+
+use crate::error::{Result, RouterError};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// Parse a base58 pubkey supplied for `field`, naming the field and the
+/// offending input in the error
+pub fn parse_pubkey(s: &str, field: &str) -> Result<Pubkey> {
+    Pubkey::from_str(s.trim()).map_err(|_| {
+        RouterError::ConfigError(format!(
+            "invalid {}: '{}' is not a valid base58 pubkey",
+            field, s
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pubkey_too_short() {
+        let err = parse_pubkey("So1111", "token_in").unwrap_err();
+        assert!(matches!(
+            err,
+            RouterError::ConfigError(msg)
+                if msg == "invalid token_in: 'So1111' is not a valid base58 pubkey"
+        ));
+    }
+
+    #[test]
+    fn test_parse_pubkey_bad_character() {
+        // '0' is not in the base58 alphabet
+        let input = "So11111111111111111111111111111111111111110";
+        let err = parse_pubkey(input, "token_out").unwrap_err();
+        assert!(matches!(
+            err,
+            RouterError::ConfigError(msg)
+                if msg == format!("invalid token_out: '{}' is not a valid base58 pubkey", input)
+        ));
+    }
+
+    #[test]
+    fn test_parse_pubkey_valid() {
+        let key = Pubkey::new_unique();
+        assert_eq!(parse_pubkey(&key.to_string(), "token_in").unwrap(), key);
+        assert_eq!(
+            parse_pubkey("So11111111111111111111111111111111111111112", "token_in")
+                .unwrap()
+                .to_string(),
+            "So11111111111111111111111111111111111111112"
+        );
+    }
+}
@@ -1,17 +1,34 @@
 //! Route and swap quote types
 
+use crate::error::{Result, RouterError};
+use crate::types::amount::format_token_amount;
+use serde::{Serialize, Serializer};
 use solana_sdk::pubkey::Pubkey;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Serialize a pubkey as its base58 string
+fn serialize_pubkey<S: Serializer>(
+    pubkey: &Pubkey,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&pubkey.to_string())
+}
 
 /// Represents a single step in a swap route
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RouteStep {
     /// The pool address to use for this step
+    #[serde(serialize_with = "serialize_pubkey")]
     pub pool_address: Pubkey,
     /// DEX name
     pub dex: String,
     /// Input token for this step
+    #[serde(serialize_with = "serialize_pubkey")]
     pub token_in: Pubkey,
     /// Output token for this step
+    #[serde(serialize_with = "serialize_pubkey")]
     pub token_out: Pubkey,
     /// Amount to swap in this step
     pub amount_in: u64,
@@ -21,11 +38,53 @@ pub struct RouteStep {
     pub price_impact_bps: u16,
     /// Fee in basis points
     pub fee_bps: u16,
+    /// Fee taken by this step, in this step's input token units
+    pub fee_amount: u64,
+}
+
+impl RouteStep {
+    /// Minimum acceptable output for this step after applying a slippage tolerance
+    pub fn minimum_out(&self, slippage_bps: u16) -> u64 {
+        let tolerance = 10000u128.saturating_sub(slippage_bps as u128);
+        (self.amount_out as u128 * tolerance / 10000) as u64
+    }
+}
+
+/// How a route's steps combine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteKind {
+    /// One step through one pool
+    Direct,
+    /// Parallel steps over the same pair, each spending part of the input
+    Split,
+    /// Sequential steps, each spending the previous step's output
+    MultiHop,
+}
+
+/// Compute units a swap transaction uses outside its swap instructions
+/// (compute budget, token account setup and checks)
+pub const BASE_COMPUTE_UNITS: u32 = 20_000;
+
+/// Estimated compute units for a swap on a DEX this module has no figure for
+pub const DEFAULT_STEP_COMPUTE_UNITS: u32 = 100_000;
+
+/// Estimated compute units for one swap instruction on `dex`
+pub fn step_compute_units(dex: &str) -> u32 {
+    match dex {
+        "Raydium" => 60_000,
+        "Lifinity" | "Meteora" | "Weighted" => 70_000,
+        "Orca" => 80_000,
+        "Phoenix" | "OpenBook" => 100_000,
+        _ => DEFAULT_STEP_COMPUTE_UNITS,
+    }
 }
 
 /// Represents a complete swap route (can be multi-hop)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Route {
+    /// How the steps combine
+    pub kind: RouteKind,
     /// All steps in the route
     pub steps: Vec<RouteStep>,
     /// Total input amount
@@ -41,6 +100,7 @@ impl Route {
     pub fn single_step(step: RouteStep, input: u64, output: u64) -> Self {
         let price_impact = step.price_impact_bps;
         Self {
+            kind: RouteKind::Direct,
             steps: vec![step],
             total_input: input,
             total_output: output,
@@ -61,6 +121,34 @@ impl Route {
             .min(10000) as u16;
 
         Self {
+            kind: RouteKind::MultiHop,
+            steps,
+            total_input,
+            total_output,
+            total_price_impact_bps,
+        }
+    }
+
+    /// Create a split route whose steps each swap part of the input over the same pair
+    ///
+    /// Totals are summed across the legs, and the price impact is the legs'
+    /// impact weighted by their share of the input.
+    pub fn split_steps(steps: Vec<RouteStep>) -> Self {
+        let total_input: u64 = steps.iter().map(|s| s.amount_in).sum();
+        let total_output: u64 = steps.iter().map(|s| s.amount_out).sum();
+
+        let weighted_impact: u128 = steps
+            .iter()
+            .map(|s| s.amount_in as u128 * s.price_impact_bps as u128)
+            .sum();
+        let total_price_impact_bps = if total_input == 0 {
+            0
+        } else {
+            (weighted_impact / total_input as u128).min(10000) as u16
+        };
+
+        Self {
+            kind: RouteKind::Split,
             steps,
             total_input,
             total_output,
@@ -69,13 +157,208 @@ impl Route {
     }
 
     /// Get the number of hops in the route
+    ///
+    /// Split legs over the same pair count as one hop, so a split route is a
+    /// single hop however many pools it uses.
     pub fn hop_count(&self) -> usize {
-        self.steps.len()
+        match self.kind {
+            RouteKind::Direct | RouteKind::Split => 1,
+            RouteKind::MultiHop => self.hops().count(),
+        }
     }
 
-    /// Check if this is a direct swap (single hop)
+    /// Check if this is a direct swap (single hop, split or not)
     pub fn is_direct(&self) -> bool {
-        self.steps.len() == 1
+        self.hop_count() == 1
+    }
+
+    /// Whether the route has no steps
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Token the route spends (the first step's input), or `None` for an empty route
+    pub fn input_token(&self) -> Option<Pubkey> {
+        self.steps.first().map(|step| step.token_in)
+    }
+
+    /// Token the route produces (the last step's output), or `None` for an empty route
+    pub fn output_token(&self) -> Option<Pubkey> {
+        self.steps.last().map(|step| step.token_out)
+    }
+
+    /// Whether every parallel leg of a split route produces the same token
+    ///
+    /// Only split routes have parallel legs, so other kinds (and empty routes)
+    /// are always consistent.
+    pub fn output_token_consistent(&self) -> bool {
+        match self.kind {
+            RouteKind::Split => self
+                .steps
+                .windows(2)
+                .all(|pair| pair[0].token_out == pair[1].token_out),
+            RouteKind::Direct | RouteKind::MultiHop => true,
+        }
+    }
+
+    /// Steps grouped into hops; consecutive steps over the same pair are split legs
+    fn hops(&self) -> impl DoubleEndedIterator<Item = &[RouteStep]> {
+        self.steps
+            .chunk_by(|a, b| a.token_in == b.token_in && a.token_out == b.token_out)
+    }
+
+    /// Total fees paid in the route's input token
+    ///
+    /// Fees on later hops are charged in intermediate tokens and cannot be summed
+    /// with the input token, so only steps that spend the route's input token (the
+    /// first hop, or every leg of a split) are included. See [`Route::fees_per_step`]
+    /// for the full breakdown.
+    pub fn total_fees_in_input_token(&self) -> u64 {
+        let Some(input_token) = self.input_token() else {
+            return 0;
+        };
+
+        self.steps
+            .iter()
+            .filter(|s| s.token_in == input_token)
+            .map(|s| s.fee_amount)
+            .sum()
+    }
+
+    /// Fee paid by each step as `(token the fee is charged in, fee amount)`
+    pub fn fees_per_step(&self) -> Vec<(Pubkey, u64)> {
+        self.steps.iter().map(|s| (s.token_in, s.fee_amount)).collect()
+    }
+
+    /// One-line summary such as `"10 So11..1112 -[Orca 0.3%]-> 498.7 EPjF..Dt1v"`
+    ///
+    /// Tokens are shown as abbreviated mints. Amounts are rendered with the
+    /// decimals in `decimals`, falling back to raw units for unknown mints.
+    /// Consecutive steps over the same pair (split legs) share one arrow, e.g.
+    /// `-[Raydium 0.25% | Orca 0.3%]->`.
+    pub fn to_compact_string(&self, decimals: &HashMap<Pubkey, u8>) -> String {
+        let (Some(first), Some(last)) = (self.steps.first(), self.steps.last()) else {
+            return String::new();
+        };
+        let format_amount = |raw: u64, token: &Pubkey| match decimals.get(token) {
+            Some(&token_decimals) => format_token_amount(raw, token_decimals),
+            None => raw.to_string(),
+        };
+
+        let mut compact = format!(
+            "{} {}",
+            format_amount(self.total_input, &first.token_in),
+            abbreviate_pubkey(&first.token_in)
+        );
+
+        let hops: Vec<&[RouteStep]> = self.hops().collect();
+        for (idx, hop) in hops.iter().enumerate() {
+            let venues: Vec<String> = hop
+                .iter()
+                .map(|s| format!("{} {}%", s.dex, s.fee_bps as f64 / 100.0))
+                .collect();
+            compact.push_str(&format!(" -[{}]-> ", venues.join(" | ")));
+
+            if idx + 1 == hops.len() {
+                compact.push_str(&format!(
+                    "{} {}",
+                    format_amount(self.total_output, &last.token_out),
+                    abbreviate_pubkey(&last.token_out)
+                ));
+            } else {
+                compact.push_str(&abbreviate_pubkey(&hop[0].token_out));
+            }
+        }
+
+        compact
+    }
+
+    /// Minimum output when every hop may slip by `per_hop_slippage_bps`
+    ///
+    /// Each hop receives the previous hop's *slipped* output, so slippage
+    /// compounds: three hops at 1% each leave `0.99³ ≈ 97.03%` of the expected
+    /// output, not the 99% that applying 1% once to the final amount (as
+    /// `SwapQuote::minimum_out` does) would suggest. Enforcing the flat figure on
+    /// a 3-hop route rejects swaps that are within every hop's tolerance.
+    ///
+    /// Starting from `total_input`, each hop's expected rate (split legs over
+    /// the same pair count as one hop) is applied and then discounted.
+    pub fn cumulative_min_out(&self, per_hop_slippage_bps: u16) -> u64 {
+        let tolerance = 10000u128.saturating_sub(per_hop_slippage_bps as u128);
+        let mut amount = self.total_input as u128;
+
+        for hop in self.hops() {
+            let hop_in: u128 = hop.iter().map(|s| s.amount_in as u128).sum();
+            let hop_out: u128 = hop.iter().map(|s| s.amount_out as u128).sum();
+            if hop_in == 0 {
+                return 0;
+            }
+            amount = amount * hop_out / hop_in * tolerance / 10000;
+        }
+
+        amount.min(u64::MAX as u128) as u64
+    }
+
+    /// Minimum output guard for each step, in step order
+    ///
+    /// Unlike `cumulative_min_out`, each step is guarded independently against
+    /// its own expected output, so a sandwich on any single hop trips that
+    /// hop's check instead of only the final one.
+    pub fn per_step_minimums(&self, slippage_bps: u16) -> Vec<u64> {
+        self.steps.iter().map(|s| s.minimum_out(slippage_bps)).collect()
+    }
+
+    /// Final output implied by the steps themselves, ignoring `total_output`
+    ///
+    /// Every leg of a split route contributes; a multi-hop route yields the
+    /// output of its last hop (summed over that hop's legs if it is split).
+    pub fn recompute_output(&self) -> u64 {
+        match self.kind {
+            RouteKind::Direct | RouteKind::Split => self.steps.iter().map(|s| s.amount_out).sum(),
+            RouteKind::MultiHop => self
+                .hops()
+                .next_back()
+                .map(|hop| hop.iter().map(|s| s.amount_out).sum())
+                .unwrap_or(0),
+        }
+    }
+
+    /// Stable identity of the route's path
+    ///
+    /// FNV-1a hash over the sorted set of `(pool, token_in, token_out)` tuples
+    /// of its steps. Amounts and step order are ignored, so two quotes through
+    /// the same pools in the same direction share a fingerprint however their
+    /// legs are listed; unlike `DefaultHasher`, the value is the same across runs
+    /// and builds.
+    pub fn fingerprint(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let pool_directions: BTreeSet<(Pubkey, Pubkey, Pubkey)> = self
+            .steps
+            .iter()
+            .map(|s| (s.pool_address, s.token_in, s.token_out))
+            .collect();
+
+        pool_directions
+            .into_iter()
+            .flat_map(|(pool, token_in, token_out)| [pool, token_in, token_out])
+            .flat_map(|key| key.to_bytes())
+            .fold(FNV_OFFSET_BASIS, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+            })
+    }
+
+    /// Estimated compute units to execute the route
+    ///
+    /// `BASE_COMPUTE_UNITS` plus each step's `step_compute_units`; split legs
+    /// are separate instructions, so they count like hops.
+    pub fn estimated_compute_units(&self) -> u32 {
+        self.steps
+            .iter()
+            .fold(BASE_COMPUTE_UNITS, |total, step| {
+                total.saturating_add(step_compute_units(&step.dex))
+            })
     }
 
     /// Calculate the effective price (output/input ratio)
@@ -88,12 +371,23 @@ impl Route {
     }
 }
 
+/// Shorten a pubkey to its first and last four characters
+fn abbreviate_pubkey(pubkey: &Pubkey) -> String {
+    let full = pubkey.to_string();
+    format!("{}..{}", &full[..4], &full[full.len() - 4..])
+}
+
+/// Price impact above which a quote is assumed to come from a misconfigured pool (50%)
+pub const MAX_VALID_PRICE_IMPACT_BPS: u16 = 5000;
+
 /// Represents a swap quote with routing information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SwapQuote {
     /// Input token mint
+    #[serde(serialize_with = "serialize_pubkey")]
     pub token_in: Pubkey,
     /// Output token mint
+    #[serde(serialize_with = "serialize_pubkey")]
     pub token_out: Pubkey,
     /// Input amount
     pub amount_in: u64,
@@ -105,9 +399,20 @@ pub struct SwapQuote {
     pub route: Route,
     /// Strategy used (e.g., "single_pool", "split", "multi_hop")
     pub strategy: String,
+    /// When the quote was made (or the reserve snapshot it was made against was
+    /// captured), in unix milliseconds
+    pub quoted_at: u64,
+}
+
+/// Current wall-clock time in unix milliseconds
+pub fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis().min(u64::MAX as u128) as u64)
 }
 
 impl SwapQuote {
+    /// Create a quote stamped with the current time
     pub fn new(
         token_in: Pubkey,
         token_out: Pubkey,
@@ -124,18 +429,201 @@ impl SwapQuote {
             price_impact_bps: route.total_price_impact_bps,
             route,
             strategy,
+            quoted_at: unix_millis(),
+        }
+    }
+
+    /// Stamp the quote with `quoted_at` (unix milliseconds), e.g. the capture
+    /// time of the reserve snapshot a backtest routed against
+    pub fn with_quoted_at(mut self, quoted_at: u64) -> Self {
+        self.quoted_at = quoted_at;
+        self
+    }
+
+    /// Whether more than `max_age_ms` has passed since the quote was made
+    pub fn is_stale(&self, max_age_ms: u64) -> bool {
+        self.is_stale_at(max_age_ms, unix_millis())
+    }
+
+    /// Whether the quote is older than `max_age_ms` at `now_ms` (unix milliseconds)
+    pub fn is_stale_at(&self, max_age_ms: u64, now_ms: u64) -> bool {
+        now_ms.saturating_sub(self.quoted_at) > max_age_ms
+    }
+
+    /// Sanity-check the quote before execution
+    ///
+    /// Rejects quotes with zero output (overall or on any step) and quotes whose
+    /// price impact exceeds `MAX_VALID_PRICE_IMPACT_BPS`.
+    pub fn validate(&self) -> Result<()> {
+        if self.amount_out == 0 {
+            return Err(RouterError::InvalidQuote("quote produces zero output".to_string()));
+        }
+
+        if let Some(step) = self.route.steps.iter().find(|s| s.amount_out == 0) {
+            return Err(RouterError::InvalidQuote(format!(
+                "step through pool {} produces zero output",
+                step.pool_address
+            )));
+        }
+
+        if self.price_impact_bps > MAX_VALID_PRICE_IMPACT_BPS {
+            return Err(RouterError::InvalidQuote(format!(
+                "price impact of {:.2}% exceeds {:.2}%",
+                self.price_impact_bps as f64 / 100.0,
+                MAX_VALID_PRICE_IMPACT_BPS as f64 / 100.0
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// One-line summary of the quote's route (see [`Route::to_compact_string`])
+    pub fn to_compact_string(&self, decimals: &HashMap<Pubkey, u8>) -> String {
+        self.route.to_compact_string(decimals)
+    }
+
+    /// Minimum acceptable output after applying a slippage tolerance
+    pub fn minimum_out(&self, slippage_bps: u16) -> u64 {
+        let tolerance = 10000u128.saturating_sub(slippage_bps as u128);
+        (self.amount_out as u128 * tolerance / 10000) as u64
+    }
+
+    /// Input paid per unit of output (`amount_in / amount_out`), or 0 for a zero output
+    pub fn execution_price(&self) -> f64 {
+        if self.amount_out == 0 {
+            0.0
+        } else {
+            self.amount_in as f64 / self.amount_out as f64
+        }
+    }
+
+    /// Output received per unit of input (`amount_out / amount_in`), or 0 for a zero input
+    pub fn inverse_execution_price(&self) -> f64 {
+        if self.amount_in == 0 {
+            0.0
+        } else {
+            self.amount_out as f64 / self.amount_in as f64
         }
     }
 
-    /// Compare quotes and return the better one (higher output)
+    /// Deviation of `execution_price` from `spot`, in signed basis points
+    ///
+    /// `spot` is quoted like `execution_price`, as input per unit of output. A
+    /// positive result means the quote pays more than spot (the usual effect of
+    /// fees and price impact); a negative one means it beats spot. Returns 0
+    /// when `spot` is not a positive price.
+    pub fn price_vs_spot_bps(&self, spot: f64) -> i32 {
+        if spot <= 0.0 || !spot.is_finite() {
+            return 0;
+        }
+
+        ((self.execution_price() - spot) / spot * 10000.0).round() as i32
+    }
+
+    /// Estimated compute units to execute the quote (see `Route::estimated_compute_units`)
+    pub fn estimated_compute_units(&self) -> u32 {
+        self.route.estimated_compute_units()
+    }
+
+    /// Output left after paying the transaction fee, in output token units
+    ///
+    /// `out_token_price_in_lamports` is the value of one raw unit of the output
+    /// token in lamports; the fee is converted at that price and subtracted from
+    /// `amount_out`. The result is negative when the fee is worth more than the
+    /// swap's output, and a zero price makes it negative infinity.
+    pub fn net_output(&self, tx_fee_lamports: u64, out_token_price_in_lamports: f64) -> f64 {
+        let fee_in_output = tx_fee_lamports as f64 / out_token_price_in_lamports;
+        self.amount_out as f64 - fee_in_output
+    }
+
+    /// Whether the output is still worth more than the transaction fee
+    pub fn is_profitable_after_fees(
+        &self,
+        tx_fee_lamports: u64,
+        out_token_price_in_lamports: f64,
+    ) -> bool {
+        self.net_output(tx_fee_lamports, out_token_price_in_lamports) > 0.0
+    }
+
+    /// Rank two quotes; `Ordering::Greater` means `self` is the better quote
+    ///
+    /// Prefers higher output, then lower price impact, then fewer steps, then the
+    /// lexicographically smaller first pool address so ties resolve deterministically.
+    pub fn compare(&self, other: &SwapQuote) -> Ordering {
+        let first_pool = |quote: &SwapQuote| quote.route.steps.first().map(|s| s.pool_address);
+
+        self.amount_out
+            .cmp(&other.amount_out)
+            .then_with(|| other.price_impact_bps.cmp(&self.price_impact_bps))
+            .then_with(|| other.route.steps.len().cmp(&self.route.steps.len()))
+            .then_with(|| first_pool(other).cmp(&first_pool(self)))
+    }
+
+    /// Compare quotes and return the better one (see `compare` for tiebreaks)
     pub fn better_than(&self, other: &SwapQuote) -> bool {
-        self.amount_out > other.amount_out
+        self.compare(other) == Ordering::Greater
+    }
+
+    /// Output discounted by `hop_penalty_bps` for every hop after the first
+    ///
+    /// Parallel legs of a split count as one hop, so only sequential routes are
+    /// penalized. The discount is capped at 100%.
+    pub fn penalized_output(&self, hop_penalty_bps: u16) -> u64 {
+        let extra_hops = self.route.hop_count().saturating_sub(1) as u128;
+        let penalty_bps = (hop_penalty_bps as u128 * extra_hops).min(10000);
+        (self.amount_out as u128 * (10000 - penalty_bps) / 10000) as u64
+    }
+
+    /// `better_than`, after discounting both outputs with `penalized_output`
+    ///
+    /// A route with more hops has to beat a shorter one by more than the penalty
+    /// per extra hop. Equal discounted outputs fall back to `compare`.
+    pub fn better_than_with_hop_penalty(&self, other: &SwapQuote, hop_penalty_bps: u16) -> bool {
+        self.penalized_output(hop_penalty_bps)
+            .cmp(&other.penalized_output(hop_penalty_bps))
+            .then_with(|| self.compare(other))
+            == Ordering::Greater
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde::Deserialize;
+
+    /// Mirror of `SwapQuote` as seen by JSON consumers
+    #[derive(Debug, Deserialize)]
+    struct QuoteJson {
+        token_in: String,
+        token_out: String,
+        amount_in: u64,
+        amount_out: u64,
+        price_impact_bps: u16,
+        route: RouteJson,
+        strategy: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct RouteJson {
+        kind: String,
+        steps: Vec<RouteStepJson>,
+        total_input: u64,
+        total_output: u64,
+        total_price_impact_bps: u16,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct RouteStepJson {
+        pool_address: String,
+        dex: String,
+        token_in: String,
+        token_out: String,
+        amount_in: u64,
+        amount_out: u64,
+        price_impact_bps: u16,
+        fee_bps: u16,
+        fee_amount: u64,
+    }
 
     fn create_test_step(amount_in: u64, amount_out: u64) -> RouteStep {
         RouteStep {
@@ -147,6 +635,7 @@ mod tests {
             amount_out,
             price_impact_bps: 50,
             fee_bps: 25,
+            fee_amount: amount_in * 25 / 10000,
         }
     }
 
@@ -155,6 +644,7 @@ mod tests {
         let step = create_test_step(1_000_000, 50_000_000);
         let route = Route::single_step(step, 1_000_000, 50_000_000);
 
+        assert_eq!(route.kind, RouteKind::Direct);
         assert_eq!(route.hop_count(), 1);
         assert!(route.is_direct());
         assert_eq!(route.total_input, 1_000_000);
@@ -168,12 +658,215 @@ mod tests {
 
         let route = Route::multi_step(vec![step1, step2]);
 
+        assert_eq!(route.kind, RouteKind::MultiHop);
         assert_eq!(route.hop_count(), 2);
         assert!(!route.is_direct());
         assert_eq!(route.total_input, 1_000_000);
         assert_eq!(route.total_output, 100_000);
     }
 
+    #[test]
+    fn test_split_route_is_one_hop() {
+        let leg1 = create_test_step(600_000, 30_000_000);
+        let leg2 = RouteStep {
+            token_in: leg1.token_in,
+            token_out: leg1.token_out,
+            price_impact_bps: 20,
+            ..create_test_step(400_000, 19_000_000)
+        };
+        let route = Route::split_steps(vec![leg1, leg2]);
+
+        assert_eq!(route.kind, RouteKind::Split);
+        assert_eq!(route.hop_count(), 1);
+        assert!(route.is_direct());
+        assert_eq!(route.total_input, 1_000_000);
+        assert_eq!(route.total_output, 49_000_000);
+        assert_eq!(route.effective_price(), 49.0);
+        // (600_000 * 50 + 400_000 * 20) / 1_000_000
+        assert_eq!(route.total_price_impact_bps, 38);
+    }
+
+    #[test]
+    fn test_compact_string_single_hop() {
+        let mut step = create_test_step(10_000_000_000, 498_700_000);
+        step.dex = "Orca".to_string();
+        step.fee_bps = 30;
+        let (sol, usdc) = (step.token_in, step.token_out);
+        let route = Route::single_step(step, 10_000_000_000, 498_700_000);
+
+        let decimals = HashMap::from([(sol, 9), (usdc, 6)]);
+        assert_eq!(
+            route.to_compact_string(&decimals),
+            format!(
+                "10 {} -[Orca 0.3%]-> 498.7 {}",
+                abbreviate_pubkey(&sol),
+                abbreviate_pubkey(&usdc)
+            )
+        );
+
+        // Unknown decimals fall back to raw units
+        assert_eq!(
+            route.to_compact_string(&HashMap::new()),
+            format!(
+                "10000000000 {} -[Orca 0.3%]-> 498700000 {}",
+                abbreviate_pubkey(&sol),
+                abbreviate_pubkey(&usdc)
+            )
+        );
+    }
+
+    #[test]
+    fn test_compact_string_three_hops() {
+        let mut step1 = create_test_step(1_000_000_000, 50_000_000);
+        let mut step2 = create_test_step(50_000_000, 20_000_000);
+        let mut step3 = create_test_step(20_000_000, 7_000_000);
+        step2.token_in = step1.token_out;
+        step3.token_in = step2.token_out;
+        step1.dex = "Raydium".to_string();
+        step2.dex = "Orca".to_string();
+        step2.fee_bps = 30;
+        step3.dex = "Raydium".to_string();
+        let tokens = [step1.token_in, step1.token_out, step2.token_out, step3.token_out];
+        let route = Route::multi_step(vec![step1, step2, step3]);
+        let quote = SwapQuote::new(
+            tokens[0],
+            tokens[3],
+            1_000_000_000,
+            7_000_000,
+            route,
+            "multi_hop".to_string(),
+        );
+
+        let decimals = HashMap::from([(tokens[0], 9), (tokens[3], 6)]);
+        assert_eq!(
+            quote.to_compact_string(&decimals),
+            format!(
+                "1 {} -[Raydium 0.25%]-> {} -[Orca 0.3%]-> {} -[Raydium 0.25%]-> 7 {}",
+                abbreviate_pubkey(&tokens[0]),
+                abbreviate_pubkey(&tokens[1]),
+                abbreviate_pubkey(&tokens[2]),
+                abbreviate_pubkey(&tokens[3])
+            )
+        );
+    }
+
+    #[test]
+    fn test_cumulative_min_out_compounds_per_hop() {
+        let step1 = create_test_step(1_000_000_000, 50_000_000_000);
+        let mut step2 = create_test_step(50_000_000_000, 20_000_000_000);
+        let mut step3 = create_test_step(20_000_000_000, 7_000_000_000);
+        step2.token_in = step1.token_out;
+        step3.token_in = step2.token_out;
+        let route = Route::multi_step(vec![step1, step2, step3]);
+        let quote = SwapQuote::new(
+            route.steps[0].token_in,
+            route.steps[2].token_out,
+            route.total_input,
+            route.total_output,
+            route.clone(),
+            "multi_hop_3".to_string(),
+        );
+
+        let compounded = route.cumulative_min_out(100);
+        let flat = quote.minimum_out(100);
+
+        // 7_000_000_000 * 0.99^3
+        assert_eq!(compounded, 6_792_093_000);
+        assert_eq!(flat, 6_930_000_000);
+        assert!(compounded < flat);
+    }
+
+    #[test]
+    fn test_cumulative_min_out_single_hop_matches_flat() {
+        let step = create_test_step(1_000_000, 50_000_000);
+        let route = Route::single_step(step, 1_000_000, 50_000_000);
+
+        assert_eq!(route.cumulative_min_out(100), 49_500_000);
+        assert_eq!(route.cumulative_min_out(0), 50_000_000);
+    }
+
+    #[test]
+    fn test_per_step_minimums() {
+        let step1 = create_test_step(1_000_000_000, 50_000_000_000);
+        let mut step2 = create_test_step(50_000_000_000, 20_000_000_000);
+        step2.token_in = step1.token_out;
+        let route = Route::multi_step(vec![step1, step2]);
+
+        let minimums = route.per_step_minimums(50);
+        assert_eq!(minimums, vec![49_750_000_000, 19_900_000_000]);
+        for (minimum, step) in minimums.iter().zip(&route.steps) {
+            assert_eq!(*minimum, step.minimum_out(50));
+            assert!(*minimum > 0 && *minimum < step.amount_out);
+        }
+    }
+
+    #[test]
+    fn test_recompute_output() {
+        // Two split legs over the same pair
+        let leg1 = create_test_step(600_000, 30_000_000);
+        let leg2 = RouteStep {
+            token_in: leg1.token_in,
+            token_out: leg1.token_out,
+            ..create_test_step(400_000, 19_000_000)
+        };
+        let split = Route::split_steps(vec![leg1, leg2]);
+        assert_eq!(split.recompute_output(), 49_000_000);
+
+        // Sequential hops only count the final step
+        let step1 = create_test_step(1_000_000, 50_000_000);
+        let mut step2 = create_test_step(50_000_000, 100_000);
+        step2.token_in = step1.token_out;
+        let multi_hop = Route::multi_step(vec![step1, step2]);
+        assert_eq!(multi_hop.recompute_output(), 100_000);
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_amounts() {
+        let pool = Pubkey::new_unique();
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let step = |amount_in, amount_out| RouteStep {
+            pool_address: pool,
+            token_in: token_a,
+            token_out: token_b,
+            ..create_test_step(amount_in, amount_out)
+        };
+
+        let small = Route::single_step(step(1_000, 990), 1_000, 990);
+        let large = Route::single_step(step(5_000_000, 4_900_000), 5_000_000, 4_900_000);
+        assert_eq!(small.fingerprint(), large.fingerprint());
+
+        // Same pool, opposite direction
+        let reverse = Route::single_step(
+            RouteStep {
+                token_in: token_b,
+                token_out: token_a,
+                ..step(1_000, 990)
+            },
+            1_000,
+            990,
+        );
+        assert_ne!(small.fingerprint(), reverse.fingerprint());
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_step_order() {
+        let leg1 = create_test_step(600_000, 30_000_000);
+        let leg2 = RouteStep {
+            token_in: leg1.token_in,
+            token_out: leg1.token_out,
+            ..create_test_step(400_000, 19_000_000)
+        };
+
+        let split = Route::split_steps(vec![leg1.clone(), leg2.clone()]);
+        let reordered = Route::split_steps(vec![leg2.clone(), leg1.clone()]);
+        assert_eq!(split.fingerprint(), reordered.fingerprint());
+
+        // A different set of pools still differs
+        let single = Route::single_step(leg1, 600_000, 30_000_000);
+        assert_ne!(split.fingerprint(), single.fingerprint());
+    }
+
     #[test]
     fn test_effective_price() {
         let step = create_test_step(1_000_000, 50_000_000);
@@ -212,4 +905,325 @@ mod tests {
         assert!(quote2.better_than(&quote1));
         assert!(!quote1.better_than(&quote2));
     }
+
+    fn create_test_quote(steps: Vec<RouteStep>, amount_out: u64) -> SwapQuote {
+        let route = Route::multi_step(steps);
+        SwapQuote::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000,
+            amount_out,
+            route,
+            "single_pool".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_net_output_after_tx_fee() {
+        // USDC at 200 USDC/SOL: one raw unit (1e-6 USDC) is worth 5 lamports,
+        // so a 5000-lamport fee costs 1000 raw units
+        let usdc_price_in_lamports = 5.0;
+        let fee_lamports = 5000;
+
+        let tiny = create_test_quote(vec![create_test_step(100, 500)], 500);
+        assert_eq!(tiny.net_output(fee_lamports, usdc_price_in_lamports), -500.0);
+        assert!(!tiny.is_profitable_after_fees(fee_lamports, usdc_price_in_lamports));
+
+        let large = create_test_quote(vec![create_test_step(1_000_000, 10_000_000)], 10_000_000);
+        assert_eq!(large.net_output(fee_lamports, usdc_price_in_lamports), 9_999_000.0);
+        assert!(large.is_profitable_after_fees(fee_lamports, usdc_price_in_lamports));
+
+        // A worthless output token can never pay for the fee
+        assert!(!large.is_profitable_after_fees(fee_lamports, 0.0));
+    }
+
+    #[test]
+    fn test_better_than_prefers_lower_impact_on_tie() {
+        let mut low_impact_step = create_test_step(1_000_000, 50_000_000);
+        low_impact_step.price_impact_bps = 10;
+        let mut high_impact_step = create_test_step(1_000_000, 50_000_000);
+        high_impact_step.price_impact_bps = 80;
+
+        let low_impact = create_test_quote(vec![low_impact_step], 50_000_000);
+        let high_impact = create_test_quote(vec![high_impact_step], 50_000_000);
+
+        assert!(low_impact.better_than(&high_impact));
+        assert!(!high_impact.better_than(&low_impact));
+    }
+
+    #[test]
+    fn test_better_than_deterministic_tiebreaks() {
+        // Same output and impact: fewer steps wins
+        let mut half = create_test_step(1_000_000, 50_000_000);
+        half.price_impact_bps = 25;
+        let one_step = create_test_quote(vec![create_test_step(1_000_000, 50_000_000)], 50_000_000);
+        let two_steps = create_test_quote(vec![half.clone(), half], 50_000_000);
+        assert!(one_step.better_than(&two_steps));
+
+        // Fully identical apart from pool: smaller first pool address wins
+        let step_a = create_test_step(1_000_000, 50_000_000);
+        let step_b = create_test_step(1_000_000, 50_000_000);
+        let (smaller, larger) = if step_a.pool_address < step_b.pool_address {
+            (step_a, step_b)
+        } else {
+            (step_b, step_a)
+        };
+        let smaller = create_test_quote(vec![smaller], 50_000_000);
+        let larger = create_test_quote(vec![larger], 50_000_000);
+        assert!(smaller.better_than(&larger));
+        assert!(!larger.better_than(&smaller));
+        assert!(!smaller.better_than(&smaller.clone()));
+    }
+
+    #[test]
+    fn test_validate_accepts_normal_quote() {
+        let quote = create_test_quote(vec![create_test_step(1_000_000, 50_000_000)], 50_000_000);
+        assert!(quote.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_zero_output() {
+        let quote = create_test_quote(vec![create_test_step(1_000_000, 0)], 0);
+        assert!(matches!(quote.validate(), Err(RouterError::InvalidQuote(_))));
+
+        // Zero output on an intermediate step is rejected even if the total is not
+        let steps = vec![
+            create_test_step(1_000_000, 0),
+            create_test_step(50_000_000, 100_000),
+        ];
+        let quote = create_test_quote(steps, 100_000);
+        assert!(matches!(quote.validate(), Err(RouterError::InvalidQuote(_))));
+    }
+
+    #[test]
+    fn test_validate_rejects_extreme_price_impact() {
+        let mut step = create_test_step(1_000_000, 50_000_000);
+        step.price_impact_bps = MAX_VALID_PRICE_IMPACT_BPS;
+        let quote = create_test_quote(vec![step.clone()], 50_000_000);
+        assert!(quote.validate().is_ok());
+
+        step.price_impact_bps = MAX_VALID_PRICE_IMPACT_BPS + 1;
+        let quote = create_test_quote(vec![step], 50_000_000);
+        assert!(matches!(quote.validate(), Err(RouterError::InvalidQuote(_))));
+    }
+
+    #[test]
+    fn test_minimum_out() {
+        let step = create_test_step(1_000_000, 50_000_000);
+        let route = Route::single_step(step, 1_000_000, 50_000_000);
+        let quote = SwapQuote::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000,
+            50_000_000,
+            route,
+            "single_pool".to_string(),
+        );
+
+        assert_eq!(quote.minimum_out(0), 50_000_000);
+        assert_eq!(quote.minimum_out(100), 49_500_000);
+        assert_eq!(quote.minimum_out(10000), 0);
+    }
+
+    #[test]
+    fn test_hop_penalty_discounts_longer_routes() {
+        let direct = SwapQuote::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000,
+            50_000_000,
+            Route::single_step(create_test_step(1_000_000, 50_000_000), 1_000_000, 50_000_000),
+            "single_pool".to_string(),
+        );
+        let three_hops = Route::multi_step(vec![
+            create_test_step(1_000_000, 2_000_000),
+            create_test_step(2_000_000, 3_000_000),
+            create_test_step(3_000_000, 50_040_000),
+        ]);
+        let multi_hop = SwapQuote::new(
+            direct.token_in,
+            direct.token_out,
+            1_000_000,
+            50_040_000,
+            three_hops,
+            "multi_hop_3".to_string(),
+        );
+
+        // 8 bps better before any penalty
+        assert_eq!(direct.penalized_output(10), 50_000_000);
+        assert_eq!(multi_hop.penalized_output(0), 50_040_000);
+        assert!(multi_hop.better_than_with_hop_penalty(&direct, 0));
+
+        // 2 extra hops at 3 bps each still leaves it ahead; at 5 bps each it isn't
+        assert_eq!(multi_hop.penalized_output(3), 50_009_976);
+        assert!(multi_hop.better_than_with_hop_penalty(&direct, 3));
+        assert!(!multi_hop.better_than_with_hop_penalty(&direct, 5));
+        assert!(direct.better_than_with_hop_penalty(&multi_hop, 5));
+
+        // The discount never exceeds the whole output
+        assert_eq!(multi_hop.penalized_output(u16::MAX), 0);
+    }
+
+    #[test]
+    fn test_route_token_accessors() {
+        // Empty
+        let empty = Route::multi_step(Vec::new());
+        assert!(empty.is_empty());
+        assert_eq!(empty.input_token(), None);
+        assert_eq!(empty.output_token(), None);
+        assert!(empty.output_token_consistent());
+
+        // Single step
+        let step = create_test_step(1_000_000, 50_000_000);
+        let (token_in, token_out) = (step.token_in, step.token_out);
+        let single = Route::single_step(step, 1_000_000, 50_000_000);
+        assert!(!single.is_empty());
+        assert_eq!(single.input_token(), Some(token_in));
+        assert_eq!(single.output_token(), Some(token_out));
+        assert!(single.output_token_consistent());
+
+        // Split legs over the same pair
+        let leg = |amount_in, amount_out| RouteStep {
+            token_in,
+            token_out,
+            ..create_test_step(amount_in, amount_out)
+        };
+        let split = Route::split_steps(vec![leg(600_000, 30_000_000), leg(400_000, 20_000_000)]);
+        assert_eq!(split.input_token(), Some(token_in));
+        assert_eq!(split.output_token(), Some(token_out));
+        assert!(split.output_token_consistent());
+
+        // A split whose legs produce different tokens
+        let stray = RouteStep {
+            token_in,
+            ..create_test_step(400_000, 20_000_000)
+        };
+        let inconsistent = Route::split_steps(vec![leg(600_000, 30_000_000), stray]);
+        assert!(!inconsistent.output_token_consistent());
+    }
+
+    #[test]
+    fn test_estimated_compute_units_scale_with_steps() {
+        let raydium_step = || RouteStep {
+            dex: "Raydium".to_string(),
+            ..create_test_step(1_000_000, 1_000_000)
+        };
+
+        let single = Route::single_step(raydium_step(), 1_000_000, 1_000_000);
+        let three = Route::multi_step(vec![raydium_step(), raydium_step(), raydium_step()]);
+
+        let per_step = single.estimated_compute_units() - BASE_COMPUTE_UNITS;
+        assert_eq!(per_step, step_compute_units("Raydium"));
+        assert_eq!(three.estimated_compute_units(), BASE_COMPUTE_UNITS + 3 * per_step);
+
+        // DEXes without a figure fall back to the default
+        let unknown = Route::single_step(create_test_step(100, 100), 100, 100);
+        assert_eq!(
+            unknown.estimated_compute_units(),
+            BASE_COMPUTE_UNITS + DEFAULT_STEP_COMPUTE_UNITS
+        );
+    }
+
+    #[test]
+    fn test_execution_price_both_directions() {
+        let quote = create_test_quote(vec![create_test_step(1_000_000, 50_000_000)], 50_000_000);
+
+        assert_eq!(quote.execution_price(), 0.02);
+        assert_eq!(quote.inverse_execution_price(), 50.0);
+
+        let empty = create_test_quote(vec![create_test_step(1_000_000, 0)], 0);
+        assert_eq!(empty.execution_price(), 0.0);
+        assert_eq!(empty.inverse_execution_price(), 0.0);
+    }
+
+    #[test]
+    fn test_price_vs_spot_sign() {
+        // Spot is 1 input per 50 output; price impact leaves only 49_000_000 out
+        let spot = 1.0 / 50.0;
+        let impacted = create_test_quote(vec![create_test_step(1_000_000, 49_000_000)], 49_000_000);
+        // 0.02 / (1 / 49) - 1 = 2.04%
+        assert_eq!(impacted.price_vs_spot_bps(spot), 204);
+
+        let better = create_test_quote(vec![create_test_step(1_000_000, 51_000_000)], 51_000_000);
+        assert!(better.price_vs_spot_bps(spot) < 0);
+
+        assert_eq!(impacted.price_vs_spot_bps(0.0), 0);
+    }
+
+    #[test]
+    fn test_quote_staleness() {
+        let step = create_test_step(1_000_000, 50_000_000);
+        let route = Route::single_step(step, 1_000_000, 50_000_000);
+        let quote = SwapQuote::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            1_000_000,
+            50_000_000,
+            route,
+            "single_pool".to_string(),
+        );
+
+        // Fresh right after creation
+        assert!(quote.quoted_at > 0);
+        assert!(!quote.is_stale(60_000));
+
+        // Advance a simulated clock past the limit
+        let created = quote.quoted_at;
+        assert!(!quote.is_stale_at(1_000, created + 1_000));
+        assert!(quote.is_stale_at(1_000, created + 1_001));
+        // A clock behind the quote never makes it stale
+        assert!(!quote.is_stale_at(0, created - 1));
+
+        // Backdated to a snapshot time
+        let snapshot = quote.with_quoted_at(created - 5_000);
+        assert!(snapshot.is_stale_at(1_000, created));
+        assert!(!snapshot.is_stale_at(10_000, created));
+    }
+
+    #[test]
+    fn test_swap_quote_json_round_trip() {
+        let token_in = Pubkey::new_unique();
+        let token_out = Pubkey::new_unique();
+        let step = create_test_step(1_000_000, 50_000_000);
+        let pool_address = step.pool_address;
+        let route = Route::single_step(step, 1_000_000, 50_000_000);
+        let quote = SwapQuote::new(
+            token_in,
+            token_out,
+            1_000_000,
+            50_000_000,
+            route,
+            "single_pool".to_string(),
+        );
+
+        let json = serde_json::to_string(&quote).unwrap();
+        let parsed: QuoteJson = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.token_in, token_in.to_string());
+        assert_eq!(parsed.token_out, token_out.to_string());
+        assert_eq!(parsed.amount_in, quote.amount_in);
+        assert_eq!(parsed.amount_out, quote.amount_out);
+        assert_eq!(parsed.price_impact_bps, quote.price_impact_bps);
+        assert_eq!(parsed.strategy, "single_pool");
+
+        assert_eq!(parsed.route.kind, "direct");
+        assert_eq!(parsed.route.total_input, quote.route.total_input);
+        assert_eq!(parsed.route.total_output, quote.route.total_output);
+        assert_eq!(
+            parsed.route.total_price_impact_bps,
+            quote.route.total_price_impact_bps
+        );
+
+        let step = &parsed.route.steps[0];
+        let original = &quote.route.steps[0];
+        assert_eq!(step.pool_address, pool_address.to_string());
+        assert_eq!(step.dex, original.dex);
+        assert_eq!(step.token_in, original.token_in.to_string());
+        assert_eq!(step.token_out, original.token_out.to_string());
+        assert_eq!(step.amount_in, original.amount_in);
+        assert_eq!(step.amount_out, original.amount_out);
+        assert_eq!(step.price_impact_bps, original.price_impact_bps);
+        assert_eq!(step.fee_bps, original.fee_bps);
+        assert_eq!(step.fee_amount, original.fee_amount);
+    }
 }
@@ -0,0 +1,236 @@
+//! Test doubles for exercising routers without AMM math
+//!
+//! Compiled for this crate's own tests, and for other crates' tests with the
+//! `test-util` feature enabled.
+
+use crate::error::{Result, RouterError};
+use crate::types::pool::Pool;
+use solana_sdk::pubkey::Pubkey;
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Reserves a `MockPool` reports unless overridden, deep enough that health
+/// ranking treats mock pools as equally deep
+const DEFAULT_MOCK_RESERVE: u64 = 1_000_000_000_000;
+
+/// Quote function behind a `MockPool`: `(input_amount, a_to_b)` to
+/// `(output_amount, price_impact_bps)`
+pub type MockQuoteFn = Arc<dyn Fn(u64, bool) -> Result<(u64, u16)> + Send + Sync>;
+
+/// A `Pool` whose quotes come from a closure instead of reserves
+///
+/// The reserves and fee are only reported, never used to price a swap, so a
+/// test can pin exactly the outputs, impacts, or errors a router sees.
+#[derive(Clone)]
+pub struct MockPool {
+    address: Pubkey,
+    dex: String,
+    token_a: Pubkey,
+    token_b: Pubkey,
+    reserve_a: u64,
+    reserve_b: u64,
+    fee_bps: u16,
+    quote: MockQuoteFn,
+    last_updated: Option<Instant>,
+}
+
+impl MockPool {
+    /// Create a mock pool that quotes every swap with `quote`
+    pub fn new(
+        token_a: Pubkey,
+        token_b: Pubkey,
+        quote: impl Fn(u64, bool) -> Result<(u64, u16)> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            address: Pubkey::new_unique(),
+            dex: "Mock".to_string(),
+            token_a,
+            token_b,
+            reserve_a: DEFAULT_MOCK_RESERVE,
+            reserve_b: DEFAULT_MOCK_RESERVE,
+            fee_bps: 0,
+            quote: Arc::new(quote),
+            last_updated: Some(Instant::now()),
+        }
+    }
+
+    /// Create a mock pool that returns `amount_out` at `impact_bps` for any
+    /// input in either direction
+    pub fn fixed(token_a: Pubkey, token_b: Pubkey, amount_out: u64, impact_bps: u16) -> Self {
+        Self::new(token_a, token_b, move |_, _| Ok((amount_out, impact_bps)))
+    }
+
+    /// Create a mock pool that quotes from a table of `(input, output)` pairs
+    ///
+    /// Inputs missing from the table fail with `InsufficientLiquidity`. Price
+    /// impact is always 0.
+    pub fn from_table(token_a: Pubkey, token_b: Pubkey, table: &[(u64, u64)]) -> Self {
+        let table = table.to_vec();
+        Self::new(token_a, token_b, move |input_amount, _| {
+            table
+                .iter()
+                .find(|(input, _)| *input == input_amount)
+                .map(|&(_, output)| (output, 0))
+                .ok_or(RouterError::InsufficientLiquidity)
+        })
+    }
+
+    /// Create a mock pool whose every quote fails with `InsufficientLiquidity`
+    pub fn failing(token_a: Pubkey, token_b: Pubkey) -> Self {
+        Self::new(token_a, token_b, |_, _| Err(RouterError::InsufficientLiquidity))
+    }
+
+    /// Set the DEX name the pool reports
+    pub fn with_dex(mut self, dex: &str) -> Self {
+        self.dex = dex.to_string();
+        self
+    }
+
+    /// Set the pool address
+    pub fn with_address(mut self, address: Pubkey) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Set the reserves the pool reports
+    pub fn with_reserves(mut self, reserve_a: u64, reserve_b: u64) -> Self {
+        self.reserve_a = reserve_a;
+        self.reserve_b = reserve_b;
+        self
+    }
+
+    /// Set the fee the pool reports
+    pub fn with_fee_bps(mut self, fee_bps: u16) -> Self {
+        self.fee_bps = fee_bps;
+        self
+    }
+}
+
+impl Pool for MockPool {
+    fn address(&self) -> &Pubkey {
+        &self.address
+    }
+
+    fn dex_name(&self) -> &str {
+        &self.dex
+    }
+
+    fn token_a(&self) -> &Pubkey {
+        &self.token_a
+    }
+
+    fn token_b(&self) -> &Pubkey {
+        &self.token_b
+    }
+
+    fn reserve_a(&self) -> u64 {
+        self.reserve_a
+    }
+
+    fn reserve_b(&self) -> u64 {
+        self.reserve_b
+    }
+
+    fn fee_bps(&self) -> u16 {
+        self.fee_bps
+    }
+
+    fn calculate_output(&self, input_amount: u64, a_to_b: bool) -> Result<(u64, u16)> {
+        (self.quote)(input_amount, a_to_b)
+    }
+
+    fn calculate_price_impact(&self, input_amount: u64, a_to_b: bool) -> Result<u16> {
+        self.calculate_output(input_amount, a_to_b)
+            .map(|(_, impact_bps)| impact_bps)
+    }
+
+    fn has_sufficient_liquidity(&self, input_amount: u64, a_to_b: bool) -> bool {
+        self.calculate_output(input_amount, a_to_b)
+            .is_ok_and(|(amount_out, _)| amount_out > 0)
+    }
+
+    fn update_reserves(&mut self, reserve_a: u64, reserve_b: u64) -> Result<()> {
+        self.reserve_a = reserve_a;
+        self.reserve_b = reserve_b;
+        self.last_updated = Some(Instant::now());
+        Ok(())
+    }
+
+    fn last_updated(&self) -> Option<Instant> {
+        self.last_updated
+    }
+
+    fn clone_box(&self) -> Box<dyn Pool> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn program_id(&self) -> Option<Pubkey> {
+        // Mock pools are never executed
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::SinglePoolRouter;
+
+    #[test]
+    fn test_fixed_mock_pool() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let pool = MockPool::fixed(token_a, token_b, 12_345, 7).with_fee_bps(30);
+
+        assert_eq!(pool.calculate_output(1_000, true).unwrap(), (12_345, 7));
+        assert_eq!(pool.calculate_output(u64::MAX, false).unwrap(), (12_345, 7));
+        assert_eq!(pool.calculate_price_impact(1_000, true).unwrap(), 7);
+        assert!(pool.has_sufficient_liquidity(1_000, true));
+
+        let pools: Vec<Box<dyn Pool>> = vec![Box::new(pool)];
+        let quote = SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, 1_000).unwrap();
+        assert_eq!(quote.amount_out, 12_345);
+        assert_eq!(quote.price_impact_bps, 7);
+        assert_eq!(quote.route.steps[0].dex, "Mock");
+        assert_eq!(quote.route.steps[0].fee_bps, 30);
+    }
+
+    #[test]
+    fn test_failing_mock_pool() {
+        let token_a = Pubkey::new_unique();
+        let token_b = Pubkey::new_unique();
+        let failing = MockPool::failing(token_a, token_b);
+
+        assert!(matches!(
+            failing.calculate_output(1_000, true),
+            Err(RouterError::InsufficientLiquidity)
+        ));
+        assert!(!failing.has_sufficient_liquidity(1_000, true));
+
+        // The router skips the failing pool in favour of a working one
+        let working = MockPool::fixed(token_a, token_b, 500, 0).with_dex("Working");
+        let pools: Vec<Box<dyn Pool>> = vec![Box::new(failing.clone()), Box::new(working)];
+        let quote = SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, 1_000).unwrap();
+        assert_eq!(quote.route.steps[0].dex, "Working");
+
+        let pools: Vec<Box<dyn Pool>> = vec![Box::new(failing)];
+        assert!(SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, 1_000).is_err());
+    }
+
+    #[test]
+    fn test_table_mock_pool() {
+        let pool = MockPool::from_table(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            &[(100, 90), (200, 170)],
+        );
+
+        assert_eq!(pool.calculate_output(100, true).unwrap(), (90, 0));
+        assert_eq!(pool.calculate_output(200, false).unwrap(), (170, 0));
+        assert!(pool.calculate_output(150, true).is_err());
+    }
+}
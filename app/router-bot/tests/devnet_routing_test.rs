@@ -37,14 +37,17 @@ fn test_comprehensive_routing_comparison() {
             25_000_000_000_000, // 25M USDC (50 USDC per SOL)
         )),
         // Orca Whirlpool: High liquidity, 0.1% fee
-        Box::new(dex::OrcaPool::new_whirlpool(
-            Pubkey::new_unique(),
-            token_sol,
-            token_usdc,
-            1_000_000_000_000,  // 1000 SOL
-            50_000_000_000_000, // 50M USDC
-            10, // 0.1% fee
-        )),
+        Box::new(
+            dex::OrcaPool::new_whirlpool(
+                Pubkey::new_unique(),
+                token_sol,
+                token_usdc,
+                1_000_000_000_000,  // 1000 SOL
+                50_000_000_000_000, // 50M USDC
+                10, // 0.1% fee
+            )
+            .unwrap(),
+        ),
         // Meteora: Medium liquidity, 0.2% fee
         Box::new(dex::MeteoraPool::new(
             Pubkey::new_unique(),
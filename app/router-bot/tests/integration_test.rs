@@ -34,6 +34,72 @@ fn test_fetch_usdc_mint() {
     assert_eq!(mint.decimals, 6);
 }
 
+#[tokio::test]
+#[ignore] // Requires network access
+async fn test_subscribe_account_receives_update() {
+    let client = SolanaClient::new_devnet();
+
+    // The clock sysvar changes every slot
+    let clock = Pubkey::from_str("SysvarC1ock11111111111111111111111111111111").unwrap();
+    let mut updates = client
+        .subscribe_account(clock)
+        .expect("Failed to start subscription");
+
+    let account = tokio::time::timeout(std::time::Duration::from_secs(30), updates.recv())
+        .await
+        .expect("No account update within 30s")
+        .expect("Subscription closed unexpectedly");
+
+    println!("✅ Received clock update ({} bytes)", account.data.len());
+    assert!(!account.data.is_empty());
+}
+
+#[test]
+#[ignore] // Requires network access
+fn test_discover_raydium_sol_usdc_pools() {
+    let client = SolanaClient::new_mainnet();
+
+    let wsol = Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap();
+    let usdc = Pubkey::from_str("EPjFWdd5AufqSSqeM2qFLdx4qtYGsQN3sgAh2BHZhh1v").unwrap();
+
+    let pools = client
+        .discover_raydium_pools(wsol, usdc)
+        .expect("Failed to discover Raydium pools");
+
+    assert!(!pools.is_empty());
+    for pool in &pools {
+        assert!(
+            (pool.token_a() == &wsol && pool.token_b() == &usdc)
+                || (pool.token_a() == &usdc && pool.token_b() == &wsol)
+        );
+    }
+    println!("✅ Discovered {} Raydium SOL/USDC pools", pools.len());
+}
+
+#[tokio::test]
+#[ignore] // Requires network access
+async fn test_fetch_multiple_mints() {
+    let client = SolanaClient::new_devnet();
+
+    let addresses = vec![
+        // USDC mint on devnet
+        Pubkey::from_str("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU").unwrap(),
+        // Wrapped SOL
+        Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap(),
+        // Random address that doesn't exist
+        Pubkey::new_unique(),
+    ];
+
+    let results = client.fetch_accounts_parallel(&addresses).await;
+
+    println!("✅ Fetched {} accounts in one call", results.len());
+
+    assert_eq!(results.len(), addresses.len());
+    assert!(results[0].is_ok());
+    assert!(results[1].is_ok());
+    assert!(matches!(results[2], Err(RouterError::AccountNotFound(_))));
+}
+
 #[test]
 fn test_single_pool_routing() {
     let token_a = Pubkey::new_unique();
@@ -47,14 +113,17 @@ fn test_single_pool_routing() {
             1_000_000_000,
             50_000_000_000,
         )),
-        Box::new(dex::OrcaPool::new_whirlpool(
-            Pubkey::new_unique(),
-            token_a,
-            token_b,
-            1_000_000_000,
-            50_000_000_000,
-            10, // Lower fee than Raydium
-        )),
+        Box::new(
+            dex::OrcaPool::new_whirlpool(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                1_000_000_000,
+                50_000_000_000,
+                10, // Lower fee than Raydium
+            )
+            .unwrap(),
+        ),
     ];
 
     let quote = router::SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, 1_000_000)
@@ -162,14 +231,17 @@ fn test_strategy_comparison() {
             500_000_000, // Smaller pool
             25_000_000_000,
         )),
-        Box::new(dex::OrcaPool::new_whirlpool(
-            Pubkey::new_unique(),
-            token_a,
-            token_b,
-            2_000_000_000, // Larger pool, lower fee
-            100_000_000_000,
-            10,
-        )),
+        Box::new(
+            dex::OrcaPool::new_whirlpool(
+                Pubkey::new_unique(),
+                token_a,
+                token_b,
+                2_000_000_000, // Larger pool, lower fee
+                100_000_000_000,
+                10,
+            )
+            .unwrap(),
+        ),
     ];
 
     let amount = 50_000_000; // Large swap
@@ -228,21 +300,80 @@ fn test_executor_dry_run() {
     assert_eq!(result.simulated_output, Some(quote.amount_out));
 }
 
+#[test]
+#[ignore] // Requires network access
+fn test_executor_confirm_unknown_signature_devnet() {
+    let client = SolanaClient::new_devnet();
+    let executor = executor::Executor::new(client, false);
+
+    let started = std::time::Instant::now();
+    let confirmed = executor
+        .confirm(
+            &solana_sdk::signature::Signature::default(),
+            std::time::Duration::from_secs(2),
+        )
+        .expect("Confirmation polling failed");
+
+    println!("✅ Unknown signature polled for {:?}", started.elapsed());
+    assert!(!confirmed);
+}
+
+#[test]
+#[ignore] // Requires network access and DEX instruction building
+fn test_executor_simulate_on_chain_devnet() {
+    let client = SolanaClient::new_devnet();
+    let executor = executor::Executor::new(client, true)
+        .with_on_chain_simulation(true)
+        .with_signer(solana_sdk::signature::Keypair::new());
+
+    let token_a = Pubkey::new_unique();
+    let token_b = Pubkey::new_unique();
+
+    let pools: Vec<Box<dyn types::Pool>> = vec![Box::new(dex::RaydiumPool::new(
+        Pubkey::new_unique(),
+        token_a,
+        token_b,
+        1_000_000_000,
+        50_000_000_000,
+    ))];
+
+    let quote = router::SinglePoolRouter::find_best_route(&pools, &token_a, &token_b, 1_000_000)
+        .expect("Failed to find route");
+
+    let result = executor.execute(&quote).expect("Simulation failed");
+
+    println!("✅ On-chain simulation finished");
+    println!("   Compute units: {:?}", result.compute_units_consumed);
+    for log in &result.logs {
+        println!("   {}", log);
+    }
+
+    assert!(result.signature.is_none());
+    assert!(!result.logs.is_empty());
+}
+
 #[test]
 fn test_config_creation() {
     use config::CliArgs;
 
     let args = CliArgs {
         rpc_url: Some("https://custom.rpc.com".to_string()),
-        network: "mainnet-beta".to_string(),
+        network: Some("mainnet-beta".to_string()),
         token_in: None,
         token_out: None,
         amount: None,
         strategy: "single".to_string(),
         max_hops: 2,
+        max_impact_bps: None,
         dry_run: true,
         config: None,
         verbose: false,
+        output: "text".to_string(),
+        log_format: "pretty".to_string(),
+        keypair_path: None,
+        offline: false,
+        pools_file: None,
+        compare_dexes: false,
     };
 
     let config = Config::from_args(args).expect("Failed to create config");